@@ -20,9 +20,8 @@ fn main() -> Result<(), metis::Error> {
         8, 12, 14,
         9, 13,
     ];
-    let mut part = vec![0x00; 15];
-    Graph::new(1, 2, xadj, adjncy)?.part_recursive(&mut part)?;
-    println!("{:?}", part);
+    let (part, _edgecut) = Graph::new(1, 2, xadj, adjncy)?.part_recursive_owned()?;
+    println!("{:?}", part.labels());
 
     Ok(())
 }
@@ -0,0 +1,54 @@
+use metis::io::{read_bin, read_graph, write_bin, write_graph};
+use std::time::Instant;
+
+fn main() -> Result<(), metis::io::IoError> {
+    // A 1000x1000 grid.
+    let n = 1000;
+    let mut xadj = vec![0];
+    let mut adjncy = Vec::new();
+    for i in 0..n {
+        for j in 0..n {
+            let v = n * i + j;
+            if 0 < i {
+                adjncy.push(v - n);
+            }
+            if 0 < j {
+                adjncy.push(v - 1);
+            }
+            if j < n - 1 {
+                adjncy.push(v + 1);
+            }
+            if i < n - 1 {
+                adjncy.push(v + n);
+            }
+            xadj.push(adjncy.len() as metis::Idx);
+        }
+    }
+
+    let mut text = Vec::new();
+    write_graph(&mut text, &xadj, &adjncy, None, None, None, 1)?;
+    let now = Instant::now();
+    let data = read_graph(&text[..])?;
+    let text_time = now.elapsed();
+
+    let mut bin = Vec::new();
+    write_bin(&mut bin, &data)?;
+    let now = Instant::now();
+    assert_eq!(read_bin(&bin[..])?, data);
+    let bin_time = now.elapsed();
+
+    println!("{:>6} {:>10} {:>10}", "format", "bytes", "load (ms)");
+    for (format, bytes, time) in [
+        ("text", text.len(), text_time),
+        ("binary", bin.len(), bin_time),
+    ] {
+        println!(
+            "{:>6} {:>10} {:>10.1}",
+            format,
+            bytes,
+            time.as_secs_f64() * 1e3
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use metis::GraphBuilder;
+
+fn main() -> Result<(), metis::Error> {
+    // A 32x32 grid.
+    let n = 32;
+    let mut builder = GraphBuilder::new(n * n)?;
+    for i in 0..n {
+        for j in 0..n - 1 {
+            builder.add_edge(n * i + j, n * i + j + 1)?;
+            builder.add_edge(n * j + i, n * j + i + n)?;
+        }
+    }
+    let graph = builder.build(1, 2)?;
+
+    let sweep = graph.as_graph().sweep_nparts(2..=16)?;
+    println!("{:>6} {:>8} {:>8} {:>8}", "nparts", "edgecut", "min", "max");
+    for entry in sweep {
+        println!(
+            "{:>6} {:>8} {:>8} {:>8}",
+            entry.nparts, entry.edgecut, entry.min_part_weight, entry.max_part_weight,
+        );
+    }
+
+    Ok(())
+}
@@ -76,7 +76,6 @@ fn build_lib() {
             "blas.c",
             "cache.c",
             "csr.c",
-            "error.c",
             "evaluate.c",
             "fkvkselect.c",
             "fs.c",
@@ -126,6 +125,16 @@ fn build_lib() {
         build.define("MACOS", None);
     }
 
+    // Fatal errors inside METIS (gk_errexit) raise a signal that every METIS
+    // routine traps with gk_sigtrap/gk_sigcatch, so that they are returned as
+    // METIS_ERROR instead of terminating the process.  Failed assertions call
+    // abort(), whose SIGABRT is trapped the same way and reported as
+    // METIS_ERROR_MEMORY.  GKlib's error.c is built through errexit.c, which
+    // records the message of these errors for `metis_sys::take_error`.
+    let errexit = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("errexit.c");
+    println!("cargo:rerun-if-changed={}", errexit.display());
+    build.file(errexit);
+
     #[cfg(any(not(debug_assertions), feature = "force-optimize-vendor"))]
     build.define("NDEBUG", None).define("NDEBUG2", None);
 
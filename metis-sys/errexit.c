@@ -0,0 +1,38 @@
+/*
+ * Builds GKlib's error.c with a replacement for gk_errexit that records the
+ * message of fatal errors, so that the Rust bindings can return it instead of
+ * printing it on the standard error stream.
+ *
+ * METIS calls gk_errexit on fatal errors.  It raises a signal that the
+ * current METIS routine traps with gk_sigtrap/gk_sigcatch, and the routine
+ * returns METIS_ERROR or METIS_ERROR_MEMORY.
+ */
+
+#define gk_errexit metis_rs_vendored_gk_errexit
+#include "error.c"
+#undef gk_errexit
+
+static __thread char metis_rs_error[1024];
+
+void gk_errexit(int signum, char *f_str, ...)
+{
+  va_list argp;
+
+  va_start(argp, f_str);
+  vsnprintf(metis_rs_error, sizeof(metis_rs_error), f_str, argp);
+  va_end(argp);
+
+  if (gk_exit_on_error)
+    raise(signum);
+}
+
+/* Returns the message of the last fatal error raised on this thread, or an
+ * empty string, and forgets it. */
+const char *metis_rs_take_error(void)
+{
+  static __thread char message[sizeof(metis_rs_error)];
+
+  memcpy(message, metis_rs_error, sizeof(message));
+  metis_rs_error[0] = '\0';
+  return message;
+}
@@ -7,3 +7,30 @@ include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
 #[cfg(feature = "vendored")]
 include!("../gen/bindings.rs");
+
+#[cfg(feature = "vendored")]
+extern "C" {
+    fn metis_rs_take_error() -> *const std::os::raw::c_char;
+}
+
+/// Returns the message of the last fatal error raised by METIS on the calling
+/// thread, and forgets it.
+///
+/// METIS reports fatal errors (e.g. a contiguous partition requested on a
+/// disconnected graph) with `METIS_ERROR`, and prints their reason.  The
+/// vendored build records the reason instead, so that it can be retrieved
+/// here.  When linking to a METIS install, this always returns `None`.
+pub fn take_error() -> Option<String> {
+    #[cfg(feature = "vendored")]
+    {
+        // SAFETY: the returned string is nul-terminated and lives in a
+        // thread-local buffer, which is only overwritten by the next call.
+        let message = unsafe { std::ffi::CStr::from_ptr(metis_rs_take_error()) };
+        let message = message.to_string_lossy();
+        let message = message.trim_end();
+        if !message.is_empty() {
+            return Some(message.to_owned());
+        }
+    }
+    None
+}
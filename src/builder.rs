@@ -0,0 +1,406 @@
+//! Construction of graphs from lists of edges.
+//!
+//! See [`GraphBuilder`] for a usage example.
+
+use crate::csr;
+use crate::GraphOwned;
+use crate::Idx;
+use crate::NewGraphError;
+use crate::StructureErrorKind;
+use std::result::Result as StdResult;
+
+/// What [`GraphBuilder::build`] does with the weights of edges that have been
+/// added several times.
+///
+/// Edges `(u, v)` and `(v, u)` are the same edge.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::builder::DuplicatePolicy;
+/// use metis::{GraphBuilder, NewGraphError};
+///
+/// // A path with heavy ends, and a light middle edge given twice.
+/// let mut builder = GraphBuilder::new(4)?;
+/// builder
+///     .add_weighted_edge(0, 1, 10)?
+///     .add_weighted_edge(1, 2, 2)?
+///     .add_weighted_edge(2, 1, 3)?
+///     .add_weighted_edge(2, 3, 10)?;
+///
+/// let mut edgecut = |policy| -> Result<_, metis::Error> {
+///     let mut part = [0; 4];
+///     let graph = builder.duplicate_policy(policy).build(1, 2)?;
+///     Ok(graph.part_recursive(&mut part)?)
+/// };
+/// assert_eq!(edgecut(DuplicatePolicy::Sum)?, 5);
+/// assert_eq!(edgecut(DuplicatePolicy::Max)?, 3);
+/// assert_eq!(edgecut(DuplicatePolicy::KeepFirst)?, 2);
+/// assert!(matches!(
+///     builder.duplicate_policy(DuplicatePolicy::Error).build(1, 2),
+///     Err(NewGraphError::DuplicateEdge { u: 1, v: 2 }),
+/// ));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Sum the weights, as if the graph was the sum of its edges.
+    #[default]
+    Sum,
+
+    /// Keep the largest weight.
+    Max,
+
+    /// Keep the weight of the edge that was added first.
+    KeepFirst,
+
+    /// Fail with [`NewGraphError::DuplicateEdge`].
+    Error,
+}
+
+/// Builder structure to make a graph out of a list of weighted edges.
+///
+/// Edges are undirected: adding the edge `(u, v)` also adds `(v, u)`, so that
+/// the resulting graph meets the requirements of METIS.  When building the
+/// graph, self-loops (edges from a vertex to itself) are dropped, unless
+/// configured otherwise with [`GraphBuilder::drop_self_loops`], and the weights
+/// of duplicate edges are merged according to
+/// [`GraphBuilder::duplicate_policy`] (summed by default).
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::GraphBuilder;
+///
+/// // Triplets of a symmetric matrix, lower triangle only.  The (2, 1) entry
+/// // is split in two.
+/// // 2 1 . .
+/// // 1 2 3 .
+/// // . 3 2 1
+/// // . . 1 2
+/// let triplets = [
+///     (0, 0, 2),
+///     (1, 0, 1),
+///     (1, 1, 2),
+///     (2, 1, 1),
+///     (2, 1, 2),
+///     (2, 2, 2),
+///     (3, 2, 1),
+///     (3, 3, 2),
+/// ];
+/// let graph = GraphBuilder::from_triplets(4, triplets, |x| x)?.build(1, 2)?;
+/// assert_eq!(graph.xadj(), &[0, 1, 3, 5, 6]);
+/// assert_eq!(graph.adjncy(), &[1, 0, 2, 1, 3, 2]);
+/// assert_eq!(graph.adjwgt(), Some(&[1, 1, 3, 3, 1, 1][..]));
+///
+/// // Same as giving the CSR by hand.
+/// let xadj = [0, 1, 3, 5, 6];
+/// let adjncy = [1, 0, 2, 1, 3, 2];
+/// let adjwgt = [1, 1, 3, 3, 1, 1];
+/// let mut part1 = [0; 4];
+/// let mut part2 = [0; 4];
+/// graph.part_recursive(&mut part1)?;
+/// metis::Graph::new(1, 2, &xadj, &adjncy)?
+///     .set_adjwgt(&adjwgt)
+///     .part_recursive(&mut part2)?;
+/// assert_eq!(part1, part2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphBuilder {
+    /// The number of vertices.
+    nvtxs: Idx,
+
+    /// The weighted edges, as given by the user.
+    edges: Vec<(Idx, Idx, Idx)>,
+
+    /// Whether self-loops are removed when building the graph.
+    drop_self_loops: bool,
+
+    /// What to do with duplicate edges.
+    duplicate_policy: DuplicatePolicy,
+}
+
+impl GraphBuilder {
+    /// Creates a new [`GraphBuilder`] for a graph of `nvtxs` vertices and no
+    /// edges.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `nvtxs` is negative.
+    pub fn new(nvtxs: Idx) -> StdResult<GraphBuilder, NewGraphError> {
+        if nvtxs < 0 {
+            return Err(NewGraphError::msg(
+                StructureErrorKind::OutOfBounds,
+                "the number of vertices is negative",
+            ));
+        }
+        Ok(GraphBuilder {
+            nvtxs,
+            edges: Vec::new(),
+            drop_self_loops: true,
+            duplicate_policy: DuplicatePolicy::default(),
+        })
+    }
+
+    /// Creates a new [`GraphBuilder`] for a graph of `nvtxs` vertices, from
+    /// `(u, v, value)` triplets.
+    ///
+    /// This is typically the COO representation of a sparse matrix `A`, where
+    /// the triplet `(u, v, x)` is the entry `A[u][v] = x`.  Diagonal entries
+    /// are dropped, and the value of the other entries is turned into an edge
+    /// weight by `weight`.  METIS needs strictly positive weights, so values
+    /// that are zero or negative, like the off-diagonal entries of a
+    /// Laplacian, must be mapped to positive weights, otherwise
+    /// [`GraphBuilder::build`] fails.  For the sparsity pattern alone, give a
+    /// closure that returns one.
+    ///
+    /// Weights of entries given several times are summed.  The matrix is then
+    /// made symmetric where needed: `(u, v)` and `(v, u)` become a single
+    /// undirected edge, whose weight is the one of the entry that is present,
+    /// or the largest of the two when both are.  The triplets of a symmetric
+    /// matrix can thus be given for one of its triangles or for the whole
+    /// matrix.
+    ///
+    /// This runs in `O(E log E)` time, where `E` is the number of triplets.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `nvtxs` is negative, if some
+    /// vertices are not within zero and `nvtxs`, or if summed weights overflow
+    /// [`Idx`].  Weights are checked to be strictly positive by
+    /// [`GraphBuilder::build`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::GraphBuilder;
+    ///
+    /// // The whole matrix of the example of `GraphBuilder`.
+    /// // 2 1 . .
+    /// // 1 2 3 .
+    /// // . 3 2 1
+    /// // . . 1 2
+    /// let triplets = [
+    ///     (0, 0, 2),
+    ///     (0, 1, 1),
+    ///     (1, 0, 1),
+    ///     (1, 1, 2),
+    ///     (1, 2, 3),
+    ///     (2, 1, 3),
+    ///     (2, 2, 2),
+    ///     (2, 3, 1),
+    ///     (3, 2, 1),
+    ///     (3, 3, 2),
+    /// ];
+    /// let graph = GraphBuilder::from_triplets(4, triplets, |x| x)?.build(1, 2)?;
+    /// assert_eq!(graph.xadj(), &[0, 1, 3, 5, 6]);
+    /// assert_eq!(graph.adjncy(), &[1, 0, 2, 1, 3, 2]);
+    /// assert_eq!(graph.adjwgt(), Some(&[1, 1, 3, 3, 1, 1][..]));
+    ///
+    /// // The Laplacian of the path 0 - 1 - 2.
+    /// let laplacian = [
+    ///     (0, 0, 1.0),
+    ///     (0, 1, -1.0),
+    ///     (1, 0, -1.0),
+    ///     (1, 1, 2.0),
+    ///     (1, 2, -1.0),
+    ///     (2, 1, -1.0),
+    ///     (2, 2, 1.0),
+    /// ];
+    /// let builder = GraphBuilder::from_triplets(3, laplacian, |x: f64| -x as metis::Idx)?;
+    /// assert_eq!(builder.build(1, 2)?.adjwgt(), Some(&[1, 1, 1, 1][..]));
+    ///
+    /// // Values used as they are fail to build.
+    /// let builder = GraphBuilder::from_triplets(3, laplacian, |x: f64| x as metis::Idx)?;
+    /// assert!(builder.build(1, 2).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_triplets<I, T, F>(
+        nvtxs: Idx,
+        triplets: I,
+        mut weight: F,
+    ) -> StdResult<GraphBuilder, NewGraphError>
+    where
+        I: IntoIterator<Item = (Idx, Idx, T)>,
+        F: FnMut(T) -> Idx,
+    {
+        let mut builder = GraphBuilder::new(nvtxs)?;
+        let mut entries = Vec::new();
+        for (u, v, x) in triplets {
+            builder.check_vertices(u, v)?;
+            if u != v {
+                entries.push((u, v, weight(x)));
+            }
+        }
+        let (xadj, adjncy, adjwgt) = csr::from_arcs(nvtxs, entries, |_, a, b| {
+            a.checked_add(b).ok_or(NewGraphError::TooLarge)
+        })?;
+
+        // Rows are sorted, so the reverse of an entry is found by bisection.
+        let row = |u: Idx| xadj[u as usize] as usize..xadj[u as usize + 1] as usize;
+        for u in 0..nvtxs {
+            for e in row(u) {
+                let (v, w) = (adjncy[e], adjwgt[e]);
+                let reverse = row(v).start;
+                match adjncy[row(v)].binary_search(&u) {
+                    Err(_) => builder.edges.push((u, v, w)),
+                    Ok(i) if u < v => builder.edges.push((u, v, Idx::max(w, adjwgt[reverse + i]))),
+                    Ok(_) => {}
+                }
+            }
+        }
+        Ok(builder)
+    }
+
+    /// Adds an undirected edge between `u` and `v`, of weight one.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `u` or `v` is not within zero and the
+    /// number of vertices.
+    pub fn add_edge(&mut self, u: Idx, v: Idx) -> StdResult<&mut GraphBuilder, NewGraphError> {
+        self.add_weighted_edge(u, v, 1)
+    }
+
+    /// Adds an undirected edge between `u` and `v`, of weight `w`.
+    ///
+    /// Both directions of the edge get the same weight in the built graph.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `u` or `v` is not within zero and the
+    /// number of vertices.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::GraphBuilder;
+    ///
+    /// // A square with one heavy side.
+    /// let mut builder = GraphBuilder::new(4)?;
+    /// builder
+    ///     .add_weighted_edge(0, 1, 10)?
+    ///     .add_edge(1, 2)?
+    ///     .add_edge(2, 3)?
+    ///     .add_edge(3, 0)?;
+    /// let graph = builder.build(1, 2)?;
+    /// assert_eq!(graph.adjwgt(), Some(&[10, 1, 10, 1, 1, 1, 1, 1][..]));
+    ///
+    /// // The heavy edge is not cut.
+    /// let mut part = [0; 4];
+    /// let edgecut = graph.part_recursive(&mut part)?;
+    /// assert_eq!(edgecut, 2);
+    /// assert_eq!(part[0], part[1]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_weighted_edge(
+        &mut self,
+        u: Idx,
+        v: Idx,
+        w: Idx,
+    ) -> StdResult<&mut GraphBuilder, NewGraphError> {
+        self.check_vertices(u, v)?;
+        self.edges.push((u, v, w));
+        Ok(self)
+    }
+
+    fn check_vertices(&self, u: Idx, v: Idx) -> StdResult<(), NewGraphError> {
+        if u < 0 || self.nvtxs <= u || v < 0 || self.nvtxs <= v {
+            return Err(NewGraphError::msg(
+                StructureErrorKind::OutOfBounds,
+                "some vertices of the edge list are out of bounds",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Sets whether self-loops are removed when building the graph.
+    ///
+    /// METIS does not allow self-loops.  By default, they are silently removed
+    /// along with their weight.  Otherwise, [`GraphBuilder::build`] returns
+    /// [`NewGraphError::SelfLoop`] when there are some.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::{GraphBuilder, NewGraphError};
+    ///
+    /// let mut builder = GraphBuilder::new(2)?;
+    /// builder.add_weighted_edge(0, 1, 2)?.add_weighted_edge(1, 1, 5)?;
+    ///
+    /// let graph = builder.build(1, 2)?;
+    /// assert_eq!(graph.adjncy(), &[1, 0]);
+    /// assert_eq!(graph.adjwgt(), Some(&[2, 2][..]));
+    ///
+    /// let err = builder.drop_self_loops(false).build(1, 2).unwrap_err();
+    /// assert!(matches!(err, NewGraphError::SelfLoop { vertex: 1 }));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn drop_self_loops(&mut self, drop_self_loops: bool) -> &mut GraphBuilder {
+        self.drop_self_loops = drop_self_loops;
+        self
+    }
+
+    /// Sets what happens to the weights of duplicate edges, see
+    /// [`DuplicatePolicy`].
+    pub fn duplicate_policy(&mut self, duplicate_policy: DuplicatePolicy) -> &mut GraphBuilder {
+        self.duplicate_policy = duplicate_policy;
+        self
+    }
+
+    /// Builds the graph.
+    ///
+    /// See [`crate::Graph::new`] for the meaning of `ncon` and `nparts`.  The
+    /// edge weights are installed with [`GraphOwned::try_set_adjwgt`], and
+    /// adjacency lists are sorted.  The builder is left as is, so that the
+    /// graph can be built again, e.g. after adding edges or changing the
+    /// settings of the builder.
+    ///
+    /// This runs in `O(E log E)` time, where `E` is the number of edges.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `ncon` or `nparts` is not strictly
+    /// greater than zero, if the graph is too large, if it has self-loops that
+    /// are not dropped, if it has duplicate edges and the policy is
+    /// [`DuplicatePolicy::Error`], if summed edge weights overflow [`Idx`], or
+    /// if some merged edge weights are not strictly positive.
+    pub fn build(&self, ncon: Idx, nparts: Idx) -> StdResult<GraphOwned, NewGraphError> {
+        let mut arcs = Vec::with_capacity(2 * self.edges.len());
+        for &(u, v, w) in &self.edges {
+            if u != v {
+                arcs.push((u, v, w));
+                arcs.push((v, u, w));
+            } else if !self.drop_self_loops {
+                return Err(NewGraphError::SelfLoop { vertex: u });
+            }
+        }
+        let policy = self.duplicate_policy;
+        let (xadj, adjncy, adjwgt) = csr::from_arcs(self.nvtxs, arcs, |(u, v), a, b| {
+            Ok(match policy {
+                DuplicatePolicy::Sum => a.checked_add(b).ok_or(NewGraphError::TooLarge)?,
+                DuplicatePolicy::Max => Idx::max(a, b),
+                DuplicatePolicy::KeepFirst => a,
+                DuplicatePolicy::Error => {
+                    return Err(NewGraphError::DuplicateEdge {
+                        u: Idx::min(u, v),
+                        v: Idx::max(u, v),
+                    })
+                }
+            })
+        })?;
+
+        GraphOwned::new(ncon, nparts, xadj, adjncy)?.try_set_adjwgt(adjwgt)
+    }
+}
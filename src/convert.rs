@@ -0,0 +1,92 @@
+//! Conversion of index arrays to [`Idx`].
+//!
+//! Graphs are often built with `usize` or `u32` indices, while METIS expects
+//! [`Idx`], whose width depends on how METIS was built (see
+//! [`crate::IDX_WIDTH`]).  The functions of this module convert such arrays
+//! in one pass, and report values that do not fit.
+
+use crate::Idx;
+use std::convert::TryInto;
+use std::fmt;
+use std::result::Result as StdResult;
+
+/// Error raised when a value does not fit in [`Idx`].
+///
+/// Unlike [`crate::NewGraphError`], this error does not mean the graph is
+/// malformed, but that it is too large for the linked METIS.  Building METIS
+/// with 64-bit indices might help.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvertError {
+    index: usize,
+    value: String,
+}
+
+impl ConvertError {
+    /// The position of the first value that does not fit in [`Idx`].
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The first value that does not fit in [`Idx`], as text.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "value {} at index {} does not fit in a {}-bit Idx",
+            self.value,
+            self.index,
+            crate::IDX_WIDTH,
+        )
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+/// Converts a slice of integers to a vector of [`Idx`].
+///
+/// # Errors
+///
+/// This function returns an error with the first value that does not fit in
+/// [`Idx`], and its position in `values`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::convert::to_idx_vec;
+///
+/// // 0 - 1 - 2
+/// let xadj: Vec<usize> = vec![0, 1, 3, 4];
+/// let adjncy: Vec<usize> = vec![1, 0, 2, 1];
+///
+/// let xadj = to_idx_vec(&xadj)?;
+/// let adjncy = to_idx_vec(&adjncy)?;
+/// let mut part = [0; 3];
+/// metis::Graph::new(1, 2, &xadj, &adjncy)?.part_kway(&mut part)?;
+///
+/// let err = to_idx_vec(&[0, 1, u64::MAX]).unwrap_err();
+/// assert_eq!(err.index(), 2);
+/// assert_eq!(err.value(), u64::MAX.to_string());
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_idx_vec<T>(values: &[T]) -> StdResult<Vec<Idx>, ConvertError>
+where
+    T: Copy + TryInto<Idx> + fmt::Display,
+{
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            (*value).try_into().map_err(|_| ConvertError {
+                index,
+                value: value.to_string(),
+            })
+        })
+        .collect()
+}
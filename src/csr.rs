@@ -0,0 +1,497 @@
+//! Utilities to manipulate graphs in CSR format.
+//!
+//! Functions in this module take and return graphs in the format described in
+//! [`crate::Graph::new`].  Unless stated otherwise, input graphs may have
+//! self-loops.
+
+use crate::check_graph_structure;
+use crate::order;
+use crate::Idx;
+use crate::NewGraphError;
+use crate::StructureErrorKind;
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::ops::Range;
+use std::result::Result as StdResult;
+
+/// How to combine the weights of edges that appear several times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightMerge {
+    /// Sum the weights.
+    Sum,
+
+    /// Keep the largest weight.
+    Max,
+
+    /// Keep the smallest weight.
+    Min,
+}
+
+impl WeightMerge {
+    pub(crate) fn apply(self, a: Idx, b: Idx) -> Idx {
+        match self {
+            WeightMerge::Sum => a + b,
+            WeightMerge::Max => Idx::max(a, b),
+            WeightMerge::Min => Idx::min(a, b),
+        }
+    }
+}
+
+/// Relabels the vertices of a graph.
+///
+/// `perm` maps new vertex indices to old ones, as in
+/// [`crate::Permutation::perm`]: vertex `i` of the resulting graph is vertex
+/// `perm[i]` of the input graph.  Adjacency lists of the result are sorted, and
+/// edge weights, if any, are carried along.
+///
+/// Returns the `xadj`, `adjncy` and `adjwgt` arrays of the relabeled graph.
+///
+/// # Errors
+///
+/// This function returns an error if:
+///
+/// - the graph does not uphold the invariants listed in the "Errors" section of
+///   [`crate::Graph::new`],
+/// - `adjwgt` does not have the same length as `adjncy`,
+/// - `perm` is not a permutation of `0..nvtxs`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::NewGraphError> {
+/// // 0 - 1 - 2
+/// let xadj = [0, 1, 3, 4];
+/// let adjncy = [1, 0, 2, 1];
+/// let adjwgt = [5, 5, 7, 7];
+///
+/// // 1 - 2 - 0
+/// let perm = [2, 0, 1];
+/// let (new_xadj, new_adjncy, new_adjwgt) =
+///     metis::csr::permute(&xadj, &adjncy, Some(&adjwgt), &perm)?;
+/// assert_eq!(new_xadj, [0, 1, 2, 4]);
+/// assert_eq!(new_adjncy, [2, 2, 0, 1]);
+/// assert_eq!(new_adjwgt.as_deref(), Some(&[7, 5, 7, 5][..]));
+///
+/// // Relabeling with the inverse permutation gives back the original graph.
+/// let iperm = [1, 2, 0];
+/// let (xadj2, adjncy2, adjwgt2) =
+///     metis::csr::permute(&new_xadj, &new_adjncy, new_adjwgt.as_deref(), &iperm)?;
+/// assert_eq!(xadj2, xadj);
+/// assert_eq!(adjncy2, adjncy);
+/// assert_eq!(adjwgt2.unwrap(), adjwgt);
+/// # Ok(())
+/// # }
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn permute(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    adjwgt: Option<&[Idx]>,
+    perm: &[Idx],
+) -> StdResult<(Vec<Idx>, Vec<Idx>, Option<Vec<Idx>>), NewGraphError> {
+    let nvtxs = check_graph_structure(xadj, adjncy)?;
+    if let Some(adjwgt) = adjwgt {
+        if adjwgt.len() != adjncy.len() {
+            return Err(NewGraphError::msg(
+                StructureErrorKind::LengthMismatch,
+                "length mismatch between adjacency and edge weight lists",
+            ));
+        }
+    }
+    if perm.len() != nvtxs as usize {
+        return Err(NewGraphError::msg(
+            StructureErrorKind::LengthMismatch,
+            "length mismatch between permutation and index list",
+        ));
+    }
+    let iperm = order::invert(perm).map_err(|_| {
+        NewGraphError::msg(
+            StructureErrorKind::NotAPermutation,
+            "perm is not a permutation of the vertices",
+        )
+    })?;
+
+    let mut new_xadj = Vec::with_capacity(xadj.len());
+    let mut new_adjncy = Vec::with_capacity(adjncy.len());
+    let mut new_adjwgt = adjwgt.map(|_| Vec::with_capacity(adjncy.len()));
+    let mut row = Vec::new();
+    new_xadj.push(0);
+    for old in perm {
+        let start = xadj[*old as usize] as usize;
+        let end = xadj[*old as usize + 1] as usize;
+
+        row.clear();
+        row.extend((start..end).map(|e| {
+            let w = adjwgt.map_or(0, |adjwgt| adjwgt[e]);
+            (iperm[adjncy[e] as usize], w)
+        }));
+        row.sort_by_key(|(v, _)| *v);
+
+        new_adjncy.extend(row.iter().map(|(v, _)| *v));
+        if let Some(new_adjwgt) = &mut new_adjwgt {
+            new_adjwgt.extend(row.iter().map(|(_, w)| *w));
+        }
+        new_xadj.push(new_adjncy.len() as Idx);
+    }
+
+    Ok((new_xadj, new_adjncy, new_adjwgt))
+}
+
+/// Whether the graph is undirected, i.e. whether for every edge `(u, v)`, the
+/// edge `(v, u)` is also present, as many times.
+///
+/// This runs in `O(E log D)` time and uses `O(E)` memory, where `E` is the
+/// number of edges and `D` the maximum degree.
+///
+/// # Errors
+///
+/// This function returns an error if the graph does not uphold the invariants
+/// listed in the "Errors" section of [`crate::Graph::new`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::NewGraphError> {
+/// // 0 - 1 - 2
+/// assert!(metis::csr::is_symmetric(&[0, 1, 3, 4], &[1, 0, 2, 1])?);
+///
+/// // 0 -> 1 - 2
+/// assert!(!metis::csr::is_symmetric(&[0, 1, 2, 3], &[1, 2, 1])?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn is_symmetric(xadj: &[Idx], adjncy: &[Idx]) -> StdResult<bool, NewGraphError> {
+    check_graph_structure(xadj, adjncy)?;
+    let (txadj, tadjncy) = transpose(xadj, adjncy);
+    if txadj != xadj {
+        return Ok(false);
+    }
+
+    // The transposition lists neighbors in increasing order.
+    let mut row = Vec::new();
+    for (w, tw) in xadj.windows(2).zip(txadj.windows(2)) {
+        row.clear();
+        row.extend_from_slice(&adjncy[w[0] as usize..w[1] as usize]);
+        row.sort_unstable();
+        if row != tadjncy[tw[0] as usize..tw[1] as usize] {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Computes the CSR of the transposed adjacency matrix, with sorted rows.
+///
+/// The graph must uphold the invariants of [`crate::Graph::new`].
+fn transpose(xadj: &[Idx], adjncy: &[Idx]) -> (Vec<Idx>, Vec<Idx>) {
+    let mut txadj = vec![0; xadj.len()];
+    for v in adjncy {
+        txadj[*v as usize + 1] += 1;
+    }
+    for i in 1..txadj.len() {
+        txadj[i] += txadj[i - 1];
+    }
+
+    let mut next = txadj.clone();
+    let mut tadjncy = vec![0; adjncy.len()];
+    for (u, w) in xadj.windows(2).enumerate() {
+        for v in &adjncy[w[0] as usize..w[1] as usize] {
+            tadjncy[next[*v as usize] as usize] = u as Idx;
+            next[*v as usize] += 1;
+        }
+    }
+
+    (txadj, tadjncy)
+}
+
+/// Makes a graph undirected, by adding the reverse of every edge.
+///
+/// The result is the union of the input adjacency matrix `A` and its transpose
+/// `Aᵀ`.  Self-loops are removed, and the weights of edges that appear several
+/// times, for example both as `(u, v)` and `(v, u)`, are combined according
+/// to `merge`.  Note that summing weights doubles the weights of a graph that
+/// is already undirected.  Adjacency lists of the result are sorted.
+///
+/// Returns the `xadj`, `adjncy` and `adjwgt` arrays of the undirected graph,
+/// which uphold the invariants of [`crate::Graph::new`].  This runs in
+/// `O(E log E)` time, where `E` is the number of edges.
+///
+/// # Errors
+///
+/// This function returns an error if:
+///
+/// - the graph does not uphold the invariants listed in the "Errors" section of
+///   [`crate::Graph::new`],
+/// - `adjwgt` does not have the same length as `adjncy`,
+/// - the resulting graph is too large, or summed weights overflow [`Idx`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::csr::WeightMerge;
+///
+/// // 0 -> 1 -> 2 -> 0, and 1 -> 0
+/// let xadj = [0, 1, 3, 4];
+/// let adjncy = [1, 0, 2, 0];
+/// let adjwgt = [1, 2, 3, 4];
+///
+/// let (xadj, adjncy, adjwgt) =
+///     metis::csr::symmetrize(&xadj, &adjncy, Some(&adjwgt), WeightMerge::Max)?;
+/// assert!(metis::csr::is_symmetric(&xadj, &adjncy)?);
+/// assert_eq!(xadj, [0, 2, 4, 6]);
+/// assert_eq!(adjncy, [1, 2, 0, 2, 0, 1]);
+/// assert_eq!(adjwgt.as_deref(), Some(&[2, 4, 2, 3, 4, 3][..]));
+///
+/// let mut part = [0; 3];
+/// metis::Graph::new(1, 2, &xadj, &adjncy)?
+///     .set_adjwgt(adjwgt.as_deref().unwrap())
+///     .part_kway(&mut part)?;
+///
+/// // Summed weights must fit in an `Idx`.
+/// let adjwgt = [1, metis::Idx::MAX, 3, 4];
+/// assert!(matches!(
+///     metis::csr::symmetrize(&[0, 1, 3, 4], &[1, 0, 2, 0], Some(&adjwgt), WeightMerge::Sum),
+///     Err(metis::NewGraphError::TooLarge),
+/// ));
+/// # Ok(())
+/// # }
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn symmetrize(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    adjwgt: Option<&[Idx]>,
+    merge: WeightMerge,
+) -> StdResult<(Vec<Idx>, Vec<Idx>, Option<Vec<Idx>>), NewGraphError> {
+    let nvtxs = check_graph_structure(xadj, adjncy)?;
+    if let Some(adjwgt) = adjwgt {
+        if adjwgt.len() != adjncy.len() {
+            return Err(NewGraphError::msg(
+                StructureErrorKind::LengthMismatch,
+                "length mismatch between adjacency and edge weight lists",
+            ));
+        }
+    }
+
+    let mut arcs = Vec::with_capacity(2 * adjncy.len());
+    for (u, w) in xadj.windows(2).enumerate() {
+        let u = u as Idx;
+        for e in w[0] as usize..w[1] as usize {
+            let v = adjncy[e];
+            let wgt = adjwgt.map_or(1, |adjwgt| adjwgt[e]);
+            if u != v {
+                arcs.push((u, v, wgt));
+                arcs.push((v, u, wgt));
+            }
+        }
+    }
+
+    let (xadj, adjncy, new_adjwgt) = from_arcs(nvtxs, arcs, |_, a, b| match merge {
+        WeightMerge::Sum => a.checked_add(b).ok_or(NewGraphError::TooLarge),
+        _ => Ok(merge.apply(a, b)),
+    })?;
+    Ok((xadj, adjncy, adjwgt.map(|_| new_adjwgt)))
+}
+
+/// Builds a CSR graph out of a list of `(u, v, weight)` arcs, whose vertices
+/// are within zero and `nvtxs`.
+///
+/// Adjacency lists of the result are sorted, and the weights of duplicate arcs
+/// are combined by calling `merge(arc, previous, weight)` in the order in which
+/// they appear in `arcs`.
+#[allow(clippy::type_complexity)]
+pub(crate) fn from_arcs<F>(
+    nvtxs: Idx,
+    mut arcs: Vec<(Idx, Idx, Idx)>,
+    mut merge: F,
+) -> StdResult<(Vec<Idx>, Vec<Idx>, Vec<Idx>), NewGraphError>
+where
+    F: FnMut((Idx, Idx), Idx, Idx) -> StdResult<Idx, NewGraphError>,
+{
+    // Stable sort, so that duplicates are merged in order.
+    arcs.sort_by_key(|(u, v, _)| (*u, *v));
+
+    let mut xadj = vec![0; nvtxs as usize + 1];
+    let mut adjncy: Vec<Idx> = Vec::with_capacity(arcs.len());
+    let mut adjwgt: Vec<Idx> = Vec::with_capacity(arcs.len());
+    let mut prev = None;
+    for (u, v, w) in arcs {
+        if prev == Some((u, v)) {
+            let last = adjwgt.last_mut().unwrap();
+            *last = merge((u, v), *last, w)?;
+            continue;
+        }
+        prev = Some((u, v));
+        xadj[u as usize + 1] += 1;
+        adjncy.push(v);
+        adjwgt.push(w);
+    }
+    let _ = Idx::try_from(adjncy.len()).map_err(|_| NewGraphError::TooLarge)?;
+    for i in 1..xadj.len() {
+        xadj[i] += xadj[i - 1];
+    }
+
+    Ok((xadj, adjncy, adjwgt))
+}
+
+/// Sorts adjacency lists and merges duplicate edges, in place.
+///
+/// The weights of duplicate edges are summed.  When duplicates are found,
+/// `adjncy` and `adjwgt` shrink, and `xadj` is updated accordingly.
+///
+/// This runs in `O(E log D)` time, where `E` is the number of edges and `D`
+/// the maximum degree, and only allocates a buffer of length `D`.
+///
+/// # Errors
+///
+/// This function returns an error, and leaves the arrays untouched, if:
+///
+/// - the graph does not uphold the invariants listed in the "Errors" section of
+///   [`crate::Graph::new`],
+/// - `adjwgt` does not have the same length as `adjncy`,
+/// - the sum of the weights of duplicate edges does not fit in an [`Idx`]
+///   ([`NewGraphError::TooLarge`]).
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::NewGraphError> {
+/// let mut xadj = vec![0, 3, 4, 6];
+/// let mut adjncy = vec![2, 1, 2, 0, 0, 0];
+/// let mut adjwgt = vec![1, 2, 3, 2, 1, 3];
+///
+/// metis::csr::canonicalize(&mut xadj, &mut adjncy, Some(&mut adjwgt))?;
+/// assert_eq!(xadj, [0, 2, 3, 4]);
+/// assert_eq!(adjncy, [1, 2, 0, 0]);
+/// assert_eq!(adjwgt, [2, 4, 2, 4]);
+///
+/// // The weights of the duplicate edges overflow, nothing is written.
+/// let mut adjncy = vec![1, 1, 0, 0];
+/// let mut adjwgt = vec![metis::Idx::MAX, 1, metis::Idx::MAX, 1];
+/// assert!(matches!(
+///     metis::csr::canonicalize(&mut [0, 2, 4], &mut adjncy, Some(&mut adjwgt)),
+///     Err(metis::NewGraphError::TooLarge),
+/// ));
+/// assert_eq!(adjncy, [1, 1, 0, 0]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn canonicalize(
+    xadj: &mut [Idx],
+    adjncy: &mut Vec<Idx>,
+    mut adjwgt: Option<&mut Vec<Idx>>,
+) -> StdResult<(), NewGraphError> {
+    check_graph_structure(xadj, adjncy)?;
+    if let Some(adjwgt) = &adjwgt {
+        if adjwgt.len() != adjncy.len() {
+            return Err(NewGraphError::msg(
+                StructureErrorKind::LengthMismatch,
+                "length mismatch between adjacency and edge weight lists",
+            ));
+        }
+    }
+
+    // Fills `row` with the sorted neighbors of a vertex and their weights.
+    fn fill_row(
+        row: &mut Vec<(Idx, Idx)>,
+        adjncy: &[Idx],
+        adjwgt: Option<&[Idx]>,
+        edges: Range<usize>,
+    ) {
+        row.clear();
+        row.extend(edges.map(|e| (adjncy[e], adjwgt.map_or(0, |adjwgt| adjwgt[e]))));
+        row.sort_unstable_by_key(|(v, _)| *v);
+    }
+
+    let mut row = Vec::new();
+
+    // Merged weights are checked before anything is written, so that the
+    // arrays are left untouched if one of them overflows.
+    if let Some(adjwgt) = adjwgt.as_deref().map(Vec::as_slice) {
+        for w in xadj.windows(2) {
+            fill_row(&mut row, adjncy, Some(adjwgt), w[0] as usize..w[1] as usize);
+            for dups in row.chunk_by(|(u, _), (v, _)| u == v) {
+                dups.iter()
+                    .try_fold(0, |sum: Idx, (_, w)| sum.checked_add(*w))
+                    .ok_or(NewGraphError::TooLarge)?;
+            }
+        }
+    }
+
+    let mut write = 0;
+    let mut start = 0;
+    for x in xadj.iter_mut().skip(1) {
+        let end = *x as usize;
+        fill_row(
+            &mut row,
+            adjncy,
+            adjwgt.as_deref().map(Vec::as_slice),
+            start..end,
+        );
+
+        let mut prev = None;
+        for (v, w) in &row {
+            if prev == Some(*v) {
+                if let Some(adjwgt) = &mut adjwgt {
+                    adjwgt[write - 1] += w;
+                }
+                continue;
+            }
+            prev = Some(*v);
+            adjncy[write] = *v;
+            if let Some(adjwgt) = &mut adjwgt {
+                adjwgt[write] = *w;
+            }
+            write += 1;
+        }
+
+        start = end;
+        *x = write as Idx;
+    }
+
+    adjncy.truncate(write);
+    if let Some(adjwgt) = adjwgt {
+        adjwgt.truncate(write);
+    }
+    Ok(())
+}
+
+/// Checks that the graph has no duplicate edges and that the reverse `(v, u)`
+/// of every edge `(u, v)` is in the graph.
+///
+/// The graph must uphold the invariants of [`crate::Graph::new`].
+pub(crate) fn check_undirected(xadj: &[Idx], adjncy: &[Idx]) -> StdResult<(), NewGraphError> {
+    let rows = || xadj.windows(2).map(|w| w[0] as usize..w[1] as usize);
+
+    let is_sorted = |row: &[Idx]| row.windows(2).all(|w| w[0] <= w[1]);
+    let sorted_adjncy = if rows().all(|r| is_sorted(&adjncy[r])) {
+        Cow::Borrowed(adjncy)
+    } else {
+        let mut sorted_adjncy = adjncy.to_vec();
+        for r in rows() {
+            sorted_adjncy[r].sort_unstable();
+        }
+        Cow::Owned(sorted_adjncy)
+    };
+
+    for (u, r) in rows().enumerate() {
+        if let Some(w) = sorted_adjncy[r].windows(2).find(|w| w[0] == w[1]) {
+            return Err(NewGraphError::DuplicateEdge {
+                u: u as Idx,
+                v: w[0],
+            });
+        }
+    }
+    for (u, r) in rows().enumerate() {
+        let u = u as Idx;
+        for v in &adjncy[r] {
+            let w = &xadj[*v as usize..*v as usize + 2];
+            let reverse_row = &sorted_adjncy[w[0] as usize..w[1] as usize];
+            if reverse_row.binary_search(&u).is_err() {
+                return Err(NewGraphError::NotSymmetric { u, v: *v });
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,149 @@
+//! Tools to investigate the behavior of METIS on a given input.
+//!
+//! See [`check_reproducible`] for a usage example.
+
+use crate::Error;
+use crate::GraphOwned;
+use crate::Idx;
+use crate::Method;
+use crate::Result;
+
+/// Whether several partitions of the same input are identical.
+///
+/// Returned by [`check_reproducible`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReproReport {
+    runs: usize,
+    first_difference: Option<usize>,
+    edgecuts: Vec<Idx>,
+}
+
+impl ReproReport {
+    /// Compares the partitions of several runs, given as the part of each
+    /// vertex and the edge-cut.
+    ///
+    /// Runs are compared to the first one.  This is useful to compare runs
+    /// that [`check_reproducible`] cannot make, for example with other
+    /// routines or other seeds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use metis::diagnostics::ReproReport;
+    ///
+    /// let report = ReproReport::from_runs([(vec![0, 0, 1], 1), (vec![0, 1, 1], 1)]);
+    /// assert!(!report.is_reproducible());
+    /// assert_eq!(report.first_difference(), Some(1));
+    /// assert_eq!(report.edgecuts(), [1]);
+    /// ```
+    pub fn from_runs<I>(runs: I) -> ReproReport
+    where
+        I: IntoIterator<Item = (Vec<Idx>, Idx)>,
+    {
+        let mut report = ReproReport {
+            runs: 0,
+            first_difference: None,
+            edgecuts: Vec::new(),
+        };
+        let mut reference: Option<Vec<Idx>> = None;
+        for (part, edgecut) in runs {
+            report.runs += 1;
+            if let Err(i) = report.edgecuts.binary_search(&edgecut) {
+                report.edgecuts.insert(i, edgecut);
+            }
+            let reference = reference.get_or_insert_with(|| part.clone());
+            let mut difference = reference.iter().zip(&part).position(|(p, q)| p != q);
+            if difference.is_none() && reference.len() != part.len() {
+                // Parts of different lengths differ past the shortest one.
+                difference = Some(usize::min(reference.len(), part.len()));
+            }
+            if let Some(v) = difference {
+                report.first_difference = Some(match report.first_difference {
+                    Some(first) => usize::min(first, v),
+                    None => v,
+                });
+            }
+        }
+        report
+    }
+
+    /// The number of runs.
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+
+    /// Whether all runs gave the same partition.
+    pub fn is_reproducible(&self) -> bool {
+        self.first_difference.is_none()
+    }
+
+    /// The first vertex whose part differs between the first run and another
+    /// one, if any.
+    pub fn first_difference(&self) -> Option<usize> {
+        self.first_difference
+    }
+
+    /// The distinct edge-cuts observed, in increasing order.
+    pub fn edgecuts(&self) -> &[Idx] {
+        &self.edgecuts
+    }
+}
+
+/// Partitions `graph` `runs` times with `method`, and reports whether all
+/// partitions are identical.
+///
+/// All runs use the options of `graph`, including its seed (see
+/// [`crate::option::Seed`]), so they are expected to match.  Runs are
+/// sequential.
+///
+/// # Errors
+///
+/// This function returns [`Error::Input`] if `runs` is zero, and the first
+/// error of the partitioning routine otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::diagnostics::{check_reproducible, ReproReport};
+/// use metis::{GraphBuilder, Method};
+///
+/// // A 16x16 grid.
+/// let mut builder = GraphBuilder::new(256)?;
+/// for i in 0..16 {
+///     for j in 0..15 {
+///         builder.add_edge(16 * i + j, 16 * i + j + 1)?;
+///         builder.add_edge(16 * j + i, 16 * j + i + 16)?;
+///     }
+/// }
+/// let graph = builder.build(1, 4)?;
+///
+/// let report = check_reproducible(&graph, Method::Kway, 5)?;
+/// assert!(report.is_reproducible());
+/// assert_eq!(report.runs(), 5);
+/// assert_eq!(report.edgecuts().len(), 1);
+///
+/// // Different seeds give different partitions.
+/// let runs = (0..5).map(|seed| {
+///     let (part, edgecut) = graph.clone().set_seed(seed).part_kway_owned().unwrap();
+///     (part.into_inner(), edgecut)
+/// });
+/// let report = ReproReport::from_runs(runs);
+/// assert!(!report.is_reproducible());
+/// assert!(report.first_difference().unwrap() < 256);
+/// assert!(report.edgecuts().len() > 1);
+/// # Ok(())
+/// # }
+/// ```
+pub fn check_reproducible(graph: &GraphOwned, method: Method, runs: usize) -> Result<ReproReport> {
+    if runs == 0 {
+        return Err(Error::Input(None));
+    }
+    let runs = (0..runs)
+        .map(|_| {
+            let (part, edgecut) = method.part(graph)?;
+            Ok((part.into_inner(), edgecut))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ReproReport::from_runs(runs))
+}
@@ -0,0 +1,2375 @@
+//! Reading and writing the file formats of METIS' command-line programs.
+//!
+//! These formats are described in the manual of METIS.  Indices are one-based
+//! in files, and zero-based in memory.
+
+use crate::check_mesh_structure;
+use crate::check_no_self_loops;
+use crate::csr;
+use crate::csr::WeightMerge;
+use crate::find_negative;
+use crate::quality::PartitionError;
+use crate::Graph;
+use crate::GraphOwned;
+use crate::Idx;
+use crate::Mesh;
+use crate::NewGraphError;
+use crate::NewMeshError;
+use crate::Numbering;
+use std::fmt;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::result::Result as StdResult;
+use std::str::FromStr;
+
+/// Error raised when reading or writing a file.
+#[derive(Debug)]
+pub enum IoError {
+    /// The underlying reader or writer failed.
+    Io(io::Error),
+
+    /// The file is not well-formed.
+    Parse {
+        /// The line where the error was found, starting from one.
+        line: usize,
+
+        /// What is wrong.
+        msg: &'static str,
+    },
+
+    /// The graph to write is not valid.
+    Graph(NewGraphError),
+
+    /// The graph to write has several constraints but no vertex weights.  The
+    /// format of `gpmetis` only gives the number of constraints along with
+    /// vertex weights.
+    ConstraintsWithoutVwgt,
+
+    /// The partition to write is not valid.
+    Partition(PartitionError),
+
+    /// The mesh to write is not valid.
+    Mesh(NewMeshError),
+
+    /// The binary file is not well-formed, or was written by an incompatible
+    /// build.
+    Binary(&'static str),
+}
+
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "i/o error: {err}"),
+            Self::Parse { line, msg } => write!(f, "line {line}: {msg}"),
+            Self::Graph(err) => write!(f, "invalid graph: {err}"),
+            Self::ConstraintsWithoutVwgt => {
+                write!(f, "constraints require vertex weights in the graph format")
+            }
+            Self::Partition(err) => write!(f, "invalid partition: {err}"),
+            Self::Mesh(err) => write!(f, "invalid mesh: {err}"),
+            Self::Binary(msg) => write!(f, "invalid binary file: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for IoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse { .. } | Self::ConstraintsWithoutVwgt | Self::Binary(_) => None,
+            Self::Graph(err) => Some(err),
+            Self::Partition(err) => Some(err),
+            Self::Mesh(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for IoError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<NewGraphError> for IoError {
+    fn from(err: NewGraphError) -> Self {
+        Self::Graph(err)
+    }
+}
+
+impl From<PartitionError> for IoError {
+    fn from(err: PartitionError) -> Self {
+        Self::Partition(err)
+    }
+}
+
+impl From<NewMeshError> for IoError {
+    fn from(err: NewMeshError) -> Self {
+        Self::Mesh(err)
+    }
+}
+
+impl IoError {
+    fn parse(line: usize, msg: &'static str) -> Self {
+        Self::Parse { line, msg }
+    }
+}
+
+/// The arrays of a graph read from a file, with zero-based indices.
+///
+/// Returned by [`read_graph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphData {
+    /// The number of balancing constraints.
+    pub ncon: Idx,
+
+    /// The adjacency structure of the graph, see [`Graph::new`].
+    pub xadj: Vec<Idx>,
+
+    /// The adjacency list of each vertex, see [`Graph::new`].
+    pub adjncy: Vec<Idx>,
+
+    /// The weights of the vertices, if the file has some.
+    pub vwgt: Option<Vec<Idx>>,
+
+    /// The sizes of the vertices, if the file has some.
+    pub vsize: Option<Vec<Idx>>,
+
+    /// The weights of the edges, if the file has some.
+    pub adjwgt: Option<Vec<Idx>>,
+}
+
+impl GraphData {
+    /// Creates a [`Graph`] that borrows these arrays, to partition it into
+    /// `nparts` parts.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the errors of [`Graph::new`] and of the
+    /// `try_set_*` methods.
+    pub fn as_graph(&self, nparts: Idx) -> StdResult<Graph<'_>, NewGraphError> {
+        let mut graph = Graph::new(self.ncon, nparts, &self.xadj, &self.adjncy)?;
+        if let Some(vwgt) = &self.vwgt {
+            graph = graph.try_set_vwgt(vwgt)?;
+        }
+        if let Some(vsize) = &self.vsize {
+            graph = graph.try_set_vsize(vsize)?;
+        }
+        if let Some(adjwgt) = &self.adjwgt {
+            graph = graph.try_set_adjwgt(adjwgt)?;
+        }
+        Ok(graph)
+    }
+
+    /// Creates a [`GraphOwned`] out of these arrays, to partition it into
+    /// `nparts` parts.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the errors of [`GraphOwned::new`] and of the
+    /// `try_set_*` methods.
+    pub fn into_graph(self, nparts: Idx) -> StdResult<GraphOwned, NewGraphError> {
+        let mut graph = GraphOwned::new(self.ncon, nparts, self.xadj, self.adjncy)?;
+        if let Some(vwgt) = self.vwgt {
+            graph = graph.try_set_vwgt(vwgt)?;
+        }
+        if let Some(vsize) = self.vsize {
+            graph = graph.try_set_vsize(vsize)?;
+        }
+        if let Some(adjwgt) = self.adjwgt {
+            graph = graph.try_set_adjwgt(adjwgt)?;
+        }
+        Ok(graph)
+    }
+}
+
+/// Lines of a file, without comments, numbered from one.
+struct Lines<R> {
+    inner: io::Lines<R>,
+    number: usize,
+}
+
+impl<R: BufRead> Lines<R> {
+    fn new(reader: R) -> Self {
+        Lines {
+            inner: reader.lines(),
+            number: 0,
+        }
+    }
+
+    /// Returns the next line that is not a comment, or `None` at the end of
+    /// the file.
+    fn next(&mut self) -> StdResult<Option<String>, IoError> {
+        for line in self.inner.by_ref() {
+            self.number += 1;
+            let line = line?;
+            if !line.starts_with('%') {
+                return Ok(Some(line));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Checks that the rest of the file is blank.
+    fn finish(&mut self) -> StdResult<(), IoError> {
+        while let Some(line) = self.next()? {
+            if !line.trim().is_empty() {
+                return Err(IoError::parse(
+                    self.number,
+                    "unexpected data after the last line",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the next line that is not a comment, or fails with `msg` at
+    /// the end of the file.
+    fn expect(&mut self, msg: &'static str) -> StdResult<String, IoError> {
+        match self.next()? {
+            Some(line) => Ok(line),
+            None => Err(IoError::parse(self.number + 1, msg)),
+        }
+    }
+
+    /// Same as [`Lines::expect`], and parses the integers of the line.
+    fn expect_values<T: FromStr>(&mut self, msg: &'static str) -> StdResult<Vec<T>, IoError> {
+        let line = self.expect(msg)?;
+        self.parse(&line)
+    }
+
+    /// Parses the integers of a line.
+    fn parse<T: FromStr>(&self, line: &str) -> StdResult<Vec<T>, IoError> {
+        self.parse_tokens(line.split_whitespace())
+    }
+
+    /// Parses integers out of the tokens of a line.
+    fn parse_tokens<'t, T: FromStr>(
+        &self,
+        tokens: impl Iterator<Item = &'t str>,
+    ) -> StdResult<Vec<T>, IoError> {
+        tokens
+            .map(|token| {
+                token
+                    .parse()
+                    .map_err(|_| IoError::parse(self.number, "invalid integer"))
+            })
+            .collect()
+    }
+}
+
+/// Reads a graph in the format of `gpmetis`.
+///
+/// The first line holds the number of vertices `n`, the number of edges `m`,
+/// and optionally a three-digit `fmt` flag and the number of constraints.
+/// The digits of `fmt` tell whether vertex sizes, vertex weights and edge
+/// weights are given, in that order.  Each of the next `n` lines describes a
+/// vertex: its size, its weights, then its neighbors, each followed by the
+/// weight of the edge.  Each edge appears in the lines of both its ends.
+/// Lines that start with `%` are comments.
+///
+/// # Errors
+///
+/// This function returns [`IoError::Io`] if `reader` fails, and
+/// [`IoError::Parse`] if the file is not well-formed, e.g. if neighbors are
+/// out of bounds or the number of edges does not match the header.  It does
+/// not check that the graph is undirected, see [`Graph::new_checked`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use metis::io::read_graph;
+///
+/// // The weighted graph of METIS' manual, with two constraints.
+/// let file = "\
+/// % 7 vertices, 11 edges, vertex and edge weights, 2 constraints.
+/// 7 11 011 2
+/// 4 2 5 1 3 2 2 1
+/// 2 2 1 1 3 2 4 1
+/// 5 2 5 3 4 2 2 2 1 2
+/// 3 1 2 1 3 2 6 2 7 5
+/// 1 1 1 1 3 3 6 2
+/// 6 2 5 2 4 2 7 6
+/// 2 1 6 6 4 5
+/// ";
+/// let data = read_graph(file.as_bytes())?;
+/// assert_eq!(data.ncon, 2);
+/// assert_eq!(data.xadj, [0, 3, 6, 10, 14, 17, 20, 22]);
+/// assert_eq!(&data.adjncy[..3], [4, 2, 1]);
+/// assert_eq!(&data.vwgt.as_ref().unwrap()[..4], [4, 2, 2, 2]);
+/// assert_eq!(&data.adjwgt.as_ref().unwrap()[..3], [1, 2, 1]);
+/// assert_eq!(data.vsize, None);
+///
+/// let mut part = [0; 7];
+/// data.as_graph(2)?.part_kway(&mut part)?;
+///
+/// // Files of the METIS distribution.
+/// let graphs = concat!(env!("CARGO_MANIFEST_DIR"), "/metis-sys/vendor/metis/graphs");
+/// for (name, nvtxs, ncon) in [("4elt.graph", 15606, 1), ("test.mgraph", 766, 2)] {
+///     let file = std::fs::File::open(format!("{graphs}/{name}"))?;
+///     let data = read_graph(std::io::BufReader::new(file))?;
+///     assert_eq!(data.xadj.len(), nvtxs + 1);
+///     assert_eq!(data.ncon, ncon);
+///     data.as_graph(4)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_graph(reader: impl BufRead) -> StdResult<GraphData, IoError> {
+    let mut lines = Lines::new(reader);
+    let header = lines
+        .next()?
+        .ok_or_else(|| IoError::parse(lines.number, "missing header"))?;
+    let tokens: Vec<&str> = header.split_whitespace().collect();
+    if !(2..=4).contains(&tokens.len()) {
+        return Err(IoError::parse(
+            lines.number,
+            "header must have 2 to 4 values",
+        ));
+    }
+    let fields = lines.parse(&header)?;
+    let (nvtxs, nedges) = (fields[0], fields[1]);
+    if nvtxs < 0 || nedges < 0 {
+        return Err(IoError::parse(lines.number, "negative size in header"));
+    }
+    let fmt = tokens.get(2).copied().unwrap_or("0");
+    if fmt.len() > 3 || fmt.chars().any(|c| c != '0' && c != '1') {
+        return Err(IoError::parse(lines.number, "invalid fmt flag"));
+    }
+    let flag = |digit: usize| fmt.len() > digit && fmt.as_bytes()[fmt.len() - 1 - digit] == b'1';
+    let (has_vsize, has_vwgt, has_adjwgt) = (flag(2), flag(1), flag(0));
+    let ncon = fields.get(3).copied().unwrap_or(1);
+    if fields.len() == 4 && !has_vwgt {
+        return Err(IoError::parse(
+            lines.number,
+            "constraints are given without vertex weights",
+        ));
+    }
+    if ncon <= 0 {
+        return Err(IoError::parse(
+            lines.number,
+            "the number of constraints must be positive",
+        ));
+    }
+
+    // Arrays grow as lines are read, since the header cannot be trusted.
+    let mut xadj = vec![0];
+    let mut adjncy = Vec::new();
+    let mut vwgt = Vec::new();
+    let mut vsize = Vec::new();
+    let mut adjwgt = Vec::new();
+    for _ in 0..nvtxs {
+        let line = lines
+            .next()?
+            .ok_or_else(|| IoError::parse(lines.number + 1, "fewer vertices than announced"))?;
+        let mut values = lines.parse(&line)?.into_iter();
+        let mut take = |msg| {
+            values
+                .next()
+                .ok_or_else(|| IoError::parse(lines.number, msg))
+        };
+        if has_vsize {
+            vsize.push(take("missing vertex size")?);
+        }
+        if has_vwgt {
+            for _ in 0..ncon {
+                vwgt.push(take("missing vertex weight")?);
+            }
+        }
+        let rest: Vec<Idx> = values.collect();
+        let stride = if has_adjwgt { 2 } else { 1 };
+        let arcs = rest.chunks_exact(stride);
+        if !arcs.remainder().is_empty() {
+            return Err(IoError::parse(lines.number, "missing edge weight"));
+        }
+        for arc in arcs {
+            if arc[0] < 1 || nvtxs < arc[0] {
+                return Err(IoError::parse(lines.number, "neighbor out of bounds"));
+            }
+            adjncy.push(arc[0] - 1);
+            if has_adjwgt {
+                adjwgt.push(arc[1]);
+            }
+        }
+        let len = Idx::try_from(adjncy.len())
+            .map_err(|_| IoError::parse(lines.number, "graph is too large"))?;
+        xadj.push(len);
+    }
+    if adjncy.len() as u64 != 2 * nedges as u64 {
+        return Err(IoError::parse(
+            lines.number,
+            "the number of edges does not match the header",
+        ));
+    }
+    lines.finish()?;
+
+    Ok(GraphData {
+        ncon,
+        xadj,
+        adjncy,
+        vwgt: has_vwgt.then_some(vwgt),
+        vsize: has_vsize.then_some(vsize),
+        adjwgt: has_adjwgt.then_some(adjwgt),
+    })
+}
+
+/// Writes a graph in the format of `gpmetis`, see [`read_graph`].
+///
+/// The arguments are those of [`Graph::new`] and its `set_*` methods.  Each
+/// edge must appear in the adjacency lists of both its ends, as METIS expects.
+/// The `fmt` flag of the header is set according to which arrays are given,
+/// and the number of constraints is written when it is not one.
+///
+/// # Errors
+///
+/// This function returns [`IoError::Graph`] if the arguments are rejected by
+/// [`Graph::new_checked`] or the `try_set_*` methods, or if the graph has
+/// self-loops, and [`IoError::ConstraintsWithoutVwgt`] if `ncon` is not one
+/// and `vwgt` is missing.  It returns [`IoError::Io`] if `writer` fails.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use metis::io::{read_graph, write_graph};
+///
+/// // 0 - 1 - 2
+/// let xadj = &[0, 1, 3, 4];
+/// let adjncy = &[1, 0, 2, 1];
+/// let adjwgt = &[5, 5, 1, 1];
+/// let vwgt = &[1, 2, 3, 4, 5, 6];
+///
+/// let mut file = Vec::new();
+/// write_graph(&mut file, xadj, adjncy, Some(vwgt), None, Some(adjwgt), 2)?;
+/// assert_eq!(
+///     String::from_utf8(file.clone())?,
+///     "3 2 011 2\n1 2 2 5\n3 4 1 5 3 1\n5 6 2 1\n",
+/// );
+///
+/// let data = read_graph(&file[..])?;
+/// assert_eq!(data.ncon, 2);
+/// assert_eq!(data.xadj, xadj);
+/// assert_eq!(data.adjncy, adjncy);
+/// assert_eq!(data.vwgt.as_deref(), Some(&vwgt[..]));
+/// assert_eq!(data.vsize, None);
+/// assert_eq!(data.adjwgt.as_deref(), Some(&adjwgt[..]));
+///
+/// let mut file = Vec::new();
+/// write_graph(&mut file, xadj, adjncy, None, Some(&[1, 1, 2]), None, 1)?;
+/// assert_eq!(String::from_utf8(file)?, "3 2 100\n1 2\n1 1 3\n2 2\n");
+///
+/// // Edges must be undirected.
+/// let err = write_graph(Vec::new(), xadj, &[1, 0, 2, 0], None, None, None, 1);
+/// assert!(err.is_err());
+///
+/// // Constraints cannot be written without vertex weights.
+/// let err = write_graph(Vec::new(), xadj, adjncy, None, None, None, 2);
+/// assert!(matches!(err, Err(metis::io::IoError::ConstraintsWithoutVwgt)));
+///
+/// // Files of the METIS distribution are read back unchanged.
+/// let graphs = concat!(env!("CARGO_MANIFEST_DIR"), "/metis-sys/vendor/metis/graphs");
+/// for name in ["4elt.graph", "test.mgraph"] {
+///     let file = std::fs::File::open(format!("{graphs}/{name}"))?;
+///     let data = read_graph(std::io::BufReader::new(file))?;
+///     let mut file = Vec::new();
+///     write_graph(
+///         &mut file,
+///         &data.xadj,
+///         &data.adjncy,
+///         data.vwgt.as_deref(),
+///         data.vsize.as_deref(),
+///         data.adjwgt.as_deref(),
+///         data.ncon,
+///     )?;
+///     assert_eq!(read_graph(&file[..])?, data);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_graph(
+    writer: impl Write,
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    vwgt: Option<&[Idx]>,
+    vsize: Option<&[Idx]>,
+    adjwgt: Option<&[Idx]>,
+    ncon: Idx,
+) -> StdResult<(), IoError> {
+    let mut graph = Graph::new_checked(ncon, 1, xadj, adjncy)?;
+    check_no_self_loops(xadj, adjncy, Numbering::C)?;
+    if let Some(vwgt) = vwgt {
+        graph = graph.try_set_vwgt(vwgt)?;
+    } else if ncon != 1 {
+        return Err(IoError::ConstraintsWithoutVwgt);
+    }
+    if let Some(vsize) = vsize {
+        graph = graph.try_set_vsize(vsize)?;
+    }
+    if let Some(adjwgt) = adjwgt {
+        graph.try_set_adjwgt(adjwgt)?;
+    }
+
+    let mut writer = io::BufWriter::new(writer);
+    let nvtxs = xadj.len() - 1;
+    write!(writer, "{} {}", nvtxs, adjncy.len() / 2)?;
+    if vsize.is_some() || vwgt.is_some() || adjwgt.is_some() {
+        let flag = |array: Option<&[Idx]>| if array.is_some() { '1' } else { '0' };
+        write!(writer, " {}{}{}", flag(vsize), flag(vwgt), flag(adjwgt))?;
+    }
+    if ncon != 1 {
+        write!(writer, " {ncon}")?;
+    }
+    writeln!(writer)?;
+
+    let ncon = ncon as usize;
+    for (v, w) in xadj.windows(2).enumerate() {
+        let mut sep = "";
+        if let Some(vsize) = vsize {
+            write!(writer, "{}", vsize[v])?;
+            sep = " ";
+        }
+        if let Some(vwgt) = vwgt {
+            for weight in &vwgt[v * ncon..(v + 1) * ncon] {
+                write!(writer, "{sep}{weight}")?;
+                sep = " ";
+            }
+        }
+        for e in w[0] as usize..w[1] as usize {
+            write!(writer, "{sep}{}", adjncy[e] + 1)?;
+            sep = " ";
+            if let Some(adjwgt) = adjwgt {
+                write!(writer, " {}", adjwgt[e])?;
+            }
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a partition in the format of `gpmetis`, e.g. a `graph.part.4` file.
+///
+/// The file holds the part of each vertex, one per line.  Lines that start
+/// with `%` are comments, and blank lines are ignored.
+///
+/// # Errors
+///
+/// This function returns [`IoError::Io`] if `reader` fails, and
+/// [`IoError::Parse`] if a value is not an integer, is negative, or does not
+/// fit in [`Idx`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use metis::io::{read_partition, write_partition};
+///
+/// let file = "% 4 vertices\n0\n 1\n1 \n\n0\n";
+/// let part = read_partition(file.as_bytes())?;
+/// assert_eq!(part, [0, 1, 1, 0]);
+///
+/// let mut file = Vec::new();
+/// write_partition(&mut file, &part)?;
+/// assert_eq!(String::from_utf8(file.clone())?, "0\n1\n1\n0\n");
+/// assert_eq!(read_partition(&file[..])?, part);
+///
+/// assert!(read_partition("0\n-1\n".as_bytes()).is_err());
+/// assert!(read_partition("0 1\n".as_bytes()).is_err());
+/// let too_large = format!("0\n{}\n", i64::from(metis::Idx::MAX) + 1);
+/// assert!(read_partition(too_large.as_bytes()).is_err());
+///
+/// // Written by `gpmetis test.mgraph 4`, which reports an edge-cut of 82 and
+/// // a communication volume of 153.
+/// use metis::io::read_graph;
+/// use metis::quality::{communication_volume, edge_cut};
+/// use std::fs::{self, File};
+/// use std::io::BufReader;
+///
+/// let root = env!("CARGO_MANIFEST_DIR");
+/// let graph = format!("{root}/metis-sys/vendor/metis/graphs/test.mgraph");
+/// let graph = read_graph(BufReader::new(File::open(graph)?))?;
+/// let file = fs::read(format!("{root}/fixtures/test.mgraph.part.4"))?;
+/// let part = read_partition(&file[..])?;
+/// assert_eq!(part.len(), 766);
+/// assert!(part.iter().all(|p| (0..4).contains(p)));
+/// let (xadj, adjncy) = (&graph.xadj, &graph.adjncy);
+/// assert_eq!(edge_cut(xadj, adjncy, graph.adjwgt.as_deref(), &part)?, 82);
+/// assert_eq!(communication_volume(xadj, adjncy, graph.vsize.as_deref(), &part)?, 153);
+///
+/// let mut written = Vec::new();
+/// write_partition(&mut written, &part)?;
+/// assert_eq!(written, file);
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_partition(reader: impl BufRead) -> StdResult<Vec<Idx>, IoError> {
+    let mut lines = Lines::new(reader);
+    let mut part = Vec::new();
+    while let Some(line) = lines.next()? {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let value: i64 = line
+            .parse()
+            .map_err(|_| IoError::parse(lines.number, "invalid integer"))?;
+        if value < 0 {
+            return Err(IoError::parse(lines.number, "negative part"));
+        }
+        let value =
+            Idx::try_from(value).map_err(|_| IoError::parse(lines.number, "part is too large"))?;
+        part.push(value);
+    }
+    Ok(part)
+}
+
+/// Writes a partition in the format of `gpmetis`, see [`read_partition`].
+///
+/// # Errors
+///
+/// This function returns [`IoError::Partition`] if some labels are negative,
+/// and [`IoError::Io`] if `writer` fails.
+pub fn write_partition(writer: impl Write, part: &[Idx]) -> StdResult<(), IoError> {
+    if let Some((index, value)) = find_negative(part) {
+        return Err(PartitionError::NegativeLabel { index, value }.into());
+    }
+    let mut writer = io::BufWriter::new(writer);
+    for p in part {
+        writeln!(writer, "{p}")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Fill colors of the parts in [`write_dot`], from the "Set3" scheme of
+/// ColorBrewer.  Parts beyond the twelfth reuse them in turn.
+const DOT_PALETTE: [&str; 12] = [
+    "#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462", "#b3de69", "#fccde5",
+    "#d9d9d9", "#bc80bd", "#ccebc5", "#ffed6f",
+];
+
+/// Writes a graph in the DOT language of [Graphviz](https://graphviz.org), to
+/// look at small graphs and their partitions.
+///
+/// The graph is undirected, and each edge is written once.  When `part` is
+/// given, vertices are filled with the color of their part, from a fixed
+/// palette of twelve colors.  See [`write_dot_weighted`] to also label
+/// vertices and edges with their weights.
+///
+/// # Errors
+///
+/// This function returns [`IoError::Graph`] if the arguments are rejected by
+/// [`Graph::new_checked`] or if the graph has self-loops,
+/// [`IoError::Partition`] if `part` does not hold one non-negative label per
+/// vertex, and [`IoError::Io`] if `writer` fails.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use metis::io::write_dot;
+///
+/// // 5 - 3 - 4 - 0
+/// //     |   | /
+/// //     2 - 1
+/// let xadj = &[0, 2, 5, 7, 10, 13, 14];
+/// let adjncy = &[1, 4, 0, 2, 4, 1, 3, 2, 4, 5, 0, 1, 3, 3];
+///
+/// let mut file = Vec::new();
+/// write_dot(&mut file, xadj, adjncy, None)?;
+/// assert_eq!(
+///     String::from_utf8(file)?,
+///     "graph {
+///     0;
+///     1;
+///     2;
+///     3;
+///     4;
+///     5;
+///     0 -- 1;
+///     0 -- 4;
+///     1 -- 2;
+///     1 -- 4;
+///     2 -- 3;
+///     3 -- 4;
+///     3 -- 5;
+/// }
+/// ",
+/// );
+///
+/// let mut file = Vec::new();
+/// write_dot(&mut file, xadj, adjncy, Some(&[0, 0, 1, 1, 0, 1]))?;
+/// assert_eq!(
+///     String::from_utf8(file)?,
+///     r##"graph {
+///     node [style=filled];
+///     0 [fillcolor="#8dd3c7"];
+///     1 [fillcolor="#8dd3c7"];
+///     2 [fillcolor="#ffffb3"];
+///     3 [fillcolor="#ffffb3"];
+///     4 [fillcolor="#8dd3c7"];
+///     5 [fillcolor="#ffffb3"];
+///     0 -- 1;
+///     0 -- 4;
+///     1 -- 2;
+///     1 -- 4;
+///     2 -- 3;
+///     3 -- 4;
+///     3 -- 5;
+/// }
+/// "##,
+/// );
+///
+/// // Edges must be undirected, and labels non-negative.
+/// assert!(write_dot(Vec::new(), &[0, 1, 1], &[1], None).is_err());
+/// assert!(write_dot(Vec::new(), xadj, adjncy, Some(&[0, 0, -1, 1, 0, 1])).is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_dot(
+    writer: impl Write,
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    part: Option<&[Idx]>,
+) -> StdResult<(), IoError> {
+    write_dot_weighted(writer, xadj, adjncy, None, None, 1, part)
+}
+
+/// Same as [`write_dot`], with vertices and edges labeled by their weights.
+///
+/// Vertices are labeled with their index followed by their `ncon` weights in
+/// parentheses, and edges with their weight.  The arguments are those of
+/// [`write_graph`], and are checked likewise.
+///
+/// # Errors
+///
+/// This function returns the errors of [`write_dot`], and also fails with
+/// [`IoError::Graph`] if the weights are rejected by the `try_set_*` methods
+/// of [`Graph`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use metis::io::write_dot_weighted;
+///
+/// // 0 - 1 - 2
+/// let xadj = &[0, 1, 3, 4];
+/// let adjncy = &[1, 0, 2, 1];
+/// let adjwgt = &[5, 5, 1, 1];
+/// let vwgt = &[1, 2, 3, 4, 5, 6];
+///
+/// let mut file = Vec::new();
+/// write_dot_weighted(&mut file, xadj, adjncy, Some(vwgt), Some(adjwgt), 2, Some(&[0, 0, 1]))?;
+/// assert_eq!(
+///     String::from_utf8(file)?,
+///     r##"graph {
+///     node [style=filled];
+///     0 [label="0 (1, 2)", fillcolor="#8dd3c7"];
+///     1 [label="1 (3, 4)", fillcolor="#8dd3c7"];
+///     2 [label="2 (5, 6)", fillcolor="#ffffb3"];
+///     0 -- 1 [label="5"];
+///     1 -- 2 [label="1"];
+/// }
+/// "##,
+/// );
+///
+/// // There must be one weight per edge.
+/// let err = write_dot_weighted(Vec::new(), xadj, adjncy, None, Some(&[5, 5, 1]), 1, None);
+/// assert!(err.is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_dot_weighted(
+    writer: impl Write,
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    vwgt: Option<&[Idx]>,
+    adjwgt: Option<&[Idx]>,
+    ncon: Idx,
+    part: Option<&[Idx]>,
+) -> StdResult<(), IoError> {
+    let mut graph = Graph::new_checked(ncon, 1, xadj, adjncy)?;
+    check_no_self_loops(xadj, adjncy, Numbering::C)?;
+    if let Some(vwgt) = vwgt {
+        graph = graph.try_set_vwgt(vwgt)?;
+    }
+    if let Some(adjwgt) = adjwgt {
+        graph.try_set_adjwgt(adjwgt)?;
+    }
+    let nvtxs = xadj.len() - 1;
+    if let Some(part) = part {
+        if part.len() != nvtxs {
+            return Err(PartitionError::LengthMismatch {
+                expected: nvtxs,
+                actual: part.len(),
+            }
+            .into());
+        }
+        if let Some((index, value)) = find_negative(part) {
+            return Err(PartitionError::NegativeLabel { index, value }.into());
+        }
+    }
+
+    let mut writer = io::BufWriter::new(writer);
+    writeln!(writer, "graph {{")?;
+    if part.is_some() {
+        writeln!(writer, "    node [style=filled];")?;
+    }
+    let ncon = ncon as usize;
+    for v in 0..nvtxs {
+        let mut attributes = Vec::new();
+        if let Some(vwgt) = vwgt {
+            let weights: Vec<String> = vwgt[v * ncon..(v + 1) * ncon]
+                .iter()
+                .map(Idx::to_string)
+                .collect();
+            attributes.push(format!("label=\"{v} ({})\"", weights.join(", ")));
+        }
+        if let Some(part) = part {
+            let color = DOT_PALETTE[part[v] as usize % DOT_PALETTE.len()];
+            attributes.push(format!("fillcolor=\"{color}\""));
+        }
+        if attributes.is_empty() {
+            writeln!(writer, "    {v};")?;
+        } else {
+            writeln!(writer, "    {v} [{}];", attributes.join(", "))?;
+        }
+    }
+    for (u, w) in xadj.windows(2).enumerate() {
+        for e in w[0] as usize..w[1] as usize {
+            let v = adjncy[e] as usize;
+            if v < u {
+                continue;
+            }
+            match adjwgt {
+                Some(adjwgt) => writeln!(writer, "    {u} -- {v} [label=\"{}\"];", adjwgt[e])?,
+                None => writeln!(writer, "    {u} -- {v};")?,
+            }
+        }
+    }
+    writeln!(writer, "}}")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// The arrays of a mesh read from a file, with zero-based indices.
+///
+/// Returned by [`read_mesh`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeshData {
+    /// The number of weights of each element.
+    pub ncon: Idx,
+
+    /// The index of the nodes of each element in `eind`, see [`Mesh::new`].
+    pub eptr: Vec<Idx>,
+
+    /// The nodes of each element, see [`Mesh::new`].
+    pub eind: Vec<Idx>,
+
+    /// The weights of the elements, if the file has some.
+    pub ewgt: Option<Vec<Idx>>,
+}
+
+impl MeshData {
+    /// Creates a [`Mesh`] that borrows these arrays, to partition it into
+    /// `nparts` parts.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the errors of [`Mesh::new`] and of
+    /// [`Mesh::try_set_element_vwgt`], which only accepts one weight per
+    /// element.
+    pub fn as_mesh(&self, nparts: Idx) -> StdResult<Mesh<'_>, NewMeshError> {
+        let mut mesh = Mesh::new(nparts, &self.eptr, &self.eind)?;
+        if let Some(ewgt) = &self.ewgt {
+            mesh = mesh.try_set_element_vwgt(ewgt)?;
+        }
+        Ok(mesh)
+    }
+}
+
+/// Reads a mesh in the format of `mpmetis`.
+///
+/// The first line holds the number of elements, and optionally the number of
+/// weights of each element.  Each of the next lines describes an element: its
+/// weights, then its nodes.  Elements may have different numbers of nodes.
+/// Lines that start with `%` are comments.
+///
+/// # Errors
+///
+/// This function returns [`IoError::Io`] if `reader` fails, and
+/// [`IoError::Parse`] if the file is not well-formed, e.g. if nodes are out of
+/// bounds or weights are negative.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use metis::io::read_mesh;
+///
+/// // A quadrangle and two triangles.
+/// //  4 --- 5 --- 6
+/// //  |     | \ 3 |
+/// //  |  1  |  \  |
+/// //  |     | 2 \ |
+/// //  1 --- 2 --- 3
+/// let file = "\
+/// % Mixed elements.
+/// 3
+/// 1 2 5 4
+/// 2 3 5
+/// 3 6 5
+/// ";
+/// let data = read_mesh(file.as_bytes())?;
+/// assert_eq!(data.eptr, [0, 4, 7, 10]);
+/// assert_eq!(data.eind, [0, 1, 4, 3, 1, 2, 4, 2, 5, 4]);
+/// assert_eq!(data.ewgt, None);
+/// assert_eq!(data.as_mesh(2)?.nn(), 6);
+///
+/// // The same mesh, with a weight per element.
+/// let file = "3 1\n4 1 2 5 4\n1 2 3 5\n1 3 6 5\n";
+/// let data = read_mesh(file.as_bytes())?;
+/// assert_eq!(data.eind, [0, 1, 4, 3, 1, 2, 4, 2, 5, 4]);
+/// assert_eq!(data.ewgt.as_deref(), Some(&[4, 1, 1][..]));
+///
+/// let mut epart = [0; 3];
+/// let mut npart = [0; 6];
+/// data.as_mesh(2)?.part_dual(&mut epart, &mut npart)?;
+///
+/// // The mesh of the METIS distribution.
+/// let mesh = concat!(env!("CARGO_MANIFEST_DIR"), "/metis-sys/vendor/metis/graphs/metis.mesh");
+/// let data = read_mesh(std::io::BufReader::new(std::fs::File::open(mesh)?))?;
+/// assert_eq!(data.eptr.len(), 7434 + 1);
+/// data.as_mesh(4)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_mesh(reader: impl BufRead) -> StdResult<MeshData, IoError> {
+    let mut lines = Lines::new(reader);
+    let header = lines
+        .next()?
+        .ok_or_else(|| IoError::parse(lines.number, "missing header"))?;
+    let fields = lines.parse(&header)?;
+    if !(1..=2).contains(&fields.len()) {
+        return Err(IoError::parse(
+            lines.number,
+            "header must have 1 or 2 values",
+        ));
+    }
+    let ne = fields[0];
+    if ne <= 0 {
+        return Err(IoError::parse(
+            lines.number,
+            "the number of elements must be positive",
+        ));
+    }
+    let nweights = fields.get(1).copied().unwrap_or(0);
+    if nweights < 0 {
+        return Err(IoError::parse(lines.number, "negative number of weights"));
+    }
+
+    // Arrays grow as lines are read, since the header cannot be trusted.
+    let mut eptr = vec![0];
+    let mut eind = Vec::new();
+    let mut ewgt = Vec::new();
+    for _ in 0..ne {
+        let line = lines
+            .next()?
+            .ok_or_else(|| IoError::parse(lines.number + 1, "fewer elements than announced"))?;
+        let mut values = lines.parse(&line)?.into_iter();
+        for _ in 0..nweights {
+            let weight = values
+                .next()
+                .ok_or_else(|| IoError::parse(lines.number, "missing element weight"))?;
+            if weight < 0 {
+                return Err(IoError::parse(lines.number, "negative element weight"));
+            }
+            ewgt.push(weight);
+        }
+        for node in values {
+            if node < 1 {
+                return Err(IoError::parse(lines.number, "node out of bounds"));
+            }
+            eind.push(node - 1);
+        }
+        let len = Idx::try_from(eind.len())
+            .map_err(|_| IoError::parse(lines.number, "mesh is too large"))?;
+        eptr.push(len);
+    }
+    lines.finish()?;
+
+    Ok(MeshData {
+        ncon: Idx::max(nweights, 1),
+        eptr,
+        eind,
+        ewgt: (nweights > 0).then_some(ewgt),
+    })
+}
+
+/// Writes a mesh in the format of `mpmetis`, see [`read_mesh`].
+///
+/// The arguments are those of [`Mesh::new`] and
+/// [`Mesh::set_element_vwgt`].  When `vwgt` is given, the header says each
+/// element has one weight.
+///
+/// # Errors
+///
+/// This function returns [`IoError::Mesh`] if `eptr` and `eind` are not a
+/// valid mesh structure, or if `vwgt` does not hold one non-negative weight
+/// per element.  It returns [`IoError::Io`] if `writer` fails.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use metis::io::{read_mesh, write_mesh};
+///
+/// // A quadrangle and a triangle.
+/// let eptr = &[0, 4, 7];
+/// let eind = &[0, 1, 2, 3, 1, 4, 2];
+///
+/// let mut file = Vec::new();
+/// write_mesh(&mut file, eptr, eind, None)?;
+/// assert_eq!(String::from_utf8(file.clone())?, "2\n1 2 3 4\n2 5 3\n");
+/// let data = read_mesh(&file[..])?;
+/// assert_eq!(data.eptr, eptr);
+/// assert_eq!(data.eind, eind);
+/// assert_eq!(data.ewgt, None);
+///
+/// let mut file = Vec::new();
+/// write_mesh(&mut file, eptr, eind, Some(&[3, 1]))?;
+/// assert_eq!(String::from_utf8(file.clone())?, "2 1\n3 1 2 3 4\n1 2 5 3\n");
+/// let data = read_mesh(&file[..])?;
+/// assert_eq!(data.eptr, eptr);
+/// assert_eq!(data.eind, eind);
+/// assert_eq!(data.ewgt.as_deref(), Some(&[3, 1][..]));
+///
+/// assert!(write_mesh(Vec::new(), eptr, eind, Some(&[1])).is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_mesh(
+    writer: impl Write,
+    eptr: &[Idx],
+    eind: &[Idx],
+    vwgt: Option<&[Idx]>,
+) -> StdResult<(), IoError> {
+    let (ne, _) = check_mesh_structure(eptr, eind)?;
+    if let Some(vwgt) = vwgt {
+        NewMeshError::check_len("vwgt", ne as usize, vwgt.len())?;
+        if let Some((index, value)) = find_negative(vwgt) {
+            return Err(NewMeshError::NegativeValue {
+                array: "vwgt",
+                index,
+                value,
+            }
+            .into());
+        }
+    }
+
+    let mut writer = io::BufWriter::new(writer);
+    if vwgt.is_some() {
+        writeln!(writer, "{ne} 1")?;
+    } else {
+        writeln!(writer, "{ne}")?;
+    }
+    for (e, w) in eptr.windows(2).enumerate() {
+        let mut sep = "";
+        if let Some(vwgt) = vwgt {
+            write!(writer, "{}", vwgt[e])?;
+            sep = " ";
+        }
+        for node in &eind[w[0] as usize..w[1] as usize] {
+            write!(writer, "{sep}{}", node + 1)?;
+            sep = " ";
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// The first bytes of the files of [`write_bin`].
+const BIN_MAGIC: &[u8; 8] = b"METISRS\0";
+
+/// The version of the format of [`write_bin`].
+const BIN_VERSION: u32 = 1;
+
+/// Writes a graph in a compact binary format, that [`read_bin`] loads much
+/// faster than the text formats.
+///
+/// The file starts with a header: 8 magic bytes, the version of the format,
+/// the sizes of [`Idx`] and [`crate::Real`], which arrays are present, `ncon`,
+/// and the length of each array.  The arrays follow, as little-endian
+/// integers.  Files are only portable between builds that use the same
+/// [`Idx`].
+///
+/// # Errors
+///
+/// This function returns [`IoError::Io`] if `writer` fails.  It does not
+/// check the graph.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use metis::io::{read_bin, read_graph, write_bin};
+///
+/// let data = read_graph("3 2 001\n2 4\n1 4 3 1\n2 1\n".as_bytes())?;
+///
+/// let mut file = Vec::new();
+/// write_bin(&mut file, &data)?;
+/// assert_eq!(read_bin(&file[..])?, data);
+///
+/// // Truncated or corrupted files are rejected.
+/// assert!(read_bin(&file[..file.len() - 1]).is_err());
+/// let mut corrupted = file.clone();
+/// // The last neighbor comes before the four edge weights.
+/// let last = corrupted.len() - 5 * 4;
+/// corrupted[last] = 7;
+/// assert!(read_bin(&corrupted[..]).is_err());
+/// assert!(metis::io::read_bin_unchecked(&corrupted[..]).is_ok());
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_bin(writer: impl Write, data: &GraphData) -> StdResult<(), IoError> {
+    let mut writer = io::BufWriter::new(writer);
+    let arrays = [
+        Some(&data.xadj),
+        Some(&data.adjncy),
+        data.vwgt.as_ref(),
+        data.vsize.as_ref(),
+        data.adjwgt.as_ref(),
+    ];
+    let flags = arrays[2..]
+        .iter()
+        .enumerate()
+        .fold(0_u8, |flags, (i, array)| {
+            flags | (u8::from(array.is_some()) << i)
+        });
+
+    writer.write_all(BIN_MAGIC)?;
+    writer.write_all(&BIN_VERSION.to_le_bytes())?;
+    writer.write_all(&[
+        std::mem::size_of::<Idx>() as u8,
+        std::mem::size_of::<crate::Real>() as u8,
+        flags,
+        0,
+    ])?;
+    writer.write_all(&i64::from(data.ncon).to_le_bytes())?;
+    for array in arrays {
+        let len = array.map_or(0, |array| array.len() as u64);
+        writer.write_all(&len.to_le_bytes())?;
+    }
+    for array in arrays.into_iter().flatten() {
+        for value in array {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a graph written by [`write_bin`], and checks it like
+/// [`GraphData::as_graph`] does.
+///
+/// # Errors
+///
+/// This function returns [`IoError::Io`] if `reader` fails,
+/// [`IoError::Binary`] if the file is truncated, was not written by
+/// [`write_bin`], or was written by a build that uses another [`Idx`], and
+/// [`IoError::Graph`] if the graph is not valid.
+pub fn read_bin(reader: impl io::Read) -> StdResult<GraphData, IoError> {
+    let data = read_bin_unchecked(reader)?;
+    data.as_graph(1)?;
+    Ok(data)
+}
+
+/// Same as [`read_bin`], without checking the graph, for trusted files.
+///
+/// The conversions of [`GraphData`] into graphs still run their checks;
+/// skipping them is up to [`crate::Graph::new_unchecked`].
+///
+/// # Errors
+///
+/// This function returns the same errors as [`read_bin`], except
+/// [`IoError::Graph`].
+pub fn read_bin_unchecked(mut reader: impl io::Read) -> StdResult<GraphData, IoError> {
+    fn read<const N: usize>(reader: &mut impl io::Read) -> StdResult<[u8; N], IoError> {
+        let mut bytes = [0; N];
+        reader.read_exact(&mut bytes).map_err(truncated)?;
+        Ok(bytes)
+    }
+
+    fn truncated(err: io::Error) -> IoError {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            IoError::Binary("file is truncated")
+        } else {
+            IoError::Io(err)
+        }
+    }
+
+    if &read::<8>(&mut reader)? != BIN_MAGIC {
+        return Err(IoError::Binary("not a graph file"));
+    }
+    if u32::from_le_bytes(read(&mut reader)?) != BIN_VERSION {
+        return Err(IoError::Binary("unsupported version"));
+    }
+    let [idx_width, _, flags, _] = read(&mut reader)?;
+    if usize::from(idx_width) != std::mem::size_of::<Idx>() {
+        return Err(IoError::Binary("integer width does not match this build"));
+    }
+    let ncon = Idx::try_from(i64::from_le_bytes(read(&mut reader)?))
+        .map_err(|_| IoError::Binary("invalid number of constraints"))?;
+    let mut lens = [0; 5];
+    for len in &mut lens {
+        *len = usize::try_from(u64::from_le_bytes(read(&mut reader)?))
+            .map_err(|_| IoError::Binary("array is too large"))?;
+    }
+
+    let mut read_array = |len: usize| -> StdResult<Vec<Idx>, IoError> {
+        // Read by chunks, so that a corrupted length does not allocate much
+        // more than the size of the file.
+        const CHUNK: usize = 1 << 16;
+        let mut array = Vec::new();
+        let mut bytes = vec![0; std::mem::size_of::<Idx>() * usize::min(len, CHUNK)];
+        let mut left = len;
+        while left > 0 {
+            let count = usize::min(left, CHUNK);
+            let bytes = &mut bytes[..count * std::mem::size_of::<Idx>()];
+            reader.read_exact(bytes).map_err(truncated)?;
+            array.extend(
+                bytes
+                    .chunks_exact(std::mem::size_of::<Idx>())
+                    .map(|chunk| Idx::from_le_bytes(chunk.try_into().unwrap())),
+            );
+            left -= count;
+        }
+        Ok(array)
+    };
+    let xadj = read_array(lens[0])?;
+    let adjncy = read_array(lens[1])?;
+    let mut optional = |i: usize| -> StdResult<Option<Vec<Idx>>, IoError> {
+        if flags & (1 << i) == 0 {
+            return Ok(None);
+        }
+        read_array(lens[2 + i]).map(Some)
+    };
+    let vwgt = optional(0)?;
+    let vsize = optional(1)?;
+    let adjwgt = optional(2)?;
+
+    Ok(GraphData {
+        ncon,
+        xadj,
+        adjncy,
+        vwgt,
+        vsize,
+        adjwgt,
+    })
+}
+
+/// How [`read_matrix_market`] turns the values of a matrix into edge weights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightPolicy {
+    /// Ignore values: the graph has no edge weights.
+    Unit,
+
+    /// Use the absolute value of each entry, rounded to the nearest integer,
+    /// and at least one.
+    AbsRounded,
+
+    /// Scale absolute values linearly into `1..=levels`, so that the largest
+    /// entry gets weight `levels`.  Levels below one act like one.
+    Quantize {
+        /// The number of distinct weights.
+        levels: Idx,
+    },
+}
+
+impl WeightPolicy {
+    /// Returns the weight of an entry of absolute value `value`, given the
+    /// largest absolute value `max` of the matrix.
+    fn apply(self, value: f64, max: f64) -> Idx {
+        let weight = match self {
+            Self::Unit => 1.0,
+            Self::AbsRounded => value.round(),
+            Self::Quantize { levels } => {
+                let levels = f64::from(Idx::max(levels, 1));
+                if max == 0.0 {
+                    1.0
+                } else {
+                    1.0 + ((levels - 1.0) * value / max).round()
+                }
+            }
+        };
+        weight.clamp(1.0, f64::from(Idx::MAX)) as Idx
+    }
+}
+
+/// Reads a sparse matrix in the coordinate Matrix Market format, as the graph
+/// of its nonzero entries.
+///
+/// The matrix must be square.  Entries `(i, j)` and `(j, i)` both become the
+/// edge between vertices `i - 1` and `j - 1`, so that the graph of a general
+/// matrix `A` is the graph of `A + Aᵀ`.  Diagonal entries are dropped.  The
+/// weight of an edge is computed by `policy` from the largest absolute value
+/// of its entries.  Pattern matrices, and the [`WeightPolicy::Unit`] policy,
+/// give graphs without edge weights.
+///
+/// Real, integer and pattern matrices are supported, with the `general`,
+/// `symmetric` and `skew-symmetric` storage schemes.
+///
+/// # Errors
+///
+/// This function returns [`IoError::Io`] if `reader` fails, and
+/// [`IoError::Parse`] if the file is not well-formed, uses the array format or
+/// complex values, or if the matrix is not square.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use metis::io::{read_matrix_market, WeightPolicy};
+///
+/// let file = "\
+/// %%MatrixMarket matrix coordinate real general
+/// % A nonsymmetric matrix.
+/// 4 4 7
+/// 1 1 4.0
+/// 1 2 -1.5
+/// 2 2 4.0
+/// 3 2 2.6
+/// 3 3 4.0
+/// 4 1 -0.4
+/// 2 4 7e0
+/// ";
+/// let data = read_matrix_market(file.as_bytes(), WeightPolicy::AbsRounded)?;
+/// assert_eq!(data.xadj, [0, 2, 5, 6, 8]);
+/// assert_eq!(data.adjncy, [1, 3, 0, 2, 3, 1, 0, 1]);
+/// assert_eq!(data.adjwgt.as_deref(), Some(&[2, 1, 2, 3, 7, 3, 1, 7][..]));
+/// assert!(metis::csr::is_symmetric(&data.xadj, &data.adjncy)?);
+/// for (v, w) in data.xadj.windows(2).enumerate() {
+///     assert!(!data.adjncy[w[0] as usize..w[1] as usize].contains(&(v as metis::Idx)));
+/// }
+///
+/// let policy = WeightPolicy::Quantize { levels: 4 };
+/// let data = read_matrix_market(file.as_bytes(), policy)?;
+/// assert_eq!(data.adjwgt.as_deref(), Some(&[2, 1, 2, 2, 4, 2, 1, 4][..]));
+///
+/// let data = read_matrix_market(file.as_bytes(), WeightPolicy::Unit)?;
+/// assert_eq!(data.adjwgt, None);
+///
+/// // 0 - 1 - 2, stored as the lower triangle of a symmetric pattern.
+/// let file = "\
+/// %%MatrixMarket matrix coordinate pattern symmetric
+/// 3 3 3
+/// 1 1
+/// 2 1
+/// 3 2
+/// ";
+/// let data = read_matrix_market(file.as_bytes(), WeightPolicy::AbsRounded)?;
+/// assert_eq!(data.xadj, [0, 1, 3, 4]);
+/// assert_eq!(data.adjncy, [1, 0, 2, 1]);
+/// assert_eq!(data.adjwgt, None);
+///
+/// let mut part = [0; 3];
+/// data.as_graph(2)?.part_recursive(&mut part)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_matrix_market(
+    reader: impl BufRead,
+    policy: WeightPolicy,
+) -> StdResult<GraphData, IoError> {
+    let mut lines = Lines::new(reader);
+    let banner = match lines.inner.next() {
+        Some(line) => line?,
+        None => return Err(IoError::parse(1, "missing header")),
+    };
+    lines.number = 1;
+    let banner: Vec<String> = banner.split_whitespace().map(str::to_lowercase).collect();
+    let banner: Vec<&str> = banner.iter().map(String::as_str).collect();
+    let (field, symmetry) = match banner[..] {
+        ["%%matrixmarket", "matrix", "coordinate", field, symmetry] => (field, symmetry),
+        ["%%matrixmarket", "matrix", _, _, _] => {
+            return Err(IoError::parse(1, "only the coordinate format is supported"))
+        }
+        _ => return Err(IoError::parse(1, "invalid header")),
+    };
+    let has_values = match field {
+        "real" | "integer" => true,
+        "pattern" => false,
+        "complex" => return Err(IoError::parse(1, "complex matrices are not supported")),
+        _ => return Err(IoError::parse(1, "invalid field type")),
+    };
+    if !matches!(symmetry, "general" | "symmetric" | "skew-symmetric") {
+        return Err(IoError::parse(1, "invalid symmetry"));
+    }
+
+    let size = loop {
+        let line = lines
+            .next()?
+            .ok_or_else(|| IoError::parse(lines.number, "missing size line"))?;
+        if !line.trim().is_empty() {
+            break line;
+        }
+    };
+    let size: Vec<u64> = size
+        .split_whitespace()
+        .map(|token| token.parse())
+        .collect::<StdResult<_, _>>()
+        .map_err(|_| IoError::parse(lines.number, "invalid integer"))?;
+    let (nrows, ncols, nnz) = match size[..] {
+        [nrows, ncols, nnz] => (nrows, ncols, nnz),
+        _ => return Err(IoError::parse(lines.number, "size line must have 3 values")),
+    };
+    if nrows != ncols {
+        return Err(IoError::parse(lines.number, "the matrix is not square"));
+    }
+    let nvtxs =
+        Idx::try_from(nrows).map_err(|_| IoError::parse(lines.number, "matrix is too large"))?;
+
+    let mut entries = Vec::new();
+    let mut max = 0.0_f64;
+    let mut count = 0;
+    while let Some(line) = lines.next()? {
+        let mut tokens = line.split_whitespace();
+        let (i, j) = match (tokens.next(), tokens.next()) {
+            (Some(i), Some(j)) => (i, j),
+            (None, _) => continue,
+            (Some(_), None) => return Err(IoError::parse(lines.number, "missing column index")),
+        };
+        let index = |token: &str| match token.parse::<Idx>() {
+            Ok(index) if 1 <= index && index <= nvtxs => Ok(index - 1),
+            Ok(_) => Err(IoError::parse(lines.number, "index out of bounds")),
+            Err(_) => Err(IoError::parse(lines.number, "invalid integer")),
+        };
+        let (i, j) = (index(i)?, index(j)?);
+        let value = if has_values {
+            let value = tokens
+                .next()
+                .ok_or_else(|| IoError::parse(lines.number, "missing value"))?;
+            match value.parse::<f64>() {
+                Ok(value) if value.is_finite() => value.abs(),
+                _ => return Err(IoError::parse(lines.number, "invalid value")),
+            }
+        } else {
+            1.0
+        };
+        if tokens.next().is_some() {
+            return Err(IoError::parse(lines.number, "too many values"));
+        }
+        count += 1;
+        if count > nnz {
+            return Err(IoError::parse(lines.number, "more entries than announced"));
+        }
+        if i != j {
+            max = f64::max(max, value);
+            entries.push((i, j, value));
+        }
+    }
+    if count != nnz {
+        return Err(IoError::parse(lines.number, "fewer entries than announced"));
+    }
+
+    // Weights only grow with values, so that the weight of an edge is the
+    // weight of its largest entry.
+    let arcs = entries
+        .into_iter()
+        .flat_map(|(i, j, value)| {
+            let weight = policy.apply(value, max);
+            [(i, j, weight), (j, i, weight)]
+        })
+        .collect();
+    let (xadj, adjncy, adjwgt) = csr::from_arcs(nvtxs, arcs, |_, a, b| Ok(Idx::max(a, b)))?;
+    let weighted = has_values && policy != WeightPolicy::Unit;
+
+    Ok(GraphData {
+        ncon: 1,
+        xadj,
+        adjncy,
+        vwgt: None,
+        vsize: None,
+        adjwgt: weighted.then_some(adjwgt),
+    })
+}
+
+/// How [`read_edge_list`] maps the vertex identifiers of a file to vertices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexIds {
+    /// Identifiers are vertex indices, starting from zero.
+    ZeroBased,
+
+    /// Identifiers are vertex indices, starting from one.
+    OneBased,
+
+    /// Identifiers are arbitrary integers, and vertices are numbered from zero
+    /// in the order of their identifiers.
+    Renumber,
+}
+
+/// Settings of [`read_edge_list`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdgeListOptions {
+    /// The column separator, or `None` for any whitespace.
+    pub delimiter: Option<char>,
+
+    /// Whether the first line holds column names.
+    pub has_header: bool,
+
+    /// How identifiers are mapped to vertices.
+    pub ids: VertexIds,
+
+    /// Whether to add the reverse `(v, u)` of each edge `(u, v)`.  Otherwise,
+    /// the file must list each edge in both directions.
+    pub symmetrize: bool,
+
+    /// How to combine the weights of edges that appear several times.
+    pub merge: WeightMerge,
+
+    /// How to turn the third column into edge weights.
+    pub weights: WeightPolicy,
+}
+
+impl Default for EdgeListOptions {
+    /// Whitespace-separated columns without header, renumbered identifiers,
+    /// symmetrized edges whose weight is the largest rounded value.
+    fn default() -> Self {
+        EdgeListOptions {
+            delimiter: None,
+            has_header: false,
+            ids: VertexIds::Renumber,
+            symmetrize: true,
+            merge: WeightMerge::Max,
+            weights: WeightPolicy::AbsRounded,
+        }
+    }
+}
+
+/// A graph read from an edge list, see [`read_edge_list`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeListGraph {
+    /// The graph, with vertices numbered from zero.
+    pub graph: GraphData,
+
+    /// The identifier of each vertex in the file.
+    pub ids: Vec<i64>,
+}
+
+/// Reads a graph given as a list of edges, one per line, such as a CSV file.
+///
+/// Each line holds the identifiers of the two ends of an edge, and optionally
+/// a weight.  Lines that start with `%` or `#` are comments, and blank lines
+/// are ignored.  Self-loops are dropped.  Edges without weight give a graph
+/// without edge weights.
+///
+/// # Errors
+///
+/// This function returns [`IoError::Io`] if `reader` fails, and
+/// [`IoError::Parse`] if the file is not well-formed, e.g. if lines do not all
+/// have the same number of columns, or if identifiers do not match
+/// `options.ids`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use metis::io::{read_edge_list, EdgeListOptions};
+///
+/// let file = "\
+/// src\tdst\tweight
+/// 10\t20\t1.5
+/// 20\t35\t2
+/// 35\t10\t0.4
+/// ## A comment.
+/// 35\t100\t3
+/// ";
+/// let options = EdgeListOptions {
+///     delimiter: Some('\t'),
+///     has_header: true,
+///     ..EdgeListOptions::default()
+/// };
+/// let edges = read_edge_list(file.as_bytes(), options)?;
+/// assert_eq!(edges.ids, [10, 20, 35, 100]);
+/// assert_eq!(edges.graph.xadj, [0, 2, 4, 7, 8]);
+/// assert_eq!(edges.graph.adjncy, [1, 2, 0, 2, 0, 1, 3, 2]);
+/// assert_eq!(edges.graph.adjwgt.as_deref(), Some(&[2, 1, 2, 2, 1, 2, 3, 3][..]));
+///
+/// let mut part = [0; 4];
+/// edges.graph.as_graph(2)?.part_kway(&mut part)?;
+///
+/// // One-based identifiers are kept as vertex indices.
+/// let options = EdgeListOptions {
+///     delimiter: Some(','),
+///     ids: metis::io::VertexIds::OneBased,
+///     ..EdgeListOptions::default()
+/// };
+/// let edges = read_edge_list("1,3\n3,4\n".as_bytes(), options)?;
+/// assert_eq!(edges.ids, [1, 2, 3, 4]);
+/// assert_eq!(edges.graph.xadj, [0, 1, 1, 3, 4]);
+/// assert_eq!(edges.graph.adjwgt, None);
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_edge_list(
+    reader: impl BufRead,
+    options: EdgeListOptions,
+) -> StdResult<EdgeListGraph, IoError> {
+    let mut lines = Lines::new(reader);
+    if options.has_header {
+        lines.expect("missing header")?;
+    }
+
+    let mut rows = Vec::new();
+    let mut has_weights = None;
+    while let Some(line) = lines.next()? {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let columns: Vec<&str> = match options.delimiter {
+            Some(delimiter) => line.split(delimiter).map(str::trim).collect(),
+            None => line.split_whitespace().collect(),
+        };
+        if columns.len() != 2 && columns.len() != 3 {
+            return Err(IoError::parse(
+                lines.number,
+                "lines must have 2 or 3 columns",
+            ));
+        }
+        if *has_weights.get_or_insert(columns.len() == 3) != (columns.len() == 3) {
+            return Err(IoError::parse(
+                lines.number,
+                "lines have different numbers of columns",
+            ));
+        }
+        let ends: Vec<i64> = lines.parse_tokens(columns[..2].iter().copied())?;
+        let value = match columns.get(2) {
+            Some(value) => match value.parse::<f64>() {
+                Ok(value) if value.is_finite() => value.abs(),
+                _ => return Err(IoError::parse(lines.number, "invalid weight")),
+            },
+            None => 1.0,
+        };
+        let (lo, hi) = match options.ids {
+            VertexIds::ZeroBased => (0, i64::from(Idx::MAX) - 1),
+            VertexIds::OneBased => (1, i64::from(Idx::MAX)),
+            VertexIds::Renumber => (i64::MIN, i64::MAX),
+        };
+        if ends.iter().any(|id| *id < lo || hi < *id) {
+            return Err(IoError::parse(lines.number, "identifier out of bounds"));
+        }
+        rows.push((ends[0], ends[1], value));
+    }
+
+    let ids: Vec<i64> = match options.ids {
+        VertexIds::ZeroBased | VertexIds::OneBased => {
+            let base = if options.ids == VertexIds::OneBased {
+                1
+            } else {
+                0
+            };
+            let max = rows.iter().map(|(u, v, _)| i64::max(*u, *v)).max();
+            (base..max.map_or(base, |max| max + 1)).collect()
+        }
+        VertexIds::Renumber => {
+            let mut ids: Vec<i64> = rows.iter().flat_map(|(u, v, _)| [*u, *v]).collect();
+            ids.sort_unstable();
+            ids.dedup();
+            ids
+        }
+    };
+    let nvtxs =
+        Idx::try_from(ids.len()).map_err(|_| IoError::parse(lines.number, "graph is too large"))?;
+    let vertex = |id: i64| match options.ids {
+        VertexIds::ZeroBased => id as Idx,
+        VertexIds::OneBased => (id - 1) as Idx,
+        VertexIds::Renumber => ids.binary_search(&id).unwrap() as Idx,
+    };
+
+    let max = rows
+        .iter()
+        .filter(|(u, v, _)| u != v)
+        .fold(0.0, |max, (_, _, value)| f64::max(max, *value));
+    let mut arcs = Vec::with_capacity(2 * rows.len());
+    for (u, v, value) in rows {
+        if u == v {
+            continue;
+        }
+        let (u, v) = (vertex(u), vertex(v));
+        let weight = options.weights.apply(value, max);
+        arcs.push((u, v, weight));
+        if options.symmetrize {
+            arcs.push((v, u, weight));
+        }
+    }
+    let merge = options.merge;
+    let (xadj, adjncy, adjwgt) = csr::from_arcs(nvtxs, arcs, |_, a, b| match merge {
+        WeightMerge::Sum => a.checked_add(b).ok_or(NewGraphError::TooLarge),
+        _ => Ok(merge.apply(a, b)),
+    })?;
+    let weighted = has_weights == Some(true) && options.weights != WeightPolicy::Unit;
+
+    Ok(EdgeListGraph {
+        graph: GraphData {
+            ncon: 1,
+            xadj,
+            adjncy,
+            vwgt: None,
+            vsize: None,
+            adjwgt: weighted.then_some(adjwgt),
+        },
+        ids,
+    })
+}
+
+/// A mesh read from a Gmsh file, see [`read_gmsh`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GmshMesh {
+    /// The selected elements, with nodes numbered from zero.
+    pub mesh: MeshData,
+
+    /// The Gmsh tag of each node of `mesh`.
+    pub node_tags: Vec<usize>,
+
+    /// The Gmsh tag of each element of `mesh`.
+    pub element_tags: Vec<usize>,
+}
+
+/// Reads the elements of highest dimension of a mesh in the MSH 4.1 ASCII
+/// format of Gmsh.
+///
+/// Lower-dimension elements, such as the faces of a volume mesh, are left out.
+/// Nodes that belong to the selected elements are numbered from zero in the
+/// order of their tags, and unused nodes are left out.  Elements keep all
+/// their nodes, including those of high-order elements.
+///
+/// # Errors
+///
+/// This function returns [`IoError::Io`] if `reader` fails, and
+/// [`IoError::Parse`] if the file is not well-formed, is not an MSH 4.1 ASCII
+/// file, or if elements refer to nodes that are not in the `$Nodes` section.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use metis::io::{read_gmsh, read_gmsh_elements};
+///
+/// // Two tetrahedra sharing a face, a triangle and a point.
+/// let file = "\
+/// $MeshFormat
+/// 4.1 0 8
+/// $EndMeshFormat
+/// $PhysicalNames
+/// 1
+/// 3 1 \"volume\"
+/// $EndPhysicalNames
+/// $Nodes
+/// 2 6 10 60
+/// 3 1 0 5
+/// 10
+/// 20
+/// 30
+/// 40
+/// 50
+/// 0 0 0
+/// 1 0 0
+/// 0 1 0
+/// 0 0 1
+/// 0 0 -1
+/// 2 1 0 1
+/// 60
+/// 1 1 0
+/// $EndNodes
+/// $Elements
+/// 3 4 1 4
+/// 0 1 15 1
+/// 1 10
+/// 2 1 2 1
+/// 2 20 30 60
+/// 3 1 4 2
+/// 3 10 20 30 40
+/// 4 10 30 20 50
+/// $EndElements
+/// ";
+///
+/// let gmsh = read_gmsh(file.as_bytes())?;
+/// assert_eq!(gmsh.mesh.eptr, [0, 4, 8]);
+/// assert_eq!(gmsh.mesh.eind, [0, 1, 2, 3, 0, 2, 1, 4]);
+/// assert_eq!(gmsh.node_tags, [10, 20, 30, 40, 50]);
+/// assert_eq!(gmsh.element_tags, [3, 4]);
+///
+/// let mut epart = [0; 2];
+/// let mut npart = [0; 5];
+/// gmsh.mesh.as_mesh(2)?.part_dual(&mut epart, &mut npart)?;
+///
+/// // Only triangles.
+/// let gmsh = read_gmsh_elements(file.as_bytes(), &[2])?;
+/// assert_eq!(gmsh.mesh.eptr, [0, 3]);
+/// assert_eq!(gmsh.mesh.eind, [0, 1, 2]);
+/// assert_eq!(gmsh.node_tags, [20, 30, 60]);
+/// assert_eq!(gmsh.element_tags, [2]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_gmsh(reader: impl BufRead) -> StdResult<GmshMesh, IoError> {
+    gmsh(reader, None)
+}
+
+/// Same as [`read_gmsh`], except that only the elements whose Gmsh type is in
+/// `element_types` are read, e.g. `&[2, 3]` for triangles and quadrangles.
+///
+/// # Errors
+///
+/// This function returns the same errors as [`read_gmsh`].
+pub fn read_gmsh_elements(
+    reader: impl BufRead,
+    element_types: &[i32],
+) -> StdResult<GmshMesh, IoError> {
+    gmsh(reader, Some(element_types))
+}
+
+/// An element of a Gmsh file.
+struct GmshElement {
+    dim: i64,
+    element_type: i64,
+    tag: usize,
+    nodes: Vec<usize>,
+}
+
+/// Reads a Gmsh file, keeping the elements of the given types, or of highest
+/// dimension.
+fn gmsh(reader: impl BufRead, element_types: Option<&[i32]>) -> StdResult<GmshMesh, IoError> {
+    const EOF: &str = "unexpected end of file";
+
+    let mut lines = Lines::new(reader);
+    let mut has_format = false;
+    let mut nodes: Option<Vec<usize>> = None;
+    let mut elements = Vec::new();
+    while let Some(line) = lines.next()? {
+        let section = match line.trim().strip_prefix('$') {
+            Some(section) => section.to_owned(),
+            None if line.trim().is_empty() => continue,
+            None => return Err(IoError::parse(lines.number, "expected a section")),
+        };
+        match section.as_str() {
+            "MeshFormat" => {
+                let line = lines.expect(EOF)?;
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                if fields.len() != 3 || fields[0] != "4.1" {
+                    return Err(IoError::parse(lines.number, "only MSH 4.1 is supported"));
+                }
+                if fields[1] != "0" {
+                    return Err(IoError::parse(
+                        lines.number,
+                        "only ASCII files are supported",
+                    ));
+                }
+                has_format = true;
+            }
+            "Nodes" => {
+                let header: Vec<usize> = lines.expect_values(EOF)?;
+                if header.len() != 4 {
+                    return Err(IoError::parse(lines.number, "invalid $Nodes header"));
+                }
+                let mut tags = Vec::new();
+                for _ in 0..header[0] {
+                    let block: Vec<usize> = lines.expect_values(EOF)?;
+                    if block.len() != 4 {
+                        return Err(IoError::parse(lines.number, "invalid node block"));
+                    }
+                    for _ in 0..block[3] {
+                        match lines.expect_values(EOF)?[..] {
+                            [tag] => tags.push(tag),
+                            _ => return Err(IoError::parse(lines.number, "expected a node tag")),
+                        }
+                    }
+                    // Coordinates are not needed.
+                    for _ in 0..block[3] {
+                        lines.expect(EOF)?;
+                    }
+                }
+                tags.sort_unstable();
+                nodes = Some(tags);
+            }
+            "Elements" => {
+                let nodes = nodes
+                    .as_ref()
+                    .ok_or_else(|| IoError::parse(lines.number, "$Elements before $Nodes"))?;
+                let header: Vec<usize> = lines.expect_values(EOF)?;
+                if header.len() != 4 {
+                    return Err(IoError::parse(lines.number, "invalid $Elements header"));
+                }
+                for _ in 0..header[0] {
+                    let block: Vec<i64> = lines.expect_values(EOF)?;
+                    if block.len() != 4 || block[3] < 0 {
+                        return Err(IoError::parse(lines.number, "invalid element block"));
+                    }
+                    for _ in 0..block[3] {
+                        let line: Vec<usize> = lines.expect_values(EOF)?;
+                        if line.len() < 2 {
+                            return Err(IoError::parse(lines.number, "element without nodes"));
+                        }
+                        if line[1..]
+                            .iter()
+                            .any(|node| nodes.binary_search(node).is_err())
+                        {
+                            return Err(IoError::parse(lines.number, "unknown node"));
+                        }
+                        elements.push(GmshElement {
+                            dim: block[0],
+                            element_type: block[2],
+                            tag: line[0],
+                            nodes: line[1..].to_vec(),
+                        });
+                    }
+                }
+            }
+            _ => {
+                // Skip unused sections.
+                let end = format!("$End{section}");
+                while lines.expect(EOF)?.trim() != end {}
+                continue;
+            }
+        }
+        if lines.expect(EOF)?.trim() != format!("$End{section}") {
+            return Err(IoError::parse(lines.number, "missing end of section"));
+        }
+    }
+    if !has_format {
+        return Err(IoError::parse(lines.number, "missing $MeshFormat section"));
+    }
+
+    let max_dim = elements.iter().map(|e| e.dim).max();
+    let selected = |element: &GmshElement| match element_types {
+        Some(types) => types.iter().any(|t| i64::from(*t) == element.element_type),
+        None => Some(element.dim) == max_dim,
+    };
+    let elements: Vec<&GmshElement> = elements.iter().filter(|e| selected(e)).collect();
+
+    let mut node_tags: Vec<usize> = elements.iter().flat_map(|e| &e.nodes).copied().collect();
+    node_tags.sort_unstable();
+    node_tags.dedup();
+    let too_large = || IoError::parse(lines.number, "mesh is too large");
+    Idx::try_from(node_tags.len()).map_err(|_| too_large())?;
+
+    let mut eptr = Vec::with_capacity(elements.len() + 1);
+    let mut eind = Vec::new();
+    eptr.push(0);
+    for element in &elements {
+        for node in &element.nodes {
+            eind.push(node_tags.binary_search(node).unwrap() as Idx);
+        }
+        eptr.push(Idx::try_from(eind.len()).map_err(|_| too_large())?);
+    }
+
+    Ok(GmshMesh {
+        mesh: MeshData {
+            ncon: 1,
+            eptr,
+            eind,
+            ewgt: None,
+        },
+        node_tags,
+        element_tags: elements.iter().map(|e| e.tag).collect(),
+    })
+}
+
+/// Reads a graph in the DIMACS format of shortest-path problems, such as the
+/// road networks of the 9th DIMACS challenge.
+///
+/// The file holds a `p sp n m` line with the number of vertices and arcs, and
+/// `a u v w` lines for each arc from `u` to `v` of weight `w`.  Lines that
+/// start with `c` are comments.  Arcs are made undirected, self-loops are
+/// dropped, and the weights of arcs between the same vertices, in either
+/// direction, are combined with `merge`.
+///
+/// # Errors
+///
+/// This function returns [`IoError::Io`] if `reader` fails, and
+/// [`IoError::Parse`] if the file is not well-formed, e.g. if vertices are out
+/// of bounds, weights are not strictly positive, or the number of arcs does
+/// not match the header.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use metis::csr::WeightMerge;
+/// use metis::io::read_dimacs;
+///
+/// let file = "\
+/// c Arcs 1 -> 2 and 2 -> 1 have different weights.
+/// p sp 4 6
+/// a 1 2 3
+/// a 2 1 5
+/// a 2 3 2
+/// a 4 3 7
+/// a 1 4 1
+/// a 3 3 4
+/// ";
+/// let data = read_dimacs(file.as_bytes(), WeightMerge::Min)?;
+/// assert_eq!(data.xadj, [0, 2, 4, 6, 8]);
+/// assert_eq!(data.adjncy, [1, 3, 0, 2, 1, 3, 0, 2]);
+/// assert_eq!(data.adjwgt.as_deref(), Some(&[3, 1, 3, 2, 2, 7, 1, 7][..]));
+/// assert!(metis::csr::is_symmetric(&data.xadj, &data.adjncy)?);
+///
+/// let data = read_dimacs(file.as_bytes(), WeightMerge::Sum)?;
+/// assert_eq!(data.adjwgt.as_deref(), Some(&[8, 1, 8, 2, 2, 7, 1, 7][..]));
+///
+/// let mut part = [0; 4];
+/// data.as_graph(2)?.part_kway(&mut part)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_dimacs(reader: impl BufRead, merge: WeightMerge) -> StdResult<GraphData, IoError> {
+    let mut lines = Lines::new(reader);
+    let mut size = None;
+    let mut narcs = 0;
+    let mut arcs = Vec::new();
+    while let Some(line) = lines.next()? {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            None | Some("c") => {}
+            Some("p") => {
+                if size.is_some() {
+                    return Err(IoError::parse(lines.number, "duplicate problem line"));
+                }
+                // The problem name, e.g. "sp", is not checked.
+                let values: Vec<Idx> = lines.parse_tokens(tokens.skip(1))?;
+                match values[..] {
+                    [n, m] if 0 <= n && 0 <= m => size = Some((n, m)),
+                    [_, _] => {
+                        return Err(IoError::parse(
+                            lines.number,
+                            "negative size in problem line",
+                        ))
+                    }
+                    _ => {
+                        return Err(IoError::parse(
+                            lines.number,
+                            "problem line must have 3 values",
+                        ))
+                    }
+                }
+            }
+            Some("a") => {
+                let (nvtxs, _) =
+                    size.ok_or_else(|| IoError::parse(lines.number, "arc before problem line"))?;
+                let values: Vec<Idx> = lines.parse_tokens(tokens)?;
+                let (u, v, w) = match values[..] {
+                    [u, v, w] => (u, v, w),
+                    _ => return Err(IoError::parse(lines.number, "arc line must have 3 values")),
+                };
+                if u < 1 || nvtxs < u || v < 1 || nvtxs < v {
+                    return Err(IoError::parse(lines.number, "vertex out of bounds"));
+                }
+                if w <= 0 {
+                    return Err(IoError::parse(
+                        lines.number,
+                        "weight is not strictly positive",
+                    ));
+                }
+                narcs += 1;
+                if u != v {
+                    arcs.push((u - 1, v - 1, w));
+                    arcs.push((v - 1, u - 1, w));
+                }
+            }
+            Some(_) => return Err(IoError::parse(lines.number, "unknown line type")),
+        }
+    }
+    let (nvtxs, nedges) =
+        size.ok_or_else(|| IoError::parse(lines.number, "missing problem line"))?;
+    if narcs != i64::from(nedges) {
+        return Err(IoError::parse(
+            lines.number,
+            "the number of arcs does not match the header",
+        ));
+    }
+
+    let (xadj, adjncy, adjwgt) = csr::from_arcs(nvtxs, arcs, |_, a, b| match merge {
+        WeightMerge::Sum => a.checked_add(b).ok_or(NewGraphError::TooLarge),
+        _ => Ok(merge.apply(a, b)),
+    })?;
+    Ok(GraphData {
+        ncon: 1,
+        xadj,
+        adjncy,
+        vwgt: None,
+        vsize: None,
+        adjwgt: Some(adjwgt),
+    })
+}
+
+/// The shape of a cell, for [`write_vtu`].
+#[cfg(feature = "vtk")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellType {
+    /// A triangle, with 3 nodes.
+    Triangle,
+
+    /// A quadrangle, with 4 nodes.
+    Quad,
+
+    /// A tetrahedron, with 4 nodes.
+    Tetra,
+
+    /// A hexahedron, with 8 nodes.
+    Hexahedron,
+}
+
+#[cfg(feature = "vtk")]
+impl CellType {
+    /// The number of nodes of a cell of this type.
+    pub fn node_count(self) -> usize {
+        match self {
+            Self::Triangle => 3,
+            Self::Quad | Self::Tetra => 4,
+            Self::Hexahedron => 8,
+        }
+    }
+
+    /// The identifier of this type in VTK files.
+    fn vtk_id(self) -> u8 {
+        match self {
+            Self::Triangle => 5,
+            Self::Quad => 9,
+            Self::Tetra => 10,
+            Self::Hexahedron => 12,
+        }
+    }
+}
+
+/// Writes a partitioned mesh in the ASCII XML format of VTK (`.vtu`), for
+/// ParaView and other VTK-based tools.
+///
+/// The mesh is given in the format of [`Mesh::new`], with the coordinates of
+/// its nodes in `points` and the shape of its elements in `cell_types`.  The
+/// part of each element is written as the `epart` cell array, and the part of
+/// each node, if given, as the `npart` point array.  Only available with the
+/// `vtk` feature.
+///
+/// # Errors
+///
+/// This function returns [`IoError::Mesh`] if the mesh structure is not
+/// valid, if elements do not have the number of nodes of their type, if nodes
+/// are not within zero and the number of points, or if the lengths of
+/// `cell_types`, `epart` and `npart` do not match the mesh.  It returns
+/// [`IoError::Io`] if `writer` fails.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use metis::io::{write_vtu, CellType};
+///
+/// // A quadrangle and a triangle.
+/// //  3 --- 2
+/// //  |     | \
+/// //  |  0  | 1 \
+/// //  0 --- 1 --- 4
+/// let points = &[
+///     [0.0, 0.0, 0.0],
+///     [1.0, 0.0, 0.0],
+///     [1.0, 1.0, 0.0],
+///     [0.0, 1.0, 0.0],
+///     [2.0, 0.0, 0.5],
+/// ];
+/// let eptr = &[0, 4, 7];
+/// let eind = &[0, 1, 2, 3, 1, 4, 2];
+/// let cell_types = &[CellType::Quad, CellType::Triangle];
+///
+/// let mut file = Vec::new();
+/// write_vtu(&mut file, points, eptr, eind, cell_types, &[0, 1], Some(&[0, 0, 0, 0, 1]))?;
+/// let golden = r#"<?xml version="1.0"?>
+/// <VTKFile type="UnstructuredGrid" version="0.1" byte_order="LittleEndian">
+///   <UnstructuredGrid>
+///     <Piece NumberOfPoints="5" NumberOfCells="2">
+///       <PointData Scalars="npart">
+///         <DataArray type="Int32" Name="npart" format="ascii">
+///           0
+///           0
+///           0
+///           0
+///           1
+///         </DataArray>
+///       </PointData>
+///       <CellData Scalars="epart">
+///         <DataArray type="Int32" Name="epart" format="ascii">
+///           0
+///           1
+///         </DataArray>
+///       </CellData>
+///       <Points>
+///         <DataArray type="Float64" NumberOfComponents="3" format="ascii">
+///           0.0 0.0 0.0
+///           1.0 0.0 0.0
+///           1.0 1.0 0.0
+///           0.0 1.0 0.0
+///           2.0 0.0 0.5
+///         </DataArray>
+///       </Points>
+///       <Cells>
+///         <DataArray type="Int32" Name="connectivity" format="ascii">
+///           0 1 2 3
+///           1 4 2
+///         </DataArray>
+///         <DataArray type="Int32" Name="offsets" format="ascii">
+///           4
+///           7
+///         </DataArray>
+///         <DataArray type="UInt8" Name="types" format="ascii">
+///           9
+///           5
+///         </DataArray>
+///       </Cells>
+///     </Piece>
+///   </UnstructuredGrid>
+/// </VTKFile>
+/// "#;
+/// assert_eq!(String::from_utf8(file)?, golden);
+///
+/// // The triangle does not have the 4 nodes of a tetrahedron.
+/// let cell_types = &[CellType::Quad, CellType::Tetra];
+/// assert!(write_vtu(Vec::new(), points, eptr, eind, cell_types, &[0, 1], None).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "vtk")]
+pub fn write_vtu(
+    writer: impl Write,
+    points: &[[f64; 3]],
+    eptr: &[Idx],
+    eind: &[Idx],
+    cell_types: &[CellType],
+    epart: &[Idx],
+    npart: Option<&[Idx]>,
+) -> StdResult<(), IoError> {
+    use crate::StructureErrorKind;
+
+    let (ne, _) = check_mesh_structure(eptr, eind)?;
+    let ne = ne as usize;
+    NewMeshError::check_len("cell_types", ne, cell_types.len())?;
+    NewMeshError::check_len("epart", ne, epart.len())?;
+    if let Some(npart) = npart {
+        NewMeshError::check_len("npart", points.len(), npart.len())?;
+    }
+    for (i, (w, cell_type)) in eptr.windows(2).zip(cell_types).enumerate() {
+        let expected = cell_type.node_count() as Idx;
+        if w[1] - w[0] != expected {
+            return Err(NewMeshError::at(
+                StructureErrorKind::LengthMismatch,
+                "elements do not have the number of nodes of their type",
+                "eptr",
+                i + 1,
+                w[1],
+                w[0] + expected..=w[0] + expected,
+            )
+            .into());
+        }
+    }
+    let npoints = Idx::try_from(points.len()).map_err(|_| NewMeshError::TooLarge)?;
+    if let Some(index) = eind.iter().position(|node| npoints <= *node) {
+        return Err(NewMeshError::at(
+            StructureErrorKind::OutOfBounds,
+            "values in the node index are out of bounds",
+            "eind",
+            index,
+            eind[index],
+            0..=npoints - 1,
+        )
+        .into());
+    }
+
+    let int_type = if std::mem::size_of::<Idx>() == 4 {
+        "Int32"
+    } else {
+        "Int64"
+    };
+    let mut w = io::BufWriter::new(writer);
+    writeln!(w, r#"<?xml version="1.0"?>"#)?;
+    writeln!(
+        w,
+        r#"<VTKFile type="UnstructuredGrid" version="0.1" byte_order="LittleEndian">"#
+    )?;
+    writeln!(w, "  <UnstructuredGrid>")?;
+    writeln!(
+        w,
+        r#"    <Piece NumberOfPoints="{}" NumberOfCells="{ne}">"#,
+        points.len(),
+    )?;
+    if let Some(npart) = npart {
+        writeln!(w, r#"      <PointData Scalars="npart">"#)?;
+        writeln!(
+            w,
+            r#"        <DataArray type="{int_type}" Name="npart" format="ascii">"#
+        )?;
+        for p in npart {
+            writeln!(w, "          {p}")?;
+        }
+        writeln!(w, "        </DataArray>")?;
+        writeln!(w, "      </PointData>")?;
+    }
+    writeln!(w, r#"      <CellData Scalars="epart">"#)?;
+    writeln!(
+        w,
+        r#"        <DataArray type="{int_type}" Name="epart" format="ascii">"#
+    )?;
+    for p in epart {
+        writeln!(w, "          {p}")?;
+    }
+    writeln!(w, "        </DataArray>")?;
+    writeln!(w, "      </CellData>")?;
+    writeln!(w, "      <Points>")?;
+    writeln!(
+        w,
+        r#"        <DataArray type="Float64" NumberOfComponents="3" format="ascii">"#
+    )?;
+    for [x, y, z] in points {
+        writeln!(w, "          {x:?} {y:?} {z:?}")?;
+    }
+    writeln!(w, "        </DataArray>")?;
+    writeln!(w, "      </Points>")?;
+    writeln!(w, "      <Cells>")?;
+    writeln!(
+        w,
+        r#"        <DataArray type="{int_type}" Name="connectivity" format="ascii">"#
+    )?;
+    for nodes in eptr.windows(2) {
+        let nodes = &eind[nodes[0] as usize..nodes[1] as usize];
+        let nodes: Vec<String> = nodes.iter().map(Idx::to_string).collect();
+        writeln!(w, "          {}", nodes.join(" "))?;
+    }
+    writeln!(w, "        </DataArray>")?;
+    writeln!(
+        w,
+        r#"        <DataArray type="{int_type}" Name="offsets" format="ascii">"#
+    )?;
+    for offset in &eptr[1..] {
+        writeln!(w, "          {offset}")?;
+    }
+    writeln!(w, "        </DataArray>")?;
+    writeln!(
+        w,
+        r#"        <DataArray type="UInt8" Name="types" format="ascii">"#
+    )?;
+    for cell_type in cell_types {
+        writeln!(w, "          {}", cell_type.vtk_id())?;
+    }
+    writeln!(w, "        </DataArray>")?;
+    writeln!(w, "      </Cells>")?;
+    writeln!(w, "    </Piece>")?;
+    writeln!(w, "  </UnstructuredGrid>")?;
+    writeln!(w, "</VTKFile>")?;
+    w.flush()?;
+    Ok(())
+}
@@ -0,0 +1,347 @@
+//! Partitioning in a child process, so that crashes inside METIS do not take
+//! the caller down.
+//!
+//! Only available on Unix with the `isolated` feature.
+
+use crate::lock_metis;
+use crate::option;
+use crate::Error;
+use crate::Graph;
+use crate::Idx;
+use crate::Result;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::io::Read as _;
+use std::mem;
+use std::os::unix::io::AsRawFd as _;
+use std::os::unix::io::FromRawFd as _;
+use std::panic;
+use std::slice;
+
+/// Status codes written by the child before the partition.
+const STATUS_OK: u8 = 0;
+const STATUS_INPUT: u8 = 1;
+const STATUS_MEMORY: u8 = 2;
+const STATUS_OTHER: u8 = 3;
+
+/// How the child process of [`Graph::part_kway_isolated`] or
+/// [`Graph::part_recursive_isolated`] ended, when it did not send a partition
+/// back.
+///
+/// This is the source of the [`Error::Other`] returned in that case.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::{ChildError, Error, Graph};
+/// use std::error::Error as _;
+///
+/// // 0 - 1 - 2
+/// let xadj = &[0, 1, 3, 4];
+/// let adjncy = &[1, 0, 2, 1];
+/// let mut graph = Graph::new(1, 2, xadj, adjncy)?;
+/// let mut part = [0; 3];
+/// let child_error = |err: &Error| err.source()?.downcast_ref::<ChildError>().copied();
+///
+/// // The child crashes, as it would on a segmentation fault inside METIS.
+/// let err = graph.part_isolated(&mut part, |_, _| std::process::abort()).unwrap_err();
+/// assert_eq!(err, Error::Other(None));
+/// assert_eq!(child_error(&err), Some(ChildError::Signaled(6))); // SIGABRT
+///
+/// // The child panics.
+/// let err = graph.part_isolated(&mut part, |_, _| panic!("oops")).unwrap_err();
+/// assert_eq!(child_error(&err), Some(ChildError::Exited(1)));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChildError {
+    /// The child exited with the given status without sending a partition
+    /// back, e.g. because it panicked.
+    Exited(i32),
+
+    /// The child was terminated by the given signal, e.g. `SIGSEGV` after a
+    /// crash or `SIGKILL` when it was killed.
+    Signaled(i32),
+}
+
+impl fmt::Display for ChildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChildError::Exited(status) => {
+                write!(f, "METIS child process exited with status {status}")
+            }
+            ChildError::Signaled(signal) => {
+                write!(f, "METIS child process was terminated by signal {signal}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChildError {}
+
+impl<'a> Graph<'a> {
+    /// Partition the graph using multilevel recursive bisection, in a child
+    /// process.
+    ///
+    /// See [`Graph::part_kway_isolated`].
+    pub fn part_recursive_isolated(&mut self, part: &mut [Idx]) -> Result<Idx> {
+        run_isolated(self, part, Graph::try_part_recursive)
+    }
+
+    /// Partition the graph using multilevel k-way partitioning, in a child
+    /// process.
+    ///
+    /// The process is forked, so that the child sees the input arrays without
+    /// copying them, and METIS runs in the child.  The partition is sent back
+    /// through a pipe.  Should the child crash (a segmentation fault, a stack
+    /// overflow...) or be killed, the caller gets [`Error::Other`] instead of
+    /// crashing as well, with a [`ChildError`] as source.  Only available on
+    /// Unix with the `isolated` feature.
+    ///
+    /// On top of the partitioning itself, each call costs a `fork`, which is
+    /// proportional to the memory mapped by the caller (page tables are
+    /// copied, not pages), and the transfer of `part` through a pipe.  This is
+    /// negligible for large graphs, but dominates for small ones.
+    ///
+    /// The `isolated` feature enables `global-lock`, and the lock is held
+    /// while forking, so that no other thread is inside METIS at that time.
+    /// The child then only runs METIS and writes to the pipe.  METIS allocates
+    /// memory, so the memory allocator must support `fork` in multi-threaded
+    /// processes, as the one of the GNU C library does.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [`Graph::try_part_kway`],
+    /// except that the sources of [`Error::Input`] and [`Error::Other`] are
+    /// only kept for errors detected before forking.  It returns
+    /// [`Error::Other`] if the child cannot be created, or does not exit
+    /// normally.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::Graph;
+    ///
+    /// // 0 - 1 - 2 - 3
+    /// let xadj = &[0, 1, 3, 5, 6];
+    /// let adjncy = &[1, 0, 2, 1, 3, 2];
+    /// let mut part = [0; 4];
+    /// let edgecut = Graph::new(1, 2, xadj, adjncy)?.part_kway_isolated(&mut part)?;
+    /// assert_eq!(edgecut, 1);
+    /// assert_eq!(part[0], part[1]);
+    /// assert_ne!(part[1], part[2]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// See [`ChildError`] for what happens when the child is killed.
+    pub fn part_kway_isolated(&mut self, part: &mut [Idx]) -> Result<Idx> {
+        run_isolated(self, part, Graph::try_part_kway)
+    }
+
+    /// Partition the graph with `f`, in a child process.
+    ///
+    /// This runs any partitioning routine like [`Graph::part_kway_isolated`]
+    /// runs [`Graph::try_part_kway`], e.g. [`Graph::part_kway_with`] to use
+    /// other options for this partitioning only.  `f` must fill the partition
+    /// array it is given, and return the objective.  It runs in a process
+    /// forked from a possibly multi-threaded one, so it should not do more
+    /// than calling METIS.  Only available on Unix with the `isolated`
+    /// feature.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the errors of `f`, with the same restrictions as
+    /// [`Graph::part_kway_isolated`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::option::KwayOptions;
+    /// use metis::Graph;
+    ///
+    /// // 0 - 1 - 2 - 3
+    /// let xadj = &[0, 1, 3, 5, 6];
+    /// let adjncy = &[1, 0, 2, 1, 3, 2];
+    /// let options = KwayOptions::default().seed(42);
+    /// let mut part = [0; 4];
+    /// let edgecut = Graph::new(1, 2, xadj, adjncy)?
+    ///     .part_isolated(&mut part, |graph, part| graph.part_kway_with(&options, part))?;
+    /// assert_eq!(edgecut, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// See [`ChildError`] for what happens when the child crashes.
+    pub fn part_isolated<F>(&mut self, part: &mut [Idx], f: F) -> Result<Idx>
+    where
+        F: FnOnce(&mut Graph<'a>, &mut [Idx]) -> Result<Idx>,
+    {
+        run_isolated(self, part, f)
+    }
+}
+
+/// Runs `f` in a child process, and copies the partition it computes back
+/// into `part`.
+fn run_isolated<'a, F>(graph: &mut Graph<'a>, part: &mut [Idx], f: F) -> Result<Idx>
+where
+    F: FnOnce(&mut Graph<'a>, &mut [Idx]) -> Result<Idx>,
+{
+    if part.len() != graph.xadj.len() - 1 {
+        return Err(Error::Input(None));
+    }
+    option::check(&graph.options)?;
+
+    let (mut reader, writer) = pipe().map_err(os_error)?;
+
+    let lock = lock_metis();
+    // SAFETY: no other thread is inside METIS, since the lock is held.  The
+    // child only runs `f`, writes to the pipe and exits without running
+    // destructors or exit handlers.
+    let pid = unsafe { libc::fork() };
+    if pid < 0 {
+        return Err(os_error(io::Error::last_os_error()));
+    }
+    if pid == 0 {
+        // This copy of the lock is only used by the child.
+        drop(lock);
+        let res = panic::catch_unwind(panic::AssertUnwindSafe(|| f(graph, part)));
+        let code = match res {
+            Ok(res) if write_result(&writer, &res, part) => 0,
+            _ => 1,
+        };
+        // SAFETY: _exit is always safe to call.
+        unsafe { libc::_exit(code) };
+    }
+    drop(lock);
+    drop(writer);
+
+    // Read before waiting, since the child blocks when the pipe is full.
+    let mut bytes = Vec::new();
+    let read = reader.read_to_end(&mut bytes);
+    let mut wstatus = 0;
+    // SAFETY: pid is a child of this process, that has not been waited for.
+    if unsafe { libc::waitpid(pid, &mut wstatus, 0) } != pid {
+        return Err(os_error(io::Error::last_os_error()));
+    }
+    if libc::WIFSIGNALED(wstatus) {
+        let signal = libc::WTERMSIG(wstatus);
+        return Err(Error::Other(Some(Box::new(ChildError::Signaled(signal)))));
+    }
+    if !libc::WIFEXITED(wstatus) || libc::WEXITSTATUS(wstatus) != 0 {
+        let status = libc::WEXITSTATUS(wstatus);
+        return Err(Error::Other(Some(Box::new(ChildError::Exited(status)))));
+    }
+    read.map_err(os_error)?;
+    read_result(&bytes, part)
+}
+
+fn os_error(err: io::Error) -> Error {
+    Error::Other(Some(Box::new(err)))
+}
+
+/// Creates a pipe whose ends are closed in programs executed by children.
+fn pipe() -> io::Result<(File, File)> {
+    let mut fds = [0; 2];
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "illumos",
+        target_os = "linux",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "solaris",
+    ))]
+    {
+        // SAFETY: fds has room for the two file descriptors.
+        if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "illumos",
+        target_os = "linux",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "solaris",
+    )))]
+    {
+        // SAFETY: fds has room for the two file descriptors.
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        for fd in fds {
+            // SAFETY: fd was just created and is owned here.
+            unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) };
+        }
+    }
+    // SAFETY: both file descriptors were just created and are owned here.
+    Ok(unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) })
+}
+
+/// Writes the status, the objective and the partition to the pipe, and
+/// returns whether it succeeded.
+///
+/// This runs in the child after `f`.  The writes to the pipe do not allocate,
+/// since the child might have been forked from a multi-threaded process.
+fn write_result(writer: &File, res: &Result<Idx>, part: &[Idx]) -> bool {
+    const SIZE: usize = mem::size_of::<Idx>();
+    let (status, objval) = match res {
+        Ok(objval) => (STATUS_OK, *objval),
+        Err(Error::Input(_)) => (STATUS_INPUT, 0),
+        Err(Error::Memory) => (STATUS_MEMORY, 0),
+        Err(Error::Other(_)) => (STATUS_OTHER, 0),
+    };
+    let mut header = [0; 1 + SIZE];
+    header[0] = status;
+    header[1..].copy_from_slice(&objval.to_ne_bytes());
+    // SAFETY: Idx has no padding bytes.
+    let part = unsafe { slice::from_raw_parts(part.as_ptr().cast::<u8>(), SIZE * part.len()) };
+    write_all(writer, &header) && write_all(writer, part)
+}
+
+/// Writes all of `bytes` with `write(2)`, which is async-signal-safe.
+fn write_all(writer: &File, mut bytes: &[u8]) -> bool {
+    while !bytes.is_empty() {
+        // SAFETY: bytes is valid for reads of its length.
+        let n = unsafe { libc::write(writer.as_raw_fd(), bytes.as_ptr().cast(), bytes.len()) };
+        if n < 0 {
+            if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return false;
+        }
+        bytes = &bytes[n as usize..];
+    }
+    true
+}
+
+/// Reads what [`write_result`] wrote.
+fn read_result(bytes: &[u8], part: &mut [Idx]) -> Result<Idx> {
+    const SIZE: usize = mem::size_of::<Idx>();
+    if bytes.len() != 1 + SIZE * (1 + part.len()) {
+        return Err(Error::Other(None));
+    }
+    let idx = |chunk: &[u8]| Idx::from_ne_bytes(chunk.try_into().unwrap());
+    match bytes[0] {
+        STATUS_OK => {}
+        STATUS_INPUT => return Err(Error::Input(None)),
+        STATUS_MEMORY => return Err(Error::Memory),
+        _ => return Err(Error::Other(None)),
+    }
+    let objval = idx(&bytes[1..1 + SIZE]);
+    for (p, chunk) in part.iter_mut().zip(bytes[1 + SIZE..].chunks_exact(SIZE)) {
+        *p = idx(chunk);
+    }
+    Ok(objval)
+}
@@ -1,20 +1,104 @@
 //! This crate provides a thin but idiomatic API around libmetis.
 //!
 //! See [`Graph`] for a usage example.
+//!
+//! # Thread safety
+//!
+//! All types of this crate are [`Send`] and [`Sync`]: graphs and meshes can be
+//! built on one thread and partitioned on another, and METIS routines can run
+//! concurrently on several threads, since METIS keeps its working memory in
+//! thread-local storage.
+//!
+//! There are two caveats, both lifted by the `global-lock` feature, which
+//! serializes calls to METIS partitioning and ordering routines:
+//!
+//! - METIS draws random numbers from a process-wide generator (the one of the
+//!   C library on most platforms).  Routines that run concurrently interfere
+//!   with each other's random sequence, so their results might differ from a
+//!   sequential run, even with a fixed seed (see [`option::Seed`]).
+//! - METIS traps its fatal errors (see [`Error::Other`]) with process-wide
+//!   signal handlers that each routine installs and restores.  When routines
+//!   run concurrently, a fatal error might not be trapped and terminate the
+//!   process instead.
+//!
+//! ```rust
+//! # fn main() -> Result<(), metis::Error> {
+//! use std::thread;
+//!
+//! // Two quadrangles sharing an edge.
+//! let dual = metis::mesh_to_dual(&[0, 4, 8], &[0, 1, 4, 3, 1, 2, 5, 4], 2)?;
+//! let graph = metis::GraphOwned::new(1, 2, vec![0, 1, 3, 4], vec![1, 0, 2, 1])?;
+//!
+//! let (dual_part, graph_part) = thread::scope(|s| {
+//!     let dual = s.spawn(move || -> Result<_, metis::Error> {
+//!         let mut part = [0; 2];
+//!         dual.as_graph(1, 2)?.part_kway(&mut part)?;
+//!         Ok(part)
+//!     });
+//!     let graph = s.spawn(move || graph.part_kway_owned());
+//!     (dual.join().unwrap(), graph.join().unwrap())
+//! });
+//! let dual_part = dual_part?;
+//! assert_ne!(dual_part[0], dual_part[1]);
+//! assert_eq!(graph_part?.1, 1);
+//! # Ok(())
+//! # }
+//! ```
 
 #![deny(missing_docs)]
 
+use crate::option::Numbering;
 use crate::option::Opt;
 use metis_sys as m;
+use std::cell;
 use std::convert::TryFrom;
 use std::fmt;
 use std::mem;
+use std::ops::RangeInclusive;
 use std::os;
+use std::panic;
 use std::ptr;
 use std::result::Result as StdResult;
 use std::slice;
+use std::sync;
+use std::thread;
 
+pub mod builder;
+pub mod convert;
+pub mod csr;
+pub mod diagnostics;
+pub mod io;
+#[cfg(all(unix, feature = "isolated"))]
+mod isolated;
+pub mod mesh;
+#[cfg(feature = "nalgebra-sparse")]
+pub mod nalgebra_sparse;
 pub mod option;
+pub mod order;
+#[cfg(feature = "rayon")]
+mod parallel;
+pub mod partition;
+#[cfg(feature = "petgraph")]
+pub mod petgraph;
+pub mod quality;
+pub mod refine;
+#[cfg(any(feature = "sprs", feature = "nalgebra-sparse"))]
+mod sparse;
+#[cfg(feature = "sprs")]
+pub mod sprs;
+
+pub use crate::builder::GraphBuilder;
+#[cfg(all(unix, feature = "isolated"))]
+pub use crate::isolated::ChildError;
+pub use crate::order::Ordering;
+pub use crate::order::Permutation;
+pub use crate::partition::partition_batch;
+pub use crate::partition::Method;
+pub use crate::partition::Partition;
+pub use crate::partition::PartitionConfig;
+pub use crate::partition::PartitionExt;
+pub use crate::partition::PartitionVec;
+pub use crate::partition::SweepEntry;
 
 #[cfg(target_pointer_width = "16")]
 compile_error!("METIS does not support 16-bit architectures");
@@ -31,41 +115,196 @@ pub type Real = m::real_t;
 /// [`Mesh::set_options`].
 pub const NOPTIONS: usize = m::METIS_NOPTIONS as usize;
 
+/// The width of [`Idx`] in bits, as configured in the linked METIS.
+pub const IDX_WIDTH: usize = 8 * mem::size_of::<Idx>();
+
+/// The width of [`Real`] in bits, as configured in the linked METIS.
+pub const REAL_WIDTH: usize = 8 * mem::size_of::<Real>();
+
+/// Returns the version of METIS these bindings were built against, as a
+/// `(major, minor, subminor)` triplet.
+///
+/// METIS does not offer a way to query its version at runtime, so these come
+/// from the headers used at build time.
+///
+/// # Example
+///
+/// ```rust
+/// use std::mem::size_of;
+///
+/// // METIS 5.1 or 5.2, the vendored one being 5.2.
+/// let (major, minor, _subminor) = metis::version();
+/// assert_eq!(major, 5);
+/// assert!((1..=2).contains(&minor));
+///
+/// assert!(metis::IDX_WIDTH == 32 || metis::IDX_WIDTH == 64);
+/// assert_eq!(size_of::<metis::Idx>(), metis::IDX_WIDTH / 8);
+/// assert!(metis::REAL_WIDTH == 32 || metis::REAL_WIDTH == 64);
+/// assert_eq!(size_of::<metis::Real>(), metis::REAL_WIDTH / 8);
+/// ```
+pub fn version() -> (u32, u32, u32) {
+    (
+        m::METIS_VER_MAJOR,
+        m::METIS_VER_MINOR,
+        m::METIS_VER_SUBMINOR,
+    )
+}
+
+/// Summary of the linked METIS, included in the [`fmt::Debug`] output of the
+/// builder structures so that bug reports carry it.
+struct BuildInfo;
+
+impl fmt::Debug for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (major, minor, subminor) = version();
+        write!(
+            f,
+            "METIS {major}.{minor}.{subminor} (idx: {IDX_WIDTH} bits, real: {REAL_WIDTH} bits)",
+        )
+    }
+}
+
 /// Error type returned by METIS.
-#[derive(Debug, PartialEq, Eq)]
+///
+/// Errors compare equal when they are the same variant: the sources of
+/// [`Error::Input`] and [`Error::Other`] are not compared.
+#[derive(Debug)]
 pub enum Error {
     /// Input is invalid.
     ///
-    /// These bindings should check for most input errors, if not all.
-    Input,
+    /// These bindings should check for most input errors, if not all.  When
+    /// the error comes from these checks, for example from a
+    /// [`NewGraphError`] converted with `?`, it is kept as the source of this
+    /// error (see [`std::error::Error::source`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use metis::{Error, NewGraphError};
+    /// use std::error::Error as _;
+    ///
+    /// fn partition(xadj: &[metis::Idx], adjncy: &[metis::Idx]) -> metis::Result<Vec<metis::Idx>> {
+    ///     let mut part = vec![0; xadj.len() - 1];
+    ///     metis::Graph::new(1, 2, xadj, adjncy)?.part_kway(&mut part)?;
+    ///     Ok(part)
+    /// }
+    ///
+    /// let err = partition(&[0, 1, 2], &[1, 2]).unwrap_err();
+    /// assert!(matches!(err, Error::Input(Some(_))));
+    /// // The source is not compared.
+    /// assert_eq!(err, Error::Input(None));
+    /// let source = err.source().unwrap();
+    /// assert!(source.to_string().contains("adjncy[1] is 2"));
+    /// assert!(matches!(
+    ///     source.downcast_ref::<NewGraphError>(),
+    ///     Some(NewGraphError::InvalidGraph(_)),
+    /// ));
+    /// ```
+    Input(Option<Box<dyn std::error::Error + Send + Sync + 'static>>),
 
     /// METIS hit an out-of-memory error.
+    ///
+    /// When the vendored METIS is built with its internal assertions (debug
+    /// builds without the `force-optimize-vendor` feature of `metis-sys`), a
+    /// failed assertion is also reported this way, because METIS traps the
+    /// resulting `SIGABRT` as it would an allocation failure.
     Memory,
 
     /// METIS returned an error but its meaning is unknown.
-    Other,
+    ///
+    /// Fatal errors raised deep inside METIS end up here: METIS traps them at
+    /// the entry of each routine and returns a generic error code, instead of
+    /// terminating the process.  With the vendored METIS, the reason METIS
+    /// gives is kept as the source of this error (see
+    /// [`std::error::Error::source`]).  An installed METIS prints it on the
+    /// standard error stream instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::option::Contig;
+    /// use metis::Error;
+    /// use std::error::Error as _;
+    ///
+    /// // A contiguous partition cannot be computed on a disconnected graph.
+    /// // 0 - 1   2 - 3
+    /// let xadj = &[0, 1, 2, 3, 4];
+    /// let adjncy = &[1, 0, 3, 2];
+    /// let mut part = [0; 4];
+    /// let err = metis::Graph::new(1, 2, xadj, adjncy)?
+    ///     .set_option(Contig(true))
+    ///     .part_kway(&mut part)
+    ///     .unwrap_err();
+    /// assert_eq!(err, Error::Other(None));
+    /// # #[cfg(any(feature = "default", feature = "vendored"))]
+    /// assert!(err
+    ///     .source()
+    ///     .unwrap()
+    ///     .to_string()
+    ///     .contains("contiguous partition is requested for a non-contiguous input graph"));
+    ///
+    /// // METIS is still usable afterwards.
+    /// metis::Graph::new(1, 2, xadj, adjncy)?.part_kway(&mut part)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    Other(Option<Box<dyn std::error::Error + Send + Sync + 'static>>),
 }
 
-impl std::error::Error for Error {}
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        mem::discriminant(self) == mem::discriminant(other)
+    }
+}
+
+impl Eq for Error {}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Input(Some(source)) | Error::Other(Some(source)) => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 impl From<NewGraphError> for Error {
-    fn from(_: NewGraphError) -> Self {
-        Self::Input
+    fn from(err: NewGraphError) -> Self {
+        Self::Input(Some(Box::new(err)))
+    }
+}
+
+impl From<convert::ConvertError> for Error {
+    fn from(err: convert::ConvertError) -> Self {
+        Self::Input(Some(Box::new(err)))
     }
 }
 
 impl From<NewMeshError> for Error {
-    fn from(_: NewMeshError) -> Self {
-        Self::Input
+    fn from(err: NewMeshError) -> Self {
+        Self::Input(Some(Box::new(err)))
+    }
+}
+
+impl From<quality::PartitionError> for Error {
+    fn from(err: quality::PartitionError) -> Self {
+        Self::Input(Some(Box::new(err)))
+    }
+}
+
+impl From<option::InvalidOptionError> for Error {
+    fn from(err: option::InvalidOptionError) -> Self {
+        Self::Input(Some(Box::new(err)))
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Error::Input => write!(f, "invalid input"),
+            Error::Input(_) => write!(f, "invalid input"),
             Error::Memory => write!(f, "out of memory"),
-            Error::Other => write!(f, "METIS returned an error"),
+            Error::Other(_) => write!(f, "METIS returned an error"),
         }
     }
 }
@@ -73,6 +312,60 @@ impl fmt::Display for Error {
 /// The result of a partitioning.
 pub type Result<T> = StdResult<T, Error>;
 
+// Compile-time checks that public types can be shared and sent across
+// threads.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Error>();
+    assert_send_sync::<Graph<'static>>();
+    assert_send_sync::<GraphOwned>();
+    assert_send_sync::<GraphParts<'static>>();
+    assert_send_sync::<GraphBuilder>();
+    assert_send_sync::<Mesh<'static>>();
+    assert_send_sync::<MeshParts<'static>>();
+    assert_send_sync::<Dual>();
+    assert_send_sync::<Ordering<'static>>();
+    assert_send_sync::<Permutation>();
+    assert_send_sync::<PartitionVec>();
+};
+
+thread_local! {
+    /// Whether [`lock_metis`] serializes calls to METIS on this thread, even
+    /// without the `global-lock` feature, see [`serialize_metis`].
+    static SERIALIZE_METIS: cell::Cell<bool> = const { cell::Cell::new(false) };
+}
+
+/// Serializes calls to METIS routines with the `global-lock` feature, and
+/// inside [`serialize_metis`].
+///
+/// The returned guard must be held for the duration of the call.
+fn lock_metis() -> Option<sync::MutexGuard<'static, ()>> {
+    static LOCK: sync::Mutex<()> = sync::Mutex::new(());
+    if cfg!(feature = "global-lock") || SERIALIZE_METIS.with(|serialize| serialize.get()) {
+        // METIS state is not left inconsistent by a panic on the Rust side.
+        Some(LOCK.lock().unwrap_or_else(sync::PoisonError::into_inner))
+    } else {
+        None
+    }
+}
+
+/// Runs `f` with its calls to METIS serialized with the ones of other threads
+/// in the same situation, as if the `global-lock` feature was enabled.
+///
+/// Used by the routines that run METIS on several threads, so that they do not
+/// interfere with each other through the random number generator of METIS.
+#[cfg(feature = "rayon")]
+fn serialize_metis<R>(f: impl FnOnce() -> R) -> R {
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SERIALIZE_METIS.with(|serialize| serialize.set(self.0));
+        }
+    }
+    let _restore = Restore(SERIALIZE_METIS.with(|serialize| serialize.replace(true)));
+    f()
+}
+
 trait ErrorCode {
     /// Makes a [`Result`] from a return code (int) from METIS.
     fn wrap(self) -> Result<()>;
@@ -80,28 +373,147 @@ trait ErrorCode {
 
 impl ErrorCode for m::rstatus_et {
     fn wrap(self) -> Result<()> {
+        // Taken in all cases, so that it is not reported by a later call.
+        let message = m::take_error();
         match self {
             m::rstatus_et_METIS_OK => Ok(()),
-            m::rstatus_et_METIS_ERROR_INPUT => Err(Error::Input),
+            m::rstatus_et_METIS_ERROR_INPUT => Err(Error::Input(None)),
             m::rstatus_et_METIS_ERROR_MEMORY => Err(Error::Memory),
-            m::rstatus_et_METIS_ERROR => Err(Error::Other),
+            m::rstatus_et_METIS_ERROR => Err(Error::Other(message.map(Into::into))),
             other => panic!("unexpected error code ({}) from METIS", other),
         }
     }
 }
 
+/// What is wrong with the structure of a graph or a mesh.
+///
+/// Returned by [`InvalidGraphError::kind`] and [`InvalidMeshError::kind`].
+///
+/// # Example
+///
+/// ```rust
+/// use metis::{Graph, Mesh, NewGraphError, NewMeshError, StructureErrorKind};
+///
+/// fn kind(err: NewGraphError) -> Option<StructureErrorKind> {
+///     match err {
+///         NewGraphError::InvalidGraph(err) => Some(err.kind()),
+///         _ => None,
+///     }
+/// }
+///
+/// // 0 - 1 - 2
+/// let xadj = &[0, 1, 3, 4];
+/// let adjncy = &[1, 0, 2, 1];
+/// let graph = |xadj, adjncy| Graph::new(1, 2, xadj, adjncy).unwrap_err();
+///
+/// assert_eq!(kind(graph(&[], adjncy)), Some(StructureErrorKind::EmptyIndex));
+/// assert_eq!(kind(graph(&[0, 1, 3, 5], adjncy)), Some(StructureErrorKind::LengthMismatch));
+/// assert_eq!(kind(graph(&[0, 3, 1, 4], adjncy)), Some(StructureErrorKind::NotSorted));
+/// assert_eq!(kind(graph(xadj, &[1, 0, 3, 1])), Some(StructureErrorKind::OutOfBounds));
+/// assert!(matches!(graph(xadj, &[1, 1, 2, 1]), NewGraphError::SelfLoop { vertex: 1 }));
+///
+/// let err = Graph::new_fortran(1, 2, &[2, 2, 4, 5], &[2, 1, 3, 2]).unwrap_err();
+/// assert_eq!(kind(err), Some(StructureErrorKind::WrongStart));
+///
+/// let err = Mesh::new(2, &[0, 2, 1], &[0]).unwrap_err();
+/// assert!(matches!(
+///     err,
+///     NewMeshError::InvalidMesh(err) if err.kind() == StructureErrorKind::NotSorted,
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StructureErrorKind {
+    /// The index array (`xadj` or `eptr`) is empty.
+    EmptyIndex,
+
+    /// The lengths of two arrays do not match, for example the last element
+    /// of `xadj` is not the length of `adjncy`.
+    LengthMismatch,
+
+    /// The index array is not sorted.
+    NotSorted,
+
+    /// The index array does not start at the expected value, e.g. one for
+    /// [`Graph::new_fortran`].
+    WrongStart,
+
+    /// A vertex or a node is out of bounds.
+    OutOfBounds,
+
+    /// An array that should be a permutation of the vertices is not.
+    NotAPermutation,
+}
+
 /// Error raised when the graph data fed to [`Graph::new`] cannot be safely
 /// passed to METIS.
 ///
 /// Graph data must follow the format described in [`Graph::new`].
 #[derive(Debug)]
 pub struct InvalidGraphError {
+    kind: StructureErrorKind,
     msg: &'static str,
+    context: Option<Offending>,
 }
 
 impl fmt::Display for InvalidGraphError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.msg.fmt(f)
+        self.msg.fmt(f)?;
+        match &self.context {
+            Some(context) => write!(f, ": {context}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl InvalidGraphError {
+    /// What is wrong with the structure, see [`StructureErrorKind`].
+    pub fn kind(&self) -> StructureErrorKind {
+        self.kind
+    }
+
+    /// The name of the array holding the offending value, if known.
+    pub fn array(&self) -> Option<&'static str> {
+        self.context.as_ref().map(|c| c.array)
+    }
+
+    /// The position of the first offending value in
+    /// [`InvalidGraphError::array`], if known.
+    pub fn position(&self) -> Option<usize> {
+        self.context.as_ref().map(|c| c.position)
+    }
+
+    /// The first offending value, if known.
+    pub fn value(&self) -> Option<Idx> {
+        self.context.as_ref().map(|c| c.value)
+    }
+
+    /// The range the offending value should have been in, if known.
+    pub fn range(&self) -> Option<RangeInclusive<Idx>> {
+        self.context.as_ref().map(|c| c.range.clone())
+    }
+}
+
+/// Where an array fails to follow the expected format.
+#[derive(Debug)]
+struct Offending {
+    array: &'static str,
+    position: usize,
+    value: Idx,
+    range: RangeInclusive<Idx>,
+}
+
+impl fmt::Display for Offending {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}] is {}, expected within {}..={}",
+            self.array,
+            self.position,
+            self.value,
+            self.range.start(),
+            self.range.end(),
+        )
     }
 }
 
@@ -126,6 +538,131 @@ pub enum NewGraphError {
     /// be raised during [`Graph::part_recursive`] and [`Graph::part_kway`] as
     /// [`Error::Input`].
     InvalidGraph(InvalidGraphError),
+
+    /// The graph is not undirected: the edge `(u, v)` is present, but not
+    /// `(v, u)`.
+    ///
+    /// Only raised by [`Graph::new_checked`] and
+    /// [`Graph::from_adjacency_lists_checked`].
+    NotSymmetric {
+        /// The source of the edge.
+        u: Idx,
+
+        /// The destination of the edge.
+        v: Idx,
+    },
+
+    /// The edge `(u, v)` appears several times in the graph.
+    ///
+    /// METIS would count its weight several times.  Only raised by
+    /// [`Graph::new_checked`] and [`Graph::from_adjacency_lists_checked`].
+    DuplicateEdge {
+        /// The source of the edge.
+        u: Idx,
+
+        /// The destination of the edge.
+        v: Idx,
+    },
+
+    /// The graph has an edge from `vertex` to itself, which METIS does not
+    /// allow.
+    ///
+    /// [`crate::GraphBuilder`] can remove such edges.
+    SelfLoop {
+        /// The vertex in its own adjacency list.
+        vertex: Idx,
+    },
+
+    /// An index array, such as `xadj`, holds a negative value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use metis::{Graph, Idx, NewGraphError};
+    ///
+    /// let adjncy = &[1, 0];
+    /// let cases: &[(&[Idx], usize)] = &[
+    ///     (&[-3, 1, 2], 0),
+    ///     (&[0, -1, 2], 1),
+    ///     (&[Idx::MIN, Idx::MIN, 2], 0),
+    /// ];
+    /// for (xadj, first) in cases {
+    ///     match Graph::new(1, 2, xadj, adjncy) {
+    ///         Err(NewGraphError::NegativeValue { array, index, .. }) => {
+    ///             assert_eq!(array, "xadj");
+    ///             assert_eq!(index, *first);
+    ///         }
+    ///         other => panic!("unexpected result: {other:?}"),
+    ///     }
+    /// }
+    /// ```
+    NegativeValue {
+        /// The name of the array, as in METIS' manual (e.g. `"xadj"`).
+        array: &'static str,
+
+        /// The position of the first negative value.
+        index: usize,
+
+        /// The first negative value.
+        value: Idx,
+    },
+
+    /// A weight array, such as `adjwgt`, holds a value that is not strictly
+    /// positive.
+    ///
+    /// Raised by the `try_set_*` methods of [`Graph`] and [`GraphOwned`].
+    NonPositiveValue {
+        /// The name of the array, as in METIS' manual (e.g. `"adjwgt"`).
+        array: &'static str,
+
+        /// The position of the first offending value, e.g. the position of
+        /// the edge in `adjncy`.
+        index: usize,
+
+        /// The first offending value.
+        value: Idx,
+    },
+
+    /// A real-valued array, such as `tpwgts`, holds a value that METIS
+    /// cannot handle, such as a target weight that is zero or negative.
+    ///
+    /// Raised by the `try_set_*` methods of [`Graph`] and [`GraphOwned`].
+    InvalidValue {
+        /// The name of the array, as in METIS' manual (e.g. `"tpwgts"`).
+        array: &'static str,
+
+        /// The position of the first offending value.
+        index: usize,
+
+        /// The first offending value.
+        value: Real,
+    },
+
+    /// The target partition weights of a constraint do not sum to 1.0.
+    ///
+    /// Raised by the `try_set_tpwgts` methods of [`Graph`] and [`GraphOwned`].
+    TargetWeightSum {
+        /// The index of the constraint.
+        constraint: usize,
+
+        /// The actual sum of its target partition weights.
+        sum: Real,
+    },
+
+    /// An optional array, such as vertex weights, does not have the expected
+    /// length.
+    ///
+    /// Raised by the `try_set_*` methods of [`Graph`] and [`GraphOwned`].
+    LengthMismatch {
+        /// The name of the array, as in METIS' manual (e.g. `"vwgt"`).
+        array: &'static str,
+
+        /// The expected length of the array.
+        expected: usize,
+
+        /// The actual length of the array.
+        actual: usize,
+    },
 }
 
 impl fmt::Display for NewGraphError {
@@ -135,6 +672,35 @@ impl fmt::Display for NewGraphError {
             Self::NoParts => write!(f, "there must be at least one part"),
             Self::TooLarge => write!(f, "graph is too large"),
             Self::InvalidGraph(err) => write!(f, "invalid graph structure: {err}"),
+            Self::NotSymmetric { u, v } => {
+                write!(f, "graph is not undirected: edge ({u}, {v}) has no reverse")
+            }
+            Self::DuplicateEdge { u, v } => write!(f, "edge ({u}, {v}) is duplicated"),
+            Self::SelfLoop { vertex } => write!(f, "vertex {vertex} has a self-loop"),
+            Self::NegativeValue {
+                array,
+                index,
+                value,
+            } => write!(f, "{array}[{index}] is negative ({value})"),
+            Self::NonPositiveValue {
+                array,
+                index,
+                value,
+            } => write!(f, "{array}[{index}] is not strictly positive ({value})"),
+            Self::InvalidValue {
+                array,
+                index,
+                value,
+            } => write!(f, "{array}[{index}] is out of range ({value})"),
+            Self::TargetWeightSum { constraint, sum } => write!(
+                f,
+                "target partition weights of constraint {constraint} sum to {sum}, expected 1.0",
+            ),
+            Self::LengthMismatch {
+                array,
+                expected,
+                actual,
+            } => write!(f, "{array} has length {actual}, expected {expected}"),
         }
     }
 }
@@ -142,9 +708,367 @@ impl fmt::Display for NewGraphError {
 impl std::error::Error for NewGraphError {}
 
 impl NewGraphError {
-    fn msg(msg: &'static str) -> Self {
-        Self::InvalidGraph(InvalidGraphError { msg })
+    fn msg(kind: StructureErrorKind, msg: &'static str) -> Self {
+        Self::InvalidGraph(InvalidGraphError {
+            kind,
+            msg,
+            context: None,
+        })
+    }
+
+    fn at(
+        kind: StructureErrorKind,
+        msg: &'static str,
+        array: &'static str,
+        position: usize,
+        value: Idx,
+        range: RangeInclusive<Idx>,
+    ) -> Self {
+        let context = Offending {
+            array,
+            position,
+            value,
+            range,
+        };
+        Self::InvalidGraph(InvalidGraphError {
+            kind,
+            msg,
+            context: Some(context),
+        })
+    }
+
+    /// The position of the first offending value in its array, if the error
+    /// is about a specific value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use metis::{Graph, NewGraphError};
+    ///
+    /// // 0 - 1 - 2, but vertex 1 refers to the nonexistent vertex 3.
+    /// let xadj = &[0, 1, 3, 4];
+    /// let adjncy = &[1, 0, 3, 1];
+    ///
+    /// let err = Graph::new(1, 2, xadj, adjncy).unwrap_err();
+    /// assert_eq!(err.position(), Some(2));
+    /// if let NewGraphError::InvalidGraph(err) = &err {
+    ///     assert_eq!(err.array(), Some("adjncy"));
+    ///     assert_eq!(err.value(), Some(3));
+    ///     assert_eq!(err.range(), Some(0..=2));
+    /// }
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "invalid graph structure: some values in the adjacency list are out of bounds: \
+    ///      adjncy[2] is 3, expected within 0..=2",
+    /// );
+    ///
+    /// let err = Graph::new(1, 2, &[0, 3, 1, 4], adjncy).unwrap_err();
+    /// assert_eq!(err.position(), Some(2));
+    /// let err = Graph::new(1, 2, &[0, 1, 3, 5], adjncy).unwrap_err();
+    /// assert_eq!(err.position(), Some(3));
+    /// let err = Graph::new(0, 2, xadj, adjncy).unwrap_err();
+    /// assert_eq!(err.position(), None);
+    /// ```
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            Self::InvalidGraph(err) => err.position(),
+            Self::NegativeValue { index, .. }
+            | Self::NonPositiveValue { index, .. }
+            | Self::InvalidValue { index, .. } => Some(*index),
+            _ => None,
+        }
+    }
+
+    fn check_len(array: &'static str, expected: usize, actual: usize) -> StdResult<(), Self> {
+        if expected != actual {
+            return Err(Self::LengthMismatch {
+                array,
+                expected,
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_positive(array: &'static str, values: &[Idx]) -> StdResult<(), Self> {
+        match values.iter().position(|x| *x <= 0) {
+            Some(index) => Err(Self::NonPositiveValue {
+                array,
+                index,
+                value: values[index],
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn check_ubvec(ubvec: &[Real]) -> StdResult<(), Self> {
+        // Also catches NaN, which compares false with everything.
+        match ubvec.iter().position(|u| !(u.is_finite() && *u >= 1.0)) {
+            Some(index) => Err(Self::InvalidValue {
+                array: "ubvec",
+                index,
+                value: ubvec[index],
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<TpwgtsFault> for NewGraphError {
+    fn from(fault: TpwgtsFault) -> Self {
+        match fault {
+            TpwgtsFault::Value { index, value } => Self::InvalidValue {
+                array: "tpwgts",
+                index,
+                value,
+            },
+            TpwgtsFault::Sum { constraint, sum } => Self::TargetWeightSum { constraint, sum },
+        }
+    }
+}
+
+/// How far from 1.0 the sum of target partition weights can be, to account for
+/// rounding errors.
+const TPWGTS_SUM_TOLERANCE: Real = 1e-3;
+
+/// Problem found by [`check_tpwgts`].
+enum TpwgtsFault {
+    Value { index: usize, value: Real },
+    Sum { constraint: usize, sum: Real },
+}
+
+/// Checks that target partition weights are strictly positive, and that they
+/// sum to 1.0 for each of the `ncon` constraints.
+///
+/// The weight of the `i`th part and `j`th constraint is at `tpwgts[i*ncon+j]`.
+fn check_tpwgts(tpwgts: &[Real], ncon: usize) -> StdResult<(), TpwgtsFault> {
+    if let Some(index) = tpwgts.iter().position(|w| !(w.is_finite() && *w > 0.0)) {
+        return Err(TpwgtsFault::Value {
+            index,
+            value: tpwgts[index],
+        });
+    }
+    for constraint in 0..ncon {
+        let sum: Real = tpwgts.iter().skip(constraint).step_by(ncon).sum();
+        if (sum - 1.0).abs() > TPWGTS_SUM_TOLERANCE {
+            return Err(TpwgtsFault::Sum { constraint, sum });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the length of an array holding `ncon` values for each of `count`
+/// items, e.g. vertex weights.
+///
+/// # Errors
+///
+/// This function returns [`NewGraphError::TooLarge`] if the length does not fit
+/// in [`Idx`], which METIS uses to index the array.
+fn array_len(ncon: Idx, count: usize) -> StdResult<usize, NewGraphError> {
+    usize::try_from(ncon)
+        .ok()
+        .and_then(|ncon| ncon.checked_mul(count))
+        .filter(|len| Idx::try_from(*len).is_ok())
+        .ok_or(NewGraphError::TooLarge)
+}
+
+/// Returns the position and value of the first negative element of `values`.
+fn find_negative(values: &[Idx]) -> Option<(usize, Idx)> {
+    values
+        .iter()
+        .enumerate()
+        .find(|(_, x)| **x < 0)
+        .map(|(index, x)| (index, *x))
+}
+
+/// Returns the number of vertices in the graph.
+fn check_graph_structure(xadj: &[Idx], adjncy: &[Idx]) -> StdResult<Idx, NewGraphError> {
+    check_numbered_graph_structure(xadj, adjncy, Numbering::C)
+}
+
+/// Same as [`check_graph_structure`], for a graph whose indices start at the
+/// given numbering.
+fn check_numbered_graph_structure(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    numbering: Numbering,
+) -> StdResult<Idx, NewGraphError> {
+    let base = numbering.value();
+    if let Some((index, value)) = find_negative(xadj) {
+        return Err(NewGraphError::NegativeValue {
+            array: "xadj",
+            index,
+            value,
+        });
+    }
+    let last_xadj = *xadj.last().ok_or(NewGraphError::msg(
+        StructureErrorKind::EmptyIndex,
+        "index list is empty",
+    ))?;
+    if xadj[0] != base {
+        return Err(NewGraphError::at(
+            StructureErrorKind::WrongStart,
+            "index list does not start at the first index",
+            "xadj",
+            0,
+            xadj[0],
+            base..=base,
+        ));
     }
+    let adjncy_len = Idx::try_from(adjncy.len()).map_err(|_| NewGraphError::TooLarge)?;
+    if last_xadj - base != adjncy_len {
+        return Err(NewGraphError::at(
+            StructureErrorKind::LengthMismatch,
+            "length mismatch between index and adjacency lists",
+            "xadj",
+            xadj.len() - 1,
+            last_xadj,
+            adjncy_len + base..=adjncy_len + base,
+        ));
+    }
+
+    let nvtxs = match Idx::try_from(xadj.len()) {
+        Ok(xadj_len) => xadj_len - 1,
+        Err(_) => {
+            return Err(NewGraphError::TooLarge);
+        }
+    };
+
+    let mut prev = base;
+    for (i, x) in xadj.iter().enumerate() {
+        if prev > *x {
+            return Err(NewGraphError::at(
+                StructureErrorKind::NotSorted,
+                "index list is not sorted",
+                "xadj",
+                i,
+                *x,
+                prev..=last_xadj,
+            ));
+        }
+        prev = *x;
+    }
+
+    for (i, a) in adjncy.iter().enumerate() {
+        if *a < base || *a >= nvtxs + base {
+            return Err(NewGraphError::at(
+                StructureErrorKind::OutOfBounds,
+                "some values in the adjacency list are out of bounds",
+                "adjncy",
+                i,
+                *a,
+                base..=nvtxs + base - 1,
+            ));
+        }
+    }
+
+    Ok(nvtxs)
+}
+
+/// Checks that no vertex is in its own adjacency list.
+///
+/// The graph must uphold the invariants checked by
+/// [`check_numbered_graph_structure`].
+fn check_no_self_loops(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    numbering: Numbering,
+) -> StdResult<(), NewGraphError> {
+    let base = numbering.value();
+    for (v, w) in xadj.windows(2).enumerate() {
+        let v = v as Idx + base;
+        if adjncy[(w[0] - base) as usize..(w[1] - base) as usize].contains(&v) {
+            return Err(NewGraphError::SelfLoop { vertex: v });
+        }
+    }
+    Ok(())
+}
+
+/// Counts the edges of the dual graph of a mesh with C-style numbering, or of
+/// its nodal graph when `ncommon` is `None`, as METIS would build them.
+///
+/// This is the edge-cut of the partitions that put each element, or each
+/// node, in its own part, so it is computed without building the graph.
+fn count_mesh_edges(eptr: &[Idx], eind: &[Idx], nn: Idx, ncommon: Option<Idx>) -> Result<Idx> {
+    let ne = eptr.len() - 1;
+    let nn = nn as usize;
+    let nodes = |e: usize| &eind[eptr[e] as usize..eptr[e + 1] as usize];
+
+    // Elements around each node, in CSR format.
+    let mut nptr = vec![0; nn + 1];
+    for n in eind {
+        nptr[*n as usize + 1] += 1;
+    }
+    for i in 1..nptr.len() {
+        nptr[i] += nptr[i - 1];
+    }
+    let mut next = nptr.clone();
+    let mut nind = vec![0; eind.len()];
+    for e in 0..ne {
+        for n in nodes(e) {
+            nind[next[*n as usize]] = e;
+            next[*n as usize] += 1;
+        }
+    }
+    let elements = |n: usize| &nind[nptr[n]..nptr[n + 1]];
+
+    // Edges are counted in both directions.
+    let mut nedges = 0;
+    match ncommon {
+        Some(ncommon) => {
+            // Same rule as FindCommonElements in METIS: elements are adjacent
+            // if they share `ncommon` nodes, or all the nodes of either one
+            // but one.  METIS also makes elements with less than two nodes
+            // adjacent to themselves, which does not change the edge-cut.
+            let ncommon = Idx::max(ncommon, 1) as usize;
+            let mut overlap = vec![0; ne];
+            let mut neighbors = Vec::new();
+            for e in 0..ne {
+                for n in nodes(e) {
+                    for f in elements(*n as usize) {
+                        if overlap[*f] == 0 {
+                            neighbors.push(*f);
+                        }
+                        overlap[*f] += 1;
+                    }
+                }
+                for f in neighbors.drain(..) {
+                    let shared = mem::take(&mut overlap[f]);
+                    if f != e
+                        && (ncommon <= shared
+                            || nodes(e).len() <= shared + 1
+                            || nodes(f).len() <= shared + 1)
+                    {
+                        nedges += 1;
+                    }
+                }
+            }
+        }
+        None => {
+            // Nodes are adjacent if they share an element.
+            let mut marker = vec![usize::MAX; nn];
+            for u in 0..nn {
+                marker[u] = u;
+                for e in elements(u) {
+                    for v in nodes(*e) {
+                        if marker[*v as usize] != u {
+                            marker[*v as usize] = u;
+                            nedges += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Idx::try_from(nedges / 2).map_err(|_| Error::Input(None))
+}
+
+/// Returns a zero-based copy of a one-based index array.
+///
+/// METIS renumbers one-based arrays in place, which is not allowed through the
+/// shared slices held by [`Graph`] and [`Mesh`].  They are copied instead.
+fn zero_based(array: &[Idx]) -> Vec<Idx> {
+    array.iter().map(|i| i - 1).collect()
 }
 
 /// Helper function to convert an immutable slice ref to a mutable pointer
@@ -166,20 +1090,23 @@ unsafe fn slice_to_mut_ptr<T>(slice: &[T]) -> *mut T {
 /// let xadj = &mut [0, 1, 2];
 /// let adjncy = &mut [1, 0];
 ///
-/// // Allocate the partition array which stores the partition of each vertex.
-/// let mut part = [0, 0];
-///
 /// // There are one constraint and two parts.  The partitioning algorithm used
 /// // is recursive bisection.  The k-way algorithm can also be used.
-/// Graph::new(1, 2, xadj, adjncy)?
-///     .part_recursive(&mut part)?;
+/// let (part, edgecut) = Graph::new(1, 2, xadj, adjncy)?.part_recursive_owned()?;
 ///
 /// // The two vertices are placed in different parts.
+/// assert_ne!(part.part_of(0), part.part_of(1));
+/// assert_eq!(edgecut, 1);
+///
+/// // The partition array, which stores the part of each vertex, can also be
+/// // allocated by the caller.
+/// let mut part = [0, 0];
+/// Graph::new(1, 2, xadj, adjncy)?.part_recursive(&mut part)?;
 /// assert_ne!(part[0], part[1]);
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug, PartialEq)]
+#[derive(PartialEq)]
 pub struct Graph<'a> {
     /// The number of balancing constrains.
     ncon: Idx,
@@ -224,6 +1151,28 @@ pub struct Graph<'a> {
 
     /// Fine-tuning parameters.
     options: [Idx; NOPTIONS],
+
+    /// Whether indices in `xadj` and `adjncy` start at zero or one.
+    numbering: Numbering,
+}
+
+impl fmt::Debug for Graph<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Graph")
+            .field("ncon", &self.ncon)
+            .field("nparts", &self.nparts)
+            .field("xadj", &self.xadj)
+            .field("adjncy", &self.adjncy)
+            .field("vwgt", &self.vwgt)
+            .field("vsize", &self.vsize)
+            .field("adjwgt", &self.adjwgt)
+            .field("tpwgts", &self.tpwgts)
+            .field("ubvec", &self.ubvec)
+            .field("options", &self.options)
+            .field("numbering", &self.numbering)
+            .field("metis", &BuildInfo)
+            .finish()
+    }
 }
 
 impl<'a> Graph<'a> {
@@ -289,10 +1238,14 @@ impl<'a> Graph<'a> {
     /// - `nparts` is strictly greater than zero,
     /// - `xadj` has at least one element (its length is the one more than the
     ///   number of vertices),
+    /// - the first element of `xadj` is zero,
     /// - `xadj` is sorted,
-    /// - elements of `xadj` are positive,
+    /// - elements of `xadj` are positive (reported as
+    ///   [`NewGraphError::NegativeValue`] with the first offending position),
     /// - the last element of `xadj` is the length of `adjncy`,
-    /// - elements of `adjncy` are within zero and the number of vertices.
+    /// - elements of `adjncy` are within zero and the number of vertices,
+    /// - there are no self-loops: vertices are not in their own adjacency list
+    ///   (reported as [`NewGraphError::SelfLoop`]).
     ///
     /// # Mutability
     ///
@@ -312,52 +1265,146 @@ impl<'a> Graph<'a> {
             return Err(NewGraphError::NoParts);
         }
 
-        let last_xadj = *xadj
-            .last()
-            .ok_or(NewGraphError::msg("index list is empty"))?;
-        let adjncy_len = Idx::try_from(adjncy.len()).map_err(|_| NewGraphError::TooLarge)?;
-        if last_xadj != adjncy_len {
-            return Err(NewGraphError::msg(
-                "length mismatch between index and adjacency lists",
-            ));
-        }
-
-        let nvtxs = match Idx::try_from(xadj.len()) {
-            Ok(xadj_len) => xadj_len - 1,
-            Err(_) => {
-                return Err(NewGraphError::TooLarge);
-            }
-        };
-
-        let mut prev = 0;
-        for x in xadj {
-            if prev > *x {
-                return Err(NewGraphError::msg("index list is not sorted"));
-            }
-            prev = *x;
-        }
-
-        for a in adjncy {
-            if *a < 0 || *a >= nvtxs {
-                return Err(NewGraphError::msg(
-                    "some values in the adjacency list are out of bounds",
-                ));
-            }
-        }
+        check_graph_structure(xadj, adjncy)?;
+        check_no_self_loops(xadj, adjncy, Numbering::C)?;
 
         Ok(unsafe { Graph::new_unchecked(ncon, nparts, xadj, adjncy) })
     }
 
-    /// Creates a new [`Graph`] object to be partitioned (unchecked version).
+    /// Creates a new [`Graph`] object to be partitioned, from arrays that use
+    /// Fortran-style numbering: indices start at one instead of zero.
     ///
-    /// - `ncon` is the number of constraints on each vertex (at least 1),
-    /// - `nparts` is the number of parts wanted in the graph partition.
+    /// The first element of `xadj` is one, the last is the length of `adjncy`
+    /// plus one, and the neighbors of the `i`th vertex (counting from one) are
+    /// `adjncy[xadj[i - 1] - 1..xadj[i] - 1]`.  The partition arrays are then
+    /// also filled with parts numbered from one.
     ///
-    /// # Input format
+    /// METIS supports this numbering by renumbering the arrays in place, which
+    /// the borrowed arrays do not allow.  Instead, they are copied to
+    /// zero-based arrays before each partitioning.
     ///
-    /// `xadj` and `adjncy` are the [CSR encoding][0] of the adjacency matrix
-    /// that represents the graph. `xadj` is the row index and `adjncy` is the
-    /// column index.
+    /// # Errors
+    ///
+    /// This function returns an error if the arrays do not uphold the
+    /// invariants listed in the "Errors" section of [`Graph::new`], adjusted
+    /// to one-based numbering.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// // 1 - 2 - 3 - 4
+    /// let xadj = &[1, 2, 4, 6, 7];
+    /// let adjncy = &[2, 1, 3, 2, 4, 3];
+    /// let mut graph = metis::Graph::new_fortran(1, 2, xadj, adjncy)?;
+    /// assert_eq!(graph.neighbors(2), &[1, 3]);
+    ///
+    /// let mut part = [0; 4];
+    /// graph.part_recursive(&mut part)?;
+    /// assert!(part.iter().all(|p| *p == 1 || *p == 2));
+    ///
+    /// // Zero-based arrays are rejected.
+    /// assert!(metis::Graph::new_fortran(1, 2, &[0, 1, 2], &[1, 0]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_fortran(
+        ncon: Idx,
+        nparts: Idx,
+        xadj: &'a [Idx],
+        adjncy: &'a [Idx],
+    ) -> StdResult<Graph<'a>, NewGraphError> {
+        if ncon <= 0 {
+            return Err(NewGraphError::NoConstraints);
+        }
+        if nparts <= 0 {
+            return Err(NewGraphError::NoParts);
+        }
+
+        check_numbered_graph_structure(xadj, adjncy, Numbering::Fortran)?;
+        check_no_self_loops(xadj, adjncy, Numbering::Fortran)?;
+
+        Ok(Graph {
+            ncon,
+            nparts,
+            xadj,
+            adjncy,
+            vwgt: None,
+            vsize: None,
+            adjwgt: None,
+            tpwgts: None,
+            ubvec: None,
+            options: option::initial(),
+            numbering: Numbering::Fortran,
+        })
+    }
+
+    /// Creates a new [`Graph`] object to be partitioned, and verifies that it
+    /// is undirected.
+    ///
+    /// On top of the checks done by [`Graph::new`], this function verifies
+    /// that for every edge `(u, v)`, the edge `(v, u)` is also present.  METIS
+    /// requires this, but does not check it.  It also verifies that no edge
+    /// appears twice, since METIS would count it twice in the edge cut.
+    ///
+    /// The check runs in `O(E log D)` time, where `E` is the number of edges
+    /// and `D` the maximum degree, with binary searches in adjacency lists.
+    /// Unsorted adjacency lists are first copied and sorted, which needs
+    /// `O(E)` additional memory.  On huge graphs that are known to be
+    /// undirected, [`Graph::new`] avoids this cost.
+    ///
+    /// # Errors
+    ///
+    /// On top of the errors returned by [`Graph::new`], this function returns
+    /// [`NewGraphError::DuplicateEdge`] with the first edge that appears
+    /// several times, or [`NewGraphError::NotSymmetric`] with the first edge
+    /// that has no reverse.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use metis::{Graph, NewGraphError};
+    /// // 0 -> 1 - 2
+    /// let xadj = &[0, 1, 2, 3];
+    /// let adjncy = &[1, 2, 1];
+    ///
+    /// assert!(Graph::new(1, 2, xadj, adjncy).is_ok());
+    /// assert!(matches!(
+    ///     Graph::new_checked(1, 2, xadj, adjncy),
+    ///     Err(NewGraphError::NotSymmetric { u: 0, v: 1 }),
+    /// ));
+    ///
+    /// // 0 = 1
+    /// let xadj = &[0, 2, 4];
+    /// let adjncy = &[1, 1, 0, 0];
+    ///
+    /// assert!(Graph::new(1, 2, xadj, adjncy).is_ok());
+    /// assert!(matches!(
+    ///     Graph::new_checked(1, 2, xadj, adjncy),
+    ///     Err(NewGraphError::DuplicateEdge { u: 0, v: 1 }),
+    /// ));
+    /// ```
+    pub fn new_checked(
+        ncon: Idx,
+        nparts: Idx,
+        xadj: &'a [Idx],
+        adjncy: &'a [Idx],
+    ) -> StdResult<Graph<'a>, NewGraphError> {
+        let graph = Graph::new(ncon, nparts, xadj, adjncy)?;
+        csr::check_undirected(xadj, adjncy)?;
+        Ok(graph)
+    }
+
+    /// Creates a new [`Graph`] object to be partitioned (unchecked version).
+    ///
+    /// - `ncon` is the number of constraints on each vertex (at least 1),
+    /// - `nparts` is the number of parts wanted in the graph partition.
+    ///
+    /// # Input format
+    ///
+    /// `xadj` and `adjncy` are the [CSR encoding][0] of the adjacency matrix
+    /// that represents the graph. `xadj` is the row index and `adjncy` is the
+    /// column index.
     ///
     /// [0]: https://en.wikipedia.org/wiki/Sparse_matrix#Compressed_sparse_row_(CSR,_CRS_or_Yale_format)
     ///
@@ -405,7 +1452,234 @@ impl<'a> Graph<'a> {
             adjwgt: None,
             tpwgts: None,
             ubvec: None,
-            options: [-1; NOPTIONS],
+            options: option::initial(),
+            numbering: Numbering::C,
+        }
+    }
+
+    /// Creates a new [`GraphOwned`] object to be partitioned, from the list of
+    /// neighbors of each vertex.
+    ///
+    /// - `ncon` is the number of constraints on each vertex (at least 1),
+    /// - `nparts` is the number of parts wanted in the graph partition,
+    /// - `lists[v]` contains the neighbors of vertex `v`.
+    ///
+    /// The lists are flattened into the CSR format described in
+    /// [`Graph::new`].  METIS requires the graph to be undirected, which
+    /// [`Graph::from_adjacency_lists_checked`] verifies.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - the number of vertices or edges cannot be held by an [`Idx`],
+    /// - `ncon` or `nparts` is not strictly greater than zero,
+    /// - some neighbors are not within zero and the number of vertices.
+    ///
+    /// Like the errors of [`Graph::new`], the error about a neighbor gives its
+    /// position in the flattened adjacency array (see
+    /// [`NewGraphError::position`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// // 5 - 3 - 4 - 0
+    /// //     |   | /
+    /// //     2 - 1
+    /// let lists = [
+    ///     vec![1, 4],
+    ///     vec![0, 2, 4],
+    ///     vec![1, 3],
+    ///     vec![2, 4, 5],
+    ///     vec![0, 1, 3],
+    ///     vec![3],
+    /// ];
+    /// let graph = metis::Graph::from_adjacency_lists(1, 2, &lists)?;
+    /// assert_eq!(graph.xadj(), &[0, 2, 5, 7, 10, 13, 14]);
+    /// assert_eq!(graph.adjncy(), &[1, 4, 0, 2, 4, 1, 3, 2, 4, 5, 0, 1, 3, 3]);
+    ///
+    /// let mut part = [0; 6];
+    /// graph.part_kway(&mut part)?;
+    ///
+    /// // Vertex 6 does not exist.
+    /// let lists = [vec![1], vec![0, 6]];
+    /// let err = metis::Graph::from_adjacency_lists(1, 2, &lists).unwrap_err();
+    /// assert_eq!(err.position(), Some(2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_adjacency_lists(
+        ncon: Idx,
+        nparts: Idx,
+        lists: &[Vec<Idx>],
+    ) -> StdResult<GraphOwned, NewGraphError> {
+        let _ = Idx::try_from(lists.len()).map_err(|_| NewGraphError::TooLarge)?;
+        let nedges: usize = lists.iter().map(Vec::len).sum();
+        let _ = Idx::try_from(nedges).map_err(|_| NewGraphError::TooLarge)?;
+
+        let mut xadj = Vec::with_capacity(lists.len() + 1);
+        let mut adjncy = Vec::with_capacity(nedges);
+        xadj.push(0);
+        for list in lists {
+            adjncy.extend_from_slice(list);
+            xadj.push(adjncy.len() as Idx);
+        }
+
+        GraphOwned::new(ncon, nparts, xadj, adjncy)
+    }
+
+    /// Creates a new [`GraphOwned`] object to be partitioned, from the list of
+    /// neighbors of each vertex, and verifies that it is undirected.
+    ///
+    /// This is [`Graph::from_adjacency_lists`] with the checks of
+    /// [`Graph::new_checked`].
+    ///
+    /// # Errors
+    ///
+    /// On top of the errors returned by [`Graph::from_adjacency_lists`], this
+    /// function returns [`NewGraphError::DuplicateEdge`] with the first edge
+    /// that appears several times, or [`NewGraphError::NotSymmetric`] with the
+    /// first edge that has no reverse.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use metis::{Graph, NewGraphError};
+    /// // 0 -> 1 - 2
+    /// let lists = [vec![1], vec![2], vec![1]];
+    ///
+    /// assert!(Graph::from_adjacency_lists(1, 2, &lists).is_ok());
+    /// assert!(matches!(
+    ///     Graph::from_adjacency_lists_checked(1, 2, &lists),
+    ///     Err(NewGraphError::NotSymmetric { u: 0, v: 1 }),
+    /// ));
+    ///
+    /// // 0 - 1 - 2
+    /// let lists = [vec![1], vec![0, 2], vec![1]];
+    /// assert!(Graph::from_adjacency_lists_checked(1, 2, &lists).is_ok());
+    /// ```
+    pub fn from_adjacency_lists_checked(
+        ncon: Idx,
+        nparts: Idx,
+        lists: &[Vec<Idx>],
+    ) -> StdResult<GraphOwned, NewGraphError> {
+        let graph = Graph::from_adjacency_lists(ncon, nparts, lists)?;
+        csr::check_undirected(graph.xadj(), graph.adjncy())?;
+        Ok(graph)
+    }
+
+    /// The number of vertices in the graph.
+    pub fn nvtxs(&self) -> Idx {
+        self.xadj.len() as Idx - 1
+    }
+
+    /// The number of edges in the graph.
+    ///
+    /// Since edges appear in the adjacency lists of both of their ends, this
+    /// is half the length of `adjncy`.
+    pub fn nedges(&self) -> Idx {
+        self.adjncy.len() as Idx / 2
+    }
+
+    /// The number of balancing constraints.
+    pub fn ncon(&self) -> Idx {
+        self.ncon
+    }
+
+    /// The number of parts to partition the graph into.
+    pub fn nparts(&self) -> Idx {
+        self.nparts
+    }
+
+    /// The adjacency list of vertex `v`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `v` is not within zero and the number of
+    /// vertices.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// // 0 - 1 - 2
+    /// let xadj = &[0, 1, 3, 4];
+    /// let adjncy = &[1, 0, 2, 1];
+    /// let graph = metis::Graph::new(1, 2, xadj, adjncy)?;
+    ///
+    /// assert_eq!(graph.nvtxs(), 3);
+    /// assert_eq!(graph.nedges(), 2);
+    /// assert_eq!(graph.neighbors(1), &[0, 2]);
+    ///
+    /// let degrees: Vec<usize> = (0..graph.nvtxs())
+    ///     .map(|v| graph.neighbors(v).len())
+    ///     .collect();
+    /// assert_eq!(degrees, [1, 2, 1]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn neighbors(&self, v: Idx) -> &'a [Idx] {
+        let base = self.numbering.value();
+        let v = v - base;
+        assert!(
+            0 <= v && v < self.nvtxs(),
+            "vertex {} out of bounds",
+            v + base
+        );
+        let v = v as usize;
+        &self.adjncy[(self.xadj[v] - base) as usize..(self.xadj[v + 1] - base) as usize]
+    }
+
+    /// Consumes the graph and returns the arrays and parameters it was built
+    /// from.
+    ///
+    /// This gives back access to the input arrays, for example to inspect them
+    /// after a failed partitioning.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::Graph;
+    ///
+    /// let xadj = vec![0, 1, 3, 4];
+    /// let adjncy = vec![1, 0, 2, 1];
+    /// let mut adjwgt = vec![1, 1, 2, 2];
+    ///
+    /// let graph = Graph::new(1, 2, &xadj, &adjncy)?
+    ///     .set_adjwgt(&adjwgt)
+    ///     .set_option(metis::option::Seed(42));
+    /// let parts = graph.into_inner();
+    /// assert_eq!(parts.adjwgt, Some(&adjwgt[..]));
+    /// assert_eq!(parts.vwgt, None);
+    /// let options = parts.options;
+    ///
+    /// // Make the second edge heavier and try again with the same options.
+    /// adjwgt[2] = 10;
+    /// adjwgt[3] = 10;
+    /// let mut part = [0; 3];
+    /// let edgecut = Graph::new(1, 2, &xadj, &adjncy)?
+    ///     .set_adjwgt(&adjwgt)
+    ///     .set_options(&options)
+    ///     .part_recursive(&mut part)?;
+    /// assert_eq!(edgecut, 1);
+    /// assert_eq!(part[1], part[2]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_inner(self) -> GraphParts<'a> {
+        GraphParts {
+            ncon: self.ncon,
+            nparts: self.nparts,
+            xadj: self.xadj,
+            adjncy: self.adjncy,
+            vwgt: self.vwgt,
+            vsize: self.vsize,
+            adjwgt: self.adjwgt,
+            tpwgts: self.tpwgts,
+            ubvec: self.ubvec,
+            options: self.options,
         }
     }
 
@@ -416,15 +1690,64 @@ impl<'a> Graph<'a> {
     /// The `ncon` weights of the `i`th vertex must be located in
     /// `vwgt[i*ncon..(i+1)*ncon]`, and all elements of `vwgt` must be positive.
     ///
+    /// This is a thin wrapper around [`Graph::try_set_vwgt`].
+    ///
     /// # Panics
     ///
     /// This function panics if the length of `vwgt` is not `ncon` times the
     /// number of vertices.
-    pub fn set_vwgt(mut self, vwgt: &'a [Idx]) -> Graph<'a> {
-        let vwgt_len = Idx::try_from(vwgt.len()).expect("vwgt array too large");
-        assert_eq!(vwgt_len, self.ncon * (self.xadj.len() as Idx - 1));
+    pub fn set_vwgt(self, vwgt: &'a [Idx]) -> Graph<'a> {
+        self.try_set_vwgt(vwgt)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Graph::set_vwgt`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`NewGraphError::LengthMismatch`] if the length of
+    /// `vwgt` is not `ncon` times the number of vertices.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::{Graph, NewGraphError};
+    ///
+    /// // 0 - 1 - 2, with two constraints and two parts.
+    /// let xadj = &[0, 1, 3, 4];
+    /// let adjncy = &[1, 0, 2, 1];
+    /// let g = || Graph::new(2, 2, xadj, adjncy).unwrap();
+    ///
+    /// let mismatch = |res: Result<Graph, NewGraphError>| match res {
+    ///     Err(NewGraphError::LengthMismatch {
+    ///         array,
+    ///         expected,
+    ///         actual,
+    ///     }) => Some((array, expected, actual)),
+    ///     _ => None,
+    /// };
+    /// assert_eq!(mismatch(g().try_set_vwgt(&[1; 3])), Some(("vwgt", 6, 3)));
+    /// assert_eq!(mismatch(g().try_set_vsize(&[1; 2])), Some(("vsize", 3, 2)));
+    /// assert_eq!(mismatch(g().try_set_adjwgt(&[1; 3])), Some(("adjwgt", 4, 3)));
+    /// assert_eq!(mismatch(g().try_set_tpwgts(&[0.5; 2])), Some(("tpwgts", 4, 2)));
+    /// assert_eq!(mismatch(g().try_set_ubvec(&[1.1; 1])), Some(("ubvec", 2, 1)));
+    ///
+    /// let mut part = [0; 3];
+    /// g().try_set_vwgt(&[1; 6])?
+    ///     .try_set_adjwgt(&[1; 4])?
+    ///     .part_kway(&mut part)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_set_vwgt(mut self, vwgt: &'a [Idx]) -> StdResult<Graph<'a>, NewGraphError> {
+        NewGraphError::check_len(
+            "vwgt",
+            array_len(self.ncon, self.xadj.len() - 1)?,
+            vwgt.len(),
+        )?;
         self.vwgt = Some(vwgt);
-        self
+        Ok(self)
     }
 
     /// Sets the communication weights of the vertices.
@@ -434,15 +1757,27 @@ impl<'a> Graph<'a> {
     /// Vertices can only have one communication weight. The length of `vsize`
     /// does not depend on `ncon`.
     ///
+    /// This is a thin wrapper around [`Graph::try_set_vsize`].
+    ///
     /// # Panics
     ///
     /// This function panics if the length of `vsize` is not the number of
     /// vertices.
-    pub fn set_vsize(mut self, vsize: &'a [Idx]) -> Graph<'a> {
-        let vsize_len = Idx::try_from(vsize.len()).expect("vsize array too large");
-        assert_eq!(vsize_len, self.xadj.len() as Idx - 1);
+    pub fn set_vsize(self, vsize: &'a [Idx]) -> Graph<'a> {
+        self.try_set_vsize(vsize)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Graph::set_vsize`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`NewGraphError::LengthMismatch`] if the length of
+    /// `vsize` is not the number of vertices.
+    pub fn try_set_vsize(mut self, vsize: &'a [Idx]) -> StdResult<Graph<'a>, NewGraphError> {
+        NewGraphError::check_len("vsize", self.xadj.len() - 1, vsize.len())?;
         self.vsize = Some(vsize);
-        self
+        Ok(self)
     }
 
     /// Sets the weights of the edges.
@@ -451,15 +1786,47 @@ impl<'a> Graph<'a> {
     ///
     /// All elements of `adjwgt` must be positive.
     ///
+    /// This is a thin wrapper around [`Graph::try_set_adjwgt`].
+    ///
     /// # Panics
     ///
     /// This function panics if the length of `adjwgt` is not equal to the
-    /// length of `adjncy`.
-    pub fn set_adjwgt(mut self, adjwgt: &'a [Idx]) -> Graph<'a> {
-        let adjwgt_len = Idx::try_from(adjwgt.len()).expect("adjwgt array too large");
-        assert_eq!(adjwgt_len, *self.xadj.last().unwrap());
+    /// length of `adjncy`, or if some weights are not strictly positive.
+    pub fn set_adjwgt(self, adjwgt: &'a [Idx]) -> Graph<'a> {
+        self.try_set_adjwgt(adjwgt)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Graph::set_adjwgt`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`NewGraphError::LengthMismatch`] if the length of
+    /// `adjwgt` is not equal to the length of `adjncy`, and
+    /// [`NewGraphError::NonPositiveValue`] with the position of the first
+    /// edge whose weight is zero or negative.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use metis::{Graph, NewGraphError};
+    /// // 0 - 1 - 2
+    /// let xadj = &[0, 1, 3, 4];
+    /// let adjncy = &[1, 0, 2, 1];
+    /// let graph = || Graph::new(1, 2, xadj, adjncy).unwrap();
+    ///
+    /// assert!(matches!(
+    ///     graph().try_set_adjwgt(&[1, 1, 0, 0]),
+    ///     Err(NewGraphError::NonPositiveValue { array: "adjwgt", index: 2, value: 0 }),
+    /// ));
+    /// assert!(graph().try_set_adjwgt(&[-1, -1, 1, 1]).is_err());
+    /// assert!(graph().try_set_adjwgt(&[2, 2, 1, 1]).is_ok());
+    /// ```
+    pub fn try_set_adjwgt(mut self, adjwgt: &'a [Idx]) -> StdResult<Graph<'a>, NewGraphError> {
+        NewGraphError::check_len("adjwgt", self.adjncy.len(), adjwgt.len())?;
+        NewGraphError::check_positive("adjwgt", adjwgt)?;
         self.adjwgt = Some(adjwgt);
-        self
+        Ok(self)
     }
 
     /// Sets the target partition weights for each part and constraint.
@@ -471,15 +1838,78 @@ impl<'a> Graph<'a> {
     /// target partition weights must be 1.0. Meaning
     /// `(0..nparts).map(|i| tpwgts[i*ncon+j]).sum() == 1.0`.
     ///
+    /// This is a thin wrapper around [`Graph::try_set_tpwgts`].
+    ///
     /// # Panics
     ///
     /// This function panics if the length of `tpwgts` is not equal to `ncon`
-    /// times `nparts`.
-    pub fn set_tpwgts(mut self, tpwgts: &'a [Real]) -> Graph<'a> {
-        let tpwgts_len = Idx::try_from(tpwgts.len()).expect("tpwgts array too large");
-        assert_eq!(tpwgts_len, self.ncon * self.nparts);
+    /// times `nparts`, or if the weights are not valid (see
+    /// [`Graph::try_set_tpwgts`]).
+    pub fn set_tpwgts(self, tpwgts: &'a [Real]) -> Graph<'a> {
+        self.try_set_tpwgts(tpwgts)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Graph::set_tpwgts`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns:
+    ///
+    /// - [`NewGraphError::LengthMismatch`] if the length of `tpwgts` is not
+    ///   equal to `ncon` times `nparts`,
+    /// - [`NewGraphError::TooLarge`] if `ncon` times `nparts` does not fit in
+    ///   [`Idx`],
+    /// - [`NewGraphError::InvalidValue`] if a weight is not strictly positive
+    ///   (zero weights make METIS divide by zero),
+    /// - [`NewGraphError::TargetWeightSum`] if the weights of a constraint do
+    ///   not sum to 1.0, give or take rounding errors.
+    ///
+    /// # Example
+    ///
+    /// Weights are laid out part by part: all the constraints of the first
+    /// part, then all the constraints of the second part, and so on.
+    ///
+    /// ```rust
+    /// # use metis::{Graph, NewGraphError};
+    /// // 0 - 1 - 2 - 3
+    /// let xadj = &[0, 1, 3, 5, 6];
+    /// let adjncy = &[1, 0, 2, 1, 3, 2];
+    /// let graph = || Graph::new(2, 2, xadj, adjncy).unwrap();
+    ///
+    /// // Part 0 gets 25% of the first constraint and 50% of the second one.
+    /// assert!(graph().try_set_tpwgts(&[0.25, 0.5, 0.75, 0.5]).is_ok());
+    ///
+    /// // Laying weights out constraint by constraint is a mistake.
+    /// assert!(matches!(
+    ///     graph().try_set_tpwgts(&[0.25, 0.75, 0.5, 0.5]),
+    ///     Err(NewGraphError::TargetWeightSum { constraint: 0, .. }),
+    /// ));
+    ///
+    /// assert!(matches!(
+    ///     graph().try_set_tpwgts(&[1.0, 0.5, 0.0, 0.5]),
+    ///     Err(NewGraphError::InvalidValue { array: "tpwgts", index: 2, .. }),
+    /// ));
+    ///
+    /// // The expected length is computed without overflowing, so that it
+    /// // cannot wrap around to the length of a short array.
+    /// let huge = Graph::new(metis::Idx::MAX, metis::Idx::MAX, xadj, adjncy).unwrap();
+    /// assert!(matches!(
+    ///     huge.try_set_tpwgts(&[1.0]),
+    ///     Err(NewGraphError::TooLarge),
+    /// ));
+    /// let huge = Graph::new(metis::Idx::MAX, 1, xadj, adjncy).unwrap();
+    /// assert!(matches!(huge.try_set_vwgt(&[1; 4]), Err(NewGraphError::TooLarge)));
+    /// ```
+    pub fn try_set_tpwgts(mut self, tpwgts: &'a [Real]) -> StdResult<Graph<'a>, NewGraphError> {
+        NewGraphError::check_len(
+            "tpwgts",
+            array_len(self.ncon, self.nparts as usize)?,
+            tpwgts.len(),
+        )?;
+        check_tpwgts(tpwgts, self.ncon as usize)?;
         self.tpwgts = Some(tpwgts);
-        self
+        Ok(self)
     }
 
     /// Sets the load imbalance tolerance for each constraint.
@@ -490,14 +1920,48 @@ impl<'a> Graph<'a> {
     /// `ubvec[j]*tpwgts[i*ncon+j]` fraction of the `j`th's constraint total
     /// weight. The load imbalances must be greater than 1.0.
     ///
+    /// This is a thin wrapper around [`Graph::try_set_ubvec`].
+    ///
     /// # Panics
     ///
-    /// This function panics if the length of `ubvec` is not equal to `ncon`.
-    pub fn set_ubvec(mut self, ubvec: &'a [Real]) -> Graph<'a> {
-        let ubvec_len = Idx::try_from(ubvec.len()).expect("ubvec array too large");
-        assert_eq!(ubvec_len, self.ncon);
+    /// This function panics if the length of `ubvec` is not equal to `ncon`,
+    /// or if some tolerances are below 1.0, infinite or NaN.  Tolerances are
+    /// not clamped.
+    pub fn set_ubvec(self, ubvec: &'a [Real]) -> Graph<'a> {
+        self.try_set_ubvec(ubvec)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Graph::set_ubvec`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`NewGraphError::LengthMismatch`] if the length of
+    /// `ubvec` is not equal to `ncon`, and [`NewGraphError::InvalidValue`] if
+    /// a tolerance is below 1.0, infinite or NaN.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use metis::{Graph, NewGraphError};
+    /// // 0 - 1 - 2
+    /// let xadj = &[0, 1, 3, 4];
+    /// let adjncy = &[1, 0, 2, 1];
+    /// let graph = || Graph::new(2, 2, xadj, adjncy).unwrap();
+    ///
+    /// assert!(matches!(
+    ///     graph().try_set_ubvec(&[1.05, 0.9]),
+    ///     Err(NewGraphError::InvalidValue { array: "ubvec", index: 1, .. }),
+    /// ));
+    /// assert!(graph().try_set_ubvec(&[f32::NAN, 1.1]).is_err());
+    /// assert!(graph().try_set_ubvec(&[1.0, f32::INFINITY]).is_err());
+    /// assert!(graph().try_set_ubvec(&[1.0, 1.1]).is_ok());
+    /// ```
+    pub fn try_set_ubvec(mut self, ubvec: &'a [Real]) -> StdResult<Graph<'a>, NewGraphError> {
+        NewGraphError::check_len("ubvec", self.ncon as usize, ubvec.len())?;
+        NewGraphError::check_ubvec(ubvec)?;
         self.ubvec = Some(ubvec);
-        self
+        Ok(self)
     }
 
     /// Sets the fine-tuning parameters for this partitioning.
@@ -516,17 +1980,15 @@ impl<'a> Graph<'a> {
     /// ```rust
     /// # fn main() -> Result<(), metis::Error> {
     /// # use metis::Graph;
-    /// use metis::option::Opt as _;
+    /// use metis::option::OptionsBuilder;
     ///
     /// let xadj = &[0, 1, 2];
     /// let adjncy = &[1, 0];
     /// let mut part = [0, 0];
     ///
-    /// // -1 is the default value.
-    /// let mut options = [-1; metis::NOPTIONS];
-    ///
-    /// // four refinement iterations instead of the default 10.
-    /// options[metis::option::NIter::INDEX] = 4;
+    /// // four refinement iterations instead of the default 10, other options
+    /// // are left to -1, the default value.
+    /// let options = OptionsBuilder::new().niter(4).build();
     ///
     /// Graph::new(1, 2, xadj, adjncy)?
     ///     .set_options(&options)
@@ -579,6 +2041,118 @@ impl<'a> Graph<'a> {
         self
     }
 
+    /// Sets the fine-tuning parameters for the next partitionings, in place.
+    ///
+    /// This is the non-consuming version of [`Graph::set_options`].
+    pub fn set_options_mut(&mut self, options: &[Idx; NOPTIONS]) -> &mut Graph<'a> {
+        self.options.copy_from_slice(options);
+        self
+    }
+
+    /// Sets the seed for the random number generator of METIS.
+    ///
+    /// Shorthand for `set_option(Seed(seed))`, see [`option::Seed`] for the
+    /// default behavior.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// # use metis::Graph;
+    /// // A 4x4 grid.
+    /// let xadj = &[0, 2, 5, 8, 10, 13, 17, 21, 24, 27, 31, 35, 38, 40, 43, 46, 48];
+    /// let adjncy = &[
+    ///     1, 4, 0, 2, 5, 1, 3, 6, 2, 7, 0, 5, 8, 1, 4, 6, 9, 2, 5, 7, 10, 3, 6,
+    ///     11, 4, 9, 12, 5, 8, 10, 13, 6, 9, 11, 14, 7, 10, 15, 8, 13, 9, 12, 14,
+    ///     10, 13, 15, 11, 14,
+    /// ];
+    ///
+    /// let partition = |seed| -> Result<_, metis::Error> {
+    ///     let mut part = [0; 16];
+    ///     Graph::new(1, 3, xadj, adjncy)?
+    ///         .set_seed(seed)
+    ///         .part_kway(&mut part)?;
+    ///     Ok(part)
+    /// };
+    ///
+    /// // The same seed gives the same partition.
+    /// assert_eq!(partition(42)?, partition(42)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_seed(self, seed: Idx) -> Graph<'a> {
+        self.set_option(option::Seed(seed))
+    }
+
+    /// Sets a fine-tuning parameter for the next partitionings, in place.
+    ///
+    /// This is the non-consuming version of [`Graph::set_option`], useful to
+    /// partition the same graph several times with different parameters.  See
+    /// [`Graph::part_kway`] for an example.
+    pub fn set_option_mut<O>(&mut self, option: O) -> &mut Graph<'a>
+    where
+        O: option::Opt,
+    {
+        self.options[O::INDEX] = option.value();
+        self
+    }
+
+    /// Changes the number of parts to partition the graph into.
+    ///
+    /// Unlike building a new [`Graph`], this does not check the graph again,
+    /// which makes it cheap to partition the same graph into several numbers
+    /// of parts.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error, and leaves the graph untouched, if
+    /// `nparts` is not strictly greater than zero, or if target partition
+    /// weights were set (see [`Graph::set_tpwgts`]) and their length is not
+    /// `ncon` times the new `nparts`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::GraphBuilder;
+    ///
+    /// // A 16x16 grid.
+    /// let mut builder = GraphBuilder::new(256)?;
+    /// for i in 0..16 {
+    ///     for j in 0..15 {
+    ///         builder.add_edge(16 * i + j, 16 * i + j + 1)?;
+    ///         builder.add_edge(16 * j + i, 16 * j + i + 16)?;
+    ///     }
+    /// }
+    /// let owned = builder.build(1, 2)?;
+    /// let mut graph = owned.as_graph();
+    ///
+    /// let mut part = [0; 256];
+    /// for nparts in [2, 4, 8, 16, 32] {
+    ///     graph.set_nparts(nparts)?;
+    ///     graph.part_kway(&mut part)?;
+    ///     assert!(part.iter().all(|p| (0..nparts).contains(p)));
+    /// }
+    ///
+    /// assert!(graph.set_nparts(0).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_nparts(&mut self, nparts: Idx) -> StdResult<(), NewGraphError> {
+        if nparts <= 0 {
+            return Err(NewGraphError::NoParts);
+        }
+        if let Some(tpwgts) = self.tpwgts {
+            NewGraphError::check_len(
+                "tpwgts",
+                array_len(self.ncon, nparts as usize)?,
+                tpwgts.len(),
+            )?;
+        }
+        self.nparts = nparts;
+        Ok(())
+    }
+
     /// Partition the graph using multilevel recursive bisection.
     ///
     /// Returns the edge-cut, the total communication volume of the
@@ -586,28 +2160,238 @@ impl<'a> Graph<'a> {
     ///
     /// Equivalent of `METIS_PartGraphRecursive`.
     ///
-    /// # Panics
+    /// The graph is left as is, so it can be partitioned again, for example
+    /// with other options (see [`Graph::set_option_mut`]).
     ///
-    /// This function panics if the length of `part` is not the number of
-    /// vertices.
-    pub fn part_recursive(mut self, part: &mut [Idx]) -> Result<Idx> {
-        self.options[option::Numbering::INDEX] = option::Numbering::C.value();
-        let part_len = Idx::try_from(part.len()).expect("part array larger than Idx::MAX");
-        assert_eq!(
-            part_len,
-            self.xadj.len() as Idx - 1,
-            "part.len() must be equal to the number of vertices",
-        );
-
-        if self.nparts == 1 {
-            // METIS does not handle this case well.
-            part.fill(0);
-            return Ok(0);
-        }
-
-        let nvtxs = self.xadj.len() as Idx - 1;
+    /// # More parts than vertices
+    ///
+    /// METIS scatters vertices among parts unpredictably when `nparts` is
+    /// greater than the number of vertices.  Instead, the `i`th vertex is put
+    /// in the `i`th part, the remaining parts are left empty, and the returned
+    /// edge-cut is the total weight of the edges, since all of them are cut.
+    /// METIS is not called.
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// // 0 - 1 - 2
+    /// let xadj = &[0, 1, 3, 4];
+    /// let adjncy = &[1, 0, 2, 1];
+    /// let mut part = [0; 3];
+    ///
+    /// let edgecut = metis::Graph::new(1, 16, xadj, adjncy)?
+    ///     .set_adjwgt(&[5, 5, 2, 2])
+    ///     .part_recursive(&mut part)?;
+    /// assert_eq!(edgecut, 7);
+    /// assert_eq!(part, [0, 1, 2]);
+    ///
+    /// let edgecut = metis::Graph::new(1, 4, xadj, adjncy)?.part_kway(&mut part)?;
+    /// assert_eq!(edgecut, 2);
+    /// assert_eq!(part, [0, 1, 2]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// In particular, partitioning a graph without vertices (`xadj == [0]`)
+    /// is a no-op that returns an edge-cut of zero.
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// let mut graph = metis::Graph::new(1, 4, &[0], &[])?;
+    /// assert_eq!(graph.part_recursive(&mut [])?, 0);
+    /// assert_eq!(graph.part_kway(&mut [])?, 0);
+    ///
+    /// let (part, edgecut) = graph.part_kway_owned()?;
+    /// assert!(part.is_empty());
+    /// assert_eq!(edgecut, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Graphs without edges
+    ///
+    /// METIS does not handle graphs where all vertices are isolated
+    /// consistently.  For such graphs, METIS is not called: vertices are
+    /// assigned in order to the part that is the least loaded relative to its
+    /// target weight (see [`Graph::set_tpwgts`]), and the returned edge-cut is
+    /// zero.  Only the first constraint is taken into account.
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// let xadj = &[0; 7];
+    /// let mut part = [0; 6];
+    ///
+    /// let edgecut = metis::Graph::new(1, 3, xadj, &[])?.part_kway(&mut part)?;
+    /// assert_eq!(edgecut, 0);
+    /// assert_eq!(part, [0, 1, 2, 0, 1, 2]);
+    ///
+    /// let edgecut = metis::Graph::new(1, 2, xadj, &[])?
+    ///     .set_tpwgts(&[0.25, 0.75])
+    ///     .set_vwgt(&[3, 1, 1, 1, 1, 1])
+    ///     .part_recursive(&mut part)?;
+    /// assert_eq!(edgecut, 0);
+    /// // Part 0 gets a weight of 2, part 1 a weight of 6.
+    /// assert_eq!(part, [1, 0, 1, 1, 0, 1]);
+    ///
+    /// // Isolated vertices among connected ones are left to METIS.
+    /// // 0 - 1   2   3 - 4   5
+    /// let xadj = &[0, 1, 2, 2, 3, 4, 4];
+    /// let adjncy = &[1, 0, 4, 3];
+    /// let edgecut = metis::Graph::new(1, 2, xadj, adjncy)?.part_kway(&mut part)?;
+    /// assert_eq!(edgecut, 0);
+    /// assert_eq!(part[0], part[1]);
+    /// assert_eq!(part[3], part[4]);
+    /// assert_eq!(part.iter().filter(|p| **p == 0).count(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `part` is not the number of
+    /// vertices.
+    pub fn part_recursive(&mut self, part: &mut [Idx]) -> Result<Idx> {
+        assert_eq!(
+            part.len(),
+            self.xadj.len() - 1,
+            "part.len() must be equal to the number of vertices",
+        );
+        self.try_part_recursive(part)
+    }
+
+    /// Puts the `i`th vertex in the `i`th part, and returns the edge-cut of
+    /// this partition, which is the total weight of the edges.
+    ///
+    /// Used when there are more parts than vertices.  Returns
+    /// [`NewGraphError::TooLarge`] if the edge-cut does not fit in [`Idx`].
+    fn part_singletons(&self, part: &mut [Idx]) -> Result<Idx> {
+        for (v, p) in part.iter_mut().enumerate() {
+            *p = v as Idx;
+        }
+        let total = match self.adjwgt {
+            Some(adjwgt) => adjwgt.iter().map(|w| i64::from(*w)).sum::<i64>(),
+            None => self.adjncy.len() as i64,
+        };
+        Ok(Idx::try_from(total / 2).map_err(|_| NewGraphError::TooLarge)?)
+    }
+
+    /// Assigns vertices one by one to the part that is the least loaded
+    /// relative to its target weight, which is a round-robin when vertices
+    /// have the same weight and parts have the same target.
+    ///
+    /// Used when the graph has no edges, since any balanced partition is then
+    /// optimal.  Only the first constraint is balanced.
+    fn part_without_edges(&self, part: &mut [Idx]) {
+        let ncon = self.ncon as usize;
+        let nparts = self.nparts as usize;
+        let target = |p: usize| match self.tpwgts {
+            Some(tpwgts) => f64::from(tpwgts[p * ncon]),
+            None => 1.0,
+        };
+        let weight = |v: usize| match self.vwgt {
+            Some(vwgt) => f64::from(vwgt[v * ncon]),
+            None => 1.0,
+        };
+        let mut loads = vec![0.0; nparts];
+        for (v, pv) in part.iter_mut().enumerate() {
+            let w = weight(v);
+            let mut best = 0;
+            for p in 1..nparts {
+                if (loads[p] + w) / target(p) < (loads[best] + w) / target(best) {
+                    best = p;
+                }
+            }
+            loads[best] += w;
+            *pv = best as Idx;
+        }
+    }
+
+    /// Whether `tpwgts` and `ubvec` still have the length expected from `ncon`
+    /// and `nparts`, which might have changed since they were set.
+    fn optional_lengths_match(&self) -> bool {
+        let ncon = self.ncon as usize;
+        if let Some(tpwgts) = self.tpwgts {
+            if ncon.checked_mul(self.nparts as usize) != Some(tpwgts.len()) {
+                return false;
+            }
+        }
+        if let Some(ubvec) = self.ubvec {
+            if ubvec.len() != ncon {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Partition the graph using multilevel recursive bisection, with the
+    /// given options.
+    ///
+    /// The options set in `options` take precedence over the ones of the
+    /// graph for this partitioning only: the options of the graph are left
+    /// unchanged.  See [`option::RbOptions`] for an example.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `part` is not the number of
+    /// vertices.
+    pub fn part_recursive_with(
+        &mut self,
+        options: &option::RbOptions,
+        part: &mut [Idx],
+    ) -> Result<Idx> {
+        let saved = self.options;
+        options.apply(&mut self.options);
+        let res = self.part_recursive(part);
+        self.options = saved;
+        res
+    }
+
+    /// Fallible version of [`Graph::part_recursive`].
+    ///
+    /// # Errors
+    ///
+    /// On top of the errors reported by METIS, this function returns
+    /// [`Error::Input`] instead of panicking if the length of `part` is not the
+    /// number of vertices, if `tpwgts` or `ubvec` no longer match `ncon` and
+    /// `nparts`, if an option holds an illegal value (see
+    /// [`option::InvalidOptionError`]), or if there are more parts than
+    /// vertices and the total weight of the edges does not fit in [`Idx`].
+    pub fn try_part_recursive(&mut self, part: &mut [Idx]) -> Result<Idx> {
+        self.options[Numbering::INDEX] = Numbering::C.value();
+        if part.len() != self.xadj.len() - 1 || !self.optional_lengths_match() {
+            return Err(Error::Input(None));
+        }
+        option::check(&self.options)?;
+        if self.numbering == Numbering::Fortran {
+            let xadj = zero_based(self.xadj);
+            let adjncy = zero_based(self.adjncy);
+            let mut graph = Graph {
+                xadj: &xadj,
+                adjncy: &adjncy,
+                numbering: Numbering::C,
+                ..*self
+            };
+            let edgecut = graph.try_part_recursive(part)?;
+            part.iter_mut().for_each(|p| *p += 1);
+            return Ok(edgecut);
+        }
+
+        if self.nparts == 1 {
+            // METIS does not handle this case well.
+            part.fill(0);
+            return Ok(0);
+        }
+
+        let nvtxs = self.xadj.len() as Idx - 1;
+        if self.nparts > nvtxs {
+            return self.part_singletons(part);
+        }
+        if self.adjncy.is_empty() {
+            self.part_without_edges(part);
+            return Ok(0);
+        }
         let mut edgecut = mem::MaybeUninit::uninit();
         let part = part.as_mut_ptr();
+        let _lock = lock_metis();
         unsafe {
             m::METIS_PartGraphRecursive(
                 &nvtxs as *const Idx as *mut Idx,
@@ -634,58 +2418,953 @@ impl<'a> Graph<'a> {
         }
     }
 
-    /// Partition the graph using multilevel k-way partitioning.
+    /// Partition the graph using multilevel k-way partitioning.
+    ///
+    /// Returns the edge-cut, the total communication volume of the
+    /// partitioning solution.
+    ///
+    /// Equivalent of `METIS_PartGraphKway`.
+    ///
+    /// The graph is left as is, so it can be partitioned again, for example
+    /// with other options (see [`Graph::set_option_mut`]).
+    ///
+    /// When `nparts` is greater than the number of vertices, each vertex gets
+    /// its own part, as described in [`Graph::part_recursive`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `part` is not the number of
+    /// vertices.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::option::Seed;
+    /// use metis::GraphBuilder;
+    ///
+    /// // A 4x4 grid.
+    /// let mut builder = GraphBuilder::new(16)?;
+    /// for i in 0..4 {
+    ///     for j in 0..3 {
+    ///         builder.add_edge(4 * i + j, 4 * i + j + 1)?;
+    ///         builder.add_edge(4 * j + i, 4 * j + i + 4)?;
+    ///     }
+    /// }
+    /// let owned = builder.build(1, 4)?;
+    /// let mut graph = owned.as_graph();
+    ///
+    /// // Keep the best of five partitions.
+    /// let mut best = None;
+    /// for seed in 0..5 {
+    ///     let mut part = [0; 16];
+    ///     let edgecut = graph.set_option_mut(Seed(seed)).part_kway(&mut part)?;
+    ///     assert!(part.iter().all(|p| (0..4).contains(p)));
+    ///     if best.map_or(true, |(best_edgecut, _)| edgecut < best_edgecut) {
+    ///         best = Some((edgecut, part));
+    ///     }
+    /// }
+    /// let (edgecut, _part) = best.unwrap();
+    /// assert!(8 <= edgecut);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn part_kway(&mut self, part: &mut [Idx]) -> Result<Idx> {
+        assert_eq!(
+            part.len(),
+            self.xadj.len() - 1,
+            "part.len() must be equal to the number of vertices",
+        );
+        self.try_part_kway(part)
+    }
+
+    /// Partition the graph using multilevel k-way partitioning, with the given
+    /// options.
+    ///
+    /// The options set in `options` take precedence over the ones of the
+    /// graph for this partitioning only: the options of the graph are left
+    /// unchanged.  See [`option::KwayOptions`] for an example.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `part` is not the number of
+    /// vertices.
+    pub fn part_kway_with(
+        &mut self,
+        options: &option::KwayOptions,
+        part: &mut [Idx],
+    ) -> Result<Idx> {
+        let saved = self.options;
+        options.apply(&mut self.options);
+        let res = self.part_kway(part);
+        self.options = saved;
+        res
+    }
+
+    /// Fallible version of [`Graph::part_kway`].
+    ///
+    /// # Errors
+    ///
+    /// On top of the errors reported by METIS, this function returns
+    /// [`Error::Input`] instead of panicking if the length of `part` is not the
+    /// number of vertices.  Like [`Graph::try_part_recursive`], it also returns
+    /// [`Error::Input`] if an option holds an illegal value, or if there are
+    /// more parts than vertices and the edge-cut, which is then the total
+    /// weight of the edges, does not fit in [`Idx`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::{Error, Graph};
+    ///
+    /// // 0 - 1 - 2
+    /// let xadj = &[0, 1, 3, 4];
+    /// let adjncy = &[1, 0, 2, 1];
+    /// let mut graph = Graph::new(1, 2, xadj, adjncy)?;
+    ///
+    /// assert_eq!(graph.try_part_kway(&mut [0; 2]), Err(Error::Input(None)));
+    /// assert_eq!(graph.try_part_kway(&mut [0; 4]), Err(Error::Input(None)));
+    /// assert_eq!(graph.try_part_recursive(&mut []), Err(Error::Input(None)));
+    ///
+    /// // The length is also checked when there is only one part.
+    /// let mut graph = Graph::new(1, 1, xadj, adjncy)?;
+    /// assert_eq!(graph.try_part_kway(&mut [0; 4]), Err(Error::Input(None)));
+    /// assert_eq!(graph.try_part_kway(&mut [0; 3]), Ok(0));
+    ///
+    /// // With more parts than vertices, every edge is cut.
+    /// let adjwgt = &[metis::Idx::MAX; 4];
+    /// let mut graph = Graph::new(1, 4, xadj, adjncy)?.set_adjwgt(&adjwgt[..]);
+    /// assert_eq!(graph.try_part_kway(&mut [0; 3]), Err(Error::Input(None)));
+    /// graph = graph.set_adjwgt(&[1, 1, 1, 1]);
+    /// assert_eq!(graph.try_part_kway(&mut [0; 3]), Ok(2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_part_kway(&mut self, part: &mut [Idx]) -> Result<Idx> {
+        self.options[Numbering::INDEX] = Numbering::C.value();
+        if part.len() != self.xadj.len() - 1 || !self.optional_lengths_match() {
+            return Err(Error::Input(None));
+        }
+        option::check(&self.options)?;
+        if self.numbering == Numbering::Fortran {
+            let xadj = zero_based(self.xadj);
+            let adjncy = zero_based(self.adjncy);
+            let mut graph = Graph {
+                xadj: &xadj,
+                adjncy: &adjncy,
+                numbering: Numbering::C,
+                ..*self
+            };
+            let edgecut = graph.try_part_kway(part)?;
+            part.iter_mut().for_each(|p| *p += 1);
+            return Ok(edgecut);
+        }
+
+        if self.nparts == 1 {
+            // METIS does not handle this case well.
+            part.fill(0);
+            return Ok(0);
+        }
+
+        let nvtxs = self.xadj.len() as Idx - 1;
+        if self.nparts > nvtxs {
+            return self.part_singletons(part);
+        }
+        if self.adjncy.is_empty() {
+            self.part_without_edges(part);
+            return Ok(0);
+        }
+        let mut edgecut = mem::MaybeUninit::uninit();
+        let part = part.as_mut_ptr();
+        let _lock = lock_metis();
+        unsafe {
+            m::METIS_PartGraphKway(
+                &nvtxs as *const Idx as *mut Idx,
+                &self.ncon as *const Idx as *mut Idx,
+                slice_to_mut_ptr(self.xadj),
+                slice_to_mut_ptr(self.adjncy),
+                self.vwgt
+                    .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
+                self.vsize
+                    .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
+                self.adjwgt
+                    .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
+                &self.nparts as *const Idx as *mut Idx,
+                self.tpwgts
+                    .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
+                self.ubvec
+                    .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
+                slice_to_mut_ptr(&self.options),
+                edgecut.as_mut_ptr(),
+                part,
+            )
+            .wrap()?;
+            Ok(edgecut.assume_init())
+        }
+    }
+
+    /// Partition the graph using multilevel recursive bisection, on a
+    /// dedicated thread with a stack of `stack_size` bytes.
+    ///
+    /// This function runs [`Graph::try_part_recursive`] on a new thread and
+    /// waits for it.  It is meant for callers whose own stack is too small
+    /// for METIS, e.g. threads of a pool configured with small stacks, since
+    /// overflowing the stack crashes the process.
+    ///
+    /// # Errors
+    ///
+    /// On top of the errors of [`Graph::try_part_recursive`], this function
+    /// returns [`Error::Other`] if the thread cannot be spawned.
+    ///
+    /// # Panics
+    ///
+    /// A panic of the spawned thread is resumed on the calling thread.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use std::thread;
+    ///
+    /// // A path.
+    /// let n = 1000;
+    /// let xadj: Vec<metis::Idx> = (0..=n).map(|v| (2 * v - 1).clamp(0, 2 * n - 2)).collect();
+    /// let adjncy: Vec<metis::Idx> = (0..n)
+    ///     .flat_map(|v| [v - 1, v + 1])
+    ///     .filter(|u| 0 <= *u && *u < n)
+    ///     .collect();
+    /// let graph = metis::Graph::new(1, 8, &xadj, &adjncy)?;
+    ///
+    /// // A thread with a small stack, that partitions on a thread with a
+    /// // larger one.
+    /// let mut part = vec![0; n as usize];
+    /// let edgecut = thread::scope(|scope| {
+    ///     thread::Builder::new()
+    ///         .stack_size(64 << 10)
+    ///         .spawn_scoped(scope, || graph.part_recursive_spawned(&mut part, 8 << 20))
+    ///         .unwrap()
+    ///         .join()
+    ///         .unwrap()
+    /// })?;
+    /// assert!(7 <= edgecut);
+    /// assert!(part.iter().all(|p| (0..8).contains(p)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn part_recursive_spawned(self, part: &mut [Idx], stack_size: usize) -> Result<Idx> {
+        let mut graph = self;
+        thread::scope(|scope| {
+            thread::Builder::new()
+                .stack_size(stack_size)
+                .spawn_scoped(scope, move || graph.try_part_recursive(part))
+                .map_err(|err| Error::Other(Some(Box::new(err))))?
+                .join()
+                .unwrap_or_else(|payload| panic::resume_unwind(payload))
+        })
+    }
+
+    /// Partition the graph using multilevel recursive bisection, and return
+    /// the partition along with the edge-cut.
+    ///
+    /// Unlike [`Graph::part_recursive`], this allocates the partition array,
+    /// so it cannot have the wrong length.  Parts are numbered from zero, even
+    /// for graphs created with [`Graph::new_fortran`].
+    pub fn part_recursive_owned(&mut self) -> Result<(PartitionVec, Idx)> {
+        let mut part = vec![0; self.xadj.len() - 1];
+        let edgecut = self.try_part_recursive(&mut part)?;
+        let base = self.numbering.value();
+        part.iter_mut().for_each(|p| *p -= base);
+        Ok((PartitionVec::new_unchecked(part, self.nparts), edgecut))
+    }
+
+    /// Partition the graph using multilevel k-way partitioning, and return
+    /// the partition along with the edge-cut.
+    ///
+    /// Unlike [`Graph::part_kway`], this allocates the partition array, so it
+    /// cannot have the wrong length.  Parts are numbered from zero, even for
+    /// graphs created with [`Graph::new_fortran`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// // 0 - 1 - 2 - 3
+    /// let xadj = &[0, 1, 3, 5, 6];
+    /// let adjncy = &[1, 0, 2, 1, 3, 2];
+    ///
+    /// let (part, edgecut) = metis::Graph::new(1, 2, xadj, adjncy)?.part_kway_owned()?;
+    /// assert_eq!(part.len(), 4);
+    /// assert_eq!(edgecut, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn part_kway_owned(&mut self) -> Result<(PartitionVec, Idx)> {
+        let mut part = vec![0; self.xadj.len() - 1];
+        let edgecut = self.try_part_kway(&mut part)?;
+        let base = self.numbering.value();
+        part.iter_mut().for_each(|p| *p -= base);
+        Ok((PartitionVec::new_unchecked(part, self.nparts), edgecut))
+    }
+
+    /// Partition the graph with [`Graph::part_kway`] once for each number of
+    /// parts in `ks`, and report the results in the same order.
+    ///
+    /// This helps choosing a number of parts, by looking at how the edge-cut
+    /// and the balance evolve.  Part weights are computed on the first
+    /// constraint.  With the `rayon` feature, partitions are computed on
+    /// several threads, with calls to METIS serialized so that results are
+    /// the same as sequential ones.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the first error of [`Graph::set_nparts`] and
+    /// [`Graph::part_kway`] in `ks` order, e.g. if some `k` is not strictly
+    /// positive.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::GraphBuilder;
+    ///
+    /// // An 8x8 grid.
+    /// let mut builder = GraphBuilder::new(64)?;
+    /// for i in 0..8 {
+    ///     for j in 0..7 {
+    ///         builder.add_edge(8 * i + j, 8 * i + j + 1)?;
+    ///         builder.add_edge(8 * j + i, 8 * j + i + 8)?;
+    ///     }
+    /// }
+    /// let owned = builder.build(1, 2)?;
+    ///
+    /// let sweep = owned.as_graph().sweep_nparts([1, 2, 4, 8])?;
+    /// let nparts: Vec<_> = sweep.iter().map(|entry| entry.nparts).collect();
+    /// assert_eq!(nparts, [1, 2, 4, 8]);
+    /// assert_eq!(sweep[0].edgecut, 0);
+    /// assert_eq!(sweep[0].max_part_weight, 64);
+    /// for entry in &sweep {
+    ///     assert_eq!(entry.part.nparts(), entry.nparts);
+    ///     assert!(entry.min_part_weight <= i64::from(64 / entry.nparts));
+    ///     assert!(entry.max_part_weight >= i64::from(64 / entry.nparts));
+    /// }
+    ///
+    /// assert!(owned.as_graph().sweep_nparts([2, 0]).is_err());
+    ///
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// let json = serde_json::to_string(&sweep).unwrap();
+    /// assert_eq!(serde_json::from_str::<Vec<metis::SweepEntry>>(&json).unwrap(), sweep);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sweep_nparts(&mut self, ks: impl IntoIterator<Item = Idx>) -> Result<Vec<SweepEntry>> {
+        let this = &*self;
+        let sweep = |nparts| -> Result<SweepEntry> {
+            let mut graph = Graph { ..*this };
+            graph.set_nparts(nparts)?;
+            let (part, edgecut) = graph.part_kway_owned()?;
+            Ok(SweepEntry::new(edgecut, part, this.vwgt, this.ncon))
+        };
+        let ks: Vec<Idx> = ks.into_iter().collect();
+
+        #[cfg(feature = "rayon")]
+        let entries = parallel::map(ks, sweep).into_iter().collect();
+        #[cfg(not(feature = "rayon"))]
+        let entries = ks.into_iter().map(sweep).collect();
+
+        entries
+    }
+}
+
+/// The arrays and parameters of a [`Graph`], returned by [`Graph::into_inner`].
+///
+/// See the setters of [`Graph`] for the meaning of each field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct GraphParts<'a> {
+    /// The number of balancing constraints.
+    pub ncon: Idx,
+
+    /// The number of parts to partition the graph.
+    pub nparts: Idx,
+
+    /// The adjacency index array.
+    pub xadj: &'a [Idx],
+
+    /// The adjacency array.
+    pub adjncy: &'a [Idx],
+
+    /// The computational weights of the vertices, if set.
+    pub vwgt: Option<&'a [Idx]>,
+
+    /// The communication weights of the vertices, if set.
+    pub vsize: Option<&'a [Idx]>,
+
+    /// The weights of the edges, if set.
+    pub adjwgt: Option<&'a [Idx]>,
+
+    /// The target partition weights, if set.
+    pub tpwgts: Option<&'a [Real]>,
+
+    /// The imbalance tolerances, if set.
+    pub ubvec: Option<&'a [Real]>,
+
+    /// The fine-tuning parameters.
+    pub options: [Idx; NOPTIONS],
+}
+
+/// Builder structure to set up a graph partition computation, that owns its
+/// arrays.
+///
+/// Unlike [`Graph`], this structure can easily be stored, returned from
+/// functions or sent to other threads.  Its methods mirror the ones of
+/// [`Graph`], and [`GraphOwned::as_graph`] gives access to the latter.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::GraphOwned;
+///
+/// // A path of `n` vertices, with a light edge in the middle.
+/// fn path(n: metis::Idx) -> Result<GraphOwned, metis::NewGraphError> {
+///     let mut xadj = vec![0];
+///     let mut adjncy = Vec::new();
+///     let mut adjwgt = Vec::new();
+///     for v in 0..n {
+///         for u in [v - 1, v + 1] {
+///             if 0 <= u && u < n {
+///                 adjncy.push(u);
+///                 adjwgt.push(if u + v == n - 1 { 1 } else { 10 });
+///             }
+///         }
+///         xadj.push(adjncy.len() as metis::Idx);
+///     }
+///     Ok(GraphOwned::new(1, 2, xadj, adjncy)?.set_adjwgt(adjwgt))
+/// }
+///
+/// let graph = path(8)?;
+///
+/// let mut part = [0; 8];
+/// let edgecut = graph.part_recursive(&mut part)?;
+/// assert_eq!(edgecut, 1);
+/// assert_eq!(part, [part[0], part[0], part[0], part[0], part[7], part[7], part[7], part[7]]);
+///
+/// # #[cfg(feature = "serde")]
+/// # {
+/// // With the "serde" feature, the graph is checked on deserialization, and
+/// // missing optional arrays are left unset.
+/// let json = serde_json::to_string(&graph).unwrap();
+/// assert_eq!(serde_json::from_str::<GraphOwned>(&json).unwrap(), graph);
+/// let bytes = bincode::serialize(&graph).unwrap();
+/// assert_eq!(bincode::deserialize::<GraphOwned>(&bytes).unwrap(), graph);
+///
+/// let json = r#"{"ncon":1,"nparts":2,"xadj":[0,1,2],"adjncy":[1,0]}"#;
+/// assert!(serde_json::from_str::<GraphOwned>(json).is_ok());
+/// let json = r#"{"ncon":1,"nparts":2,"xadj":[0,1,2],"adjncy":[1,2]}"#;
+/// let err = serde_json::from_str::<GraphOwned>(json).unwrap_err();
+/// assert!(err.to_string().contains("adjncy[1] is 2"));
+///
+/// // Data written by a newer version of this crate is rejected.
+/// let json = r#"{"version":2,"ncon":1,"nparts":2,"xadj":[0,1,2],"adjncy":[1,0]}"#;
+/// let err = serde_json::from_str::<GraphOwned>(json).unwrap_err();
+/// assert!(err
+///     .to_string()
+///     .starts_with("unsupported GraphOwned format version 2, expected ≤ 1"));
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(try_from = "RawGraphOwned")
+)]
+pub struct GraphOwned {
+    ncon: Idx,
+    nparts: Idx,
+    xadj: Vec<Idx>,
+    adjncy: Vec<Idx>,
+    vwgt: Option<Vec<Idx>>,
+    vsize: Option<Vec<Idx>>,
+    adjwgt: Option<Vec<Idx>>,
+    tpwgts: Option<Vec<Real>>,
+    ubvec: Option<Vec<Real>>,
+    options: [Idx; NOPTIONS],
+}
+
+impl fmt::Debug for GraphOwned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GraphOwned")
+            .field("ncon", &self.ncon)
+            .field("nparts", &self.nparts)
+            .field("xadj", &self.xadj)
+            .field("adjncy", &self.adjncy)
+            .field("vwgt", &self.vwgt)
+            .field("vsize", &self.vsize)
+            .field("adjwgt", &self.adjwgt)
+            .field("tpwgts", &self.tpwgts)
+            .field("ubvec", &self.ubvec)
+            .field("options", &self.options)
+            .field("metis", &BuildInfo)
+            .finish()
+    }
+}
+
+/// Version of the serialized form of [`GraphOwned`].
+#[cfg(feature = "serde")]
+const GRAPH_OWNED_VERSION: u32 = 1;
+
+/// Serialized form of [`GraphOwned`].
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct GraphOwnedRef<'a> {
+    version: u32,
+    ncon: Idx,
+    nparts: Idx,
+    xadj: &'a [Idx],
+    adjncy: &'a [Idx],
+    vwgt: Option<&'a [Idx]>,
+    vsize: Option<&'a [Idx]>,
+    adjwgt: Option<&'a [Idx]>,
+    tpwgts: Option<&'a [Real]>,
+    ubvec: Option<&'a [Real]>,
+    options: &'a [Idx],
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for GraphOwned {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        GraphOwnedRef {
+            version: GRAPH_OWNED_VERSION,
+            ncon: self.ncon,
+            nparts: self.nparts,
+            xadj: &self.xadj,
+            adjncy: &self.adjncy,
+            vwgt: self.vwgt.as_deref(),
+            vsize: self.vsize.as_deref(),
+            adjwgt: self.adjwgt.as_deref(),
+            tpwgts: self.tpwgts.as_deref(),
+            ubvec: self.ubvec.as_deref(),
+            options: &self.options,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Unchecked form of [`GraphOwned`], validated on deserialization.
+///
+/// Fields that are missing take their default value, so that data written
+/// before they were added keeps loading.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawGraphOwned {
+    #[serde(default = "default_graph_owned_version")]
+    version: u32,
+    ncon: Idx,
+    nparts: Idx,
+    xadj: Vec<Idx>,
+    adjncy: Vec<Idx>,
+    #[serde(default)]
+    vwgt: Option<Vec<Idx>>,
+    #[serde(default)]
+    vsize: Option<Vec<Idx>>,
+    #[serde(default)]
+    adjwgt: Option<Vec<Idx>>,
+    #[serde(default)]
+    tpwgts: Option<Vec<Real>>,
+    #[serde(default)]
+    ubvec: Option<Vec<Real>>,
+    #[serde(default = "default_graph_owned_options")]
+    options: Vec<Idx>,
+}
+
+#[cfg(feature = "serde")]
+fn default_graph_owned_version() -> u32 {
+    GRAPH_OWNED_VERSION
+}
+
+#[cfg(feature = "serde")]
+fn default_graph_owned_options() -> Vec<Idx> {
+    option::initial().to_vec()
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawGraphOwned> for GraphOwned {
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn try_from(raw: RawGraphOwned) -> StdResult<Self, Self::Error> {
+        if raw.version > GRAPH_OWNED_VERSION {
+            return Err(format!(
+                "unsupported GraphOwned format version {}, expected ≤ {}",
+                raw.version, GRAPH_OWNED_VERSION,
+            )
+            .into());
+        }
+        let mut graph = GraphOwned::new(raw.ncon, raw.nparts, raw.xadj, raw.adjncy)?;
+        if let Some(vwgt) = raw.vwgt {
+            graph = graph.try_set_vwgt(vwgt)?;
+        }
+        if let Some(vsize) = raw.vsize {
+            graph = graph.try_set_vsize(vsize)?;
+        }
+        if let Some(adjwgt) = raw.adjwgt {
+            graph = graph.try_set_adjwgt(adjwgt)?;
+        }
+        if let Some(tpwgts) = raw.tpwgts {
+            graph = graph.try_set_tpwgts(tpwgts)?;
+        }
+        if let Some(ubvec) = raw.ubvec {
+            graph = graph.try_set_ubvec(ubvec)?;
+        }
+        let options_len = raw.options.len();
+        graph.options = raw
+            .options
+            .try_into()
+            .map_err(|_| NewGraphError::LengthMismatch {
+                array: "options",
+                expected: NOPTIONS,
+                actual: options_len,
+            })?;
+        option::check(&graph.options)?;
+        Ok(graph)
+    }
+}
+
+impl From<Graph<'_>> for GraphOwned {
+    /// Copies the arrays borrowed by the [`Graph`].
+    ///
+    /// Indices are converted to zero-based numbering if needed, see
+    /// [`Graph::new_fortran`].
+    fn from(graph: Graph<'_>) -> Self {
+        let base = graph.numbering.value();
+        GraphOwned {
+            ncon: graph.ncon,
+            nparts: graph.nparts,
+            xadj: graph.xadj.iter().map(|x| x - base).collect(),
+            adjncy: graph.adjncy.iter().map(|v| v - base).collect(),
+            vwgt: graph.vwgt.map(<[Idx]>::to_vec),
+            vsize: graph.vsize.map(<[Idx]>::to_vec),
+            adjwgt: graph.adjwgt.map(<[Idx]>::to_vec),
+            tpwgts: graph.tpwgts.map(<[Real]>::to_vec),
+            ubvec: graph.ubvec.map(<[Real]>::to_vec),
+            options: graph.options,
+        }
+    }
+}
+
+impl GraphOwned {
+    /// Creates a new [`GraphOwned`] object to be partitioned.
+    ///
+    /// See [`Graph::new`] for details on the arguments.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [`Graph::new`].
+    pub fn new(
+        ncon: Idx,
+        nparts: Idx,
+        xadj: Vec<Idx>,
+        adjncy: Vec<Idx>,
+    ) -> StdResult<GraphOwned, NewGraphError> {
+        Graph::new(ncon, nparts, &xadj, &adjncy)?;
+        Ok(GraphOwned {
+            ncon,
+            nparts,
+            xadj,
+            adjncy,
+            vwgt: None,
+            vsize: None,
+            adjwgt: None,
+            tpwgts: None,
+            ubvec: None,
+            options: option::initial(),
+        })
+    }
+
+    /// The adjacency index array.
+    pub fn xadj(&self) -> &[Idx] {
+        &self.xadj
+    }
+
+    /// The adjacency array.
+    pub fn adjncy(&self) -> &[Idx] {
+        &self.adjncy
+    }
+
+    /// The weights of the edges, if set.
+    pub fn adjwgt(&self) -> Option<&[Idx]> {
+        self.adjwgt.as_deref()
+    }
+
+    /// Borrows the arrays as a [`Graph`].
+    pub fn as_graph(&self) -> Graph<'_> {
+        Graph {
+            ncon: self.ncon,
+            nparts: self.nparts,
+            xadj: &self.xadj,
+            adjncy: &self.adjncy,
+            vwgt: self.vwgt.as_deref(),
+            vsize: self.vsize.as_deref(),
+            adjwgt: self.adjwgt.as_deref(),
+            tpwgts: self.tpwgts.as_deref(),
+            ubvec: self.ubvec.as_deref(),
+            options: self.options,
+            numbering: Numbering::C,
+        }
+    }
+
+    /// Sets the computational weights of the vertices.
+    ///
+    /// See [`Graph::set_vwgt`].
+    ///
+    /// This is a thin wrapper around [`GraphOwned::try_set_vwgt`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `vwgt` is not `ncon` times the
+    /// number of vertices.
+    pub fn set_vwgt(self, vwgt: Vec<Idx>) -> GraphOwned {
+        self.try_set_vwgt(vwgt)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`GraphOwned::set_vwgt`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`NewGraphError::LengthMismatch`] if the length of
+    /// `vwgt` is not `ncon` times the number of vertices.
+    pub fn try_set_vwgt(mut self, vwgt: Vec<Idx>) -> StdResult<GraphOwned, NewGraphError> {
+        NewGraphError::check_len(
+            "vwgt",
+            array_len(self.ncon, self.xadj.len() - 1)?,
+            vwgt.len(),
+        )?;
+        self.vwgt = Some(vwgt);
+        Ok(self)
+    }
+
+    /// Sets the communication weights of the vertices.
+    ///
+    /// See [`Graph::set_vsize`].
+    ///
+    /// This is a thin wrapper around [`GraphOwned::try_set_vsize`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `vsize` is not the number of
+    /// vertices.
+    pub fn set_vsize(self, vsize: Vec<Idx>) -> GraphOwned {
+        self.try_set_vsize(vsize)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`GraphOwned::set_vsize`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`NewGraphError::LengthMismatch`] if the length of
+    /// `vsize` is not the number of vertices.
+    pub fn try_set_vsize(mut self, vsize: Vec<Idx>) -> StdResult<GraphOwned, NewGraphError> {
+        NewGraphError::check_len("vsize", self.xadj.len() - 1, vsize.len())?;
+        self.vsize = Some(vsize);
+        Ok(self)
+    }
+
+    /// Sets the weights of the edges.
     ///
-    /// Returns the edge-cut, the total communication volume of the
-    /// partitioning solution.
+    /// See [`Graph::set_adjwgt`].
     ///
-    /// Equivalent of `METIS_PartGraphKway`.
+    /// This is a thin wrapper around [`GraphOwned::try_set_adjwgt`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `adjwgt` is not equal to the
+    /// length of `adjncy`, or if some weights are not strictly positive.
+    pub fn set_adjwgt(self, adjwgt: Vec<Idx>) -> GraphOwned {
+        self.try_set_adjwgt(adjwgt)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`GraphOwned::set_adjwgt`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Graph::try_set_adjwgt`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use metis::{GraphOwned, NewGraphError};
+    /// let graph = GraphOwned::new(1, 2, vec![0, 1, 2], vec![1, 0]).unwrap();
+    ///
+    /// assert!(matches!(
+    ///     graph.clone().try_set_adjwgt(vec![1]),
+    ///     Err(NewGraphError::LengthMismatch { array: "adjwgt", expected: 2, actual: 1 }),
+    /// ));
+    /// assert!(graph.try_set_adjwgt(vec![1, 1]).is_ok());
+    /// ```
+    pub fn try_set_adjwgt(mut self, adjwgt: Vec<Idx>) -> StdResult<GraphOwned, NewGraphError> {
+        NewGraphError::check_len("adjwgt", self.adjncy.len(), adjwgt.len())?;
+        NewGraphError::check_positive("adjwgt", &adjwgt)?;
+        self.adjwgt = Some(adjwgt);
+        Ok(self)
+    }
+
+    /// Sets the target partition weights for each part and constraint.
+    ///
+    /// See [`Graph::set_tpwgts`].
+    ///
+    /// This is a thin wrapper around [`GraphOwned::try_set_tpwgts`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `tpwgts` is not equal to `ncon`
+    /// times `nparts`, or if the weights are not valid (see
+    /// [`Graph::try_set_tpwgts`]).
+    pub fn set_tpwgts(self, tpwgts: Vec<Real>) -> GraphOwned {
+        self.try_set_tpwgts(tpwgts)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`GraphOwned::set_tpwgts`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Graph::try_set_tpwgts`].
+    pub fn try_set_tpwgts(mut self, tpwgts: Vec<Real>) -> StdResult<GraphOwned, NewGraphError> {
+        NewGraphError::check_len(
+            "tpwgts",
+            array_len(self.ncon, self.nparts as usize)?,
+            tpwgts.len(),
+        )?;
+        check_tpwgts(&tpwgts, self.ncon as usize)?;
+        self.tpwgts = Some(tpwgts);
+        Ok(self)
+    }
+
+    /// Sets the load imbalance tolerance for each constraint.
+    ///
+    /// See [`Graph::set_ubvec`].
+    ///
+    /// This is a thin wrapper around [`GraphOwned::try_set_ubvec`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `ubvec` is not equal to `ncon`,
+    /// or if some tolerances are below 1.0, infinite or NaN.
+    pub fn set_ubvec(self, ubvec: Vec<Real>) -> GraphOwned {
+        self.try_set_ubvec(ubvec)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`GraphOwned::set_ubvec`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Graph::try_set_ubvec`].
+    pub fn try_set_ubvec(mut self, ubvec: Vec<Real>) -> StdResult<GraphOwned, NewGraphError> {
+        NewGraphError::check_len("ubvec", self.ncon as usize, ubvec.len())?;
+        NewGraphError::check_ubvec(&ubvec)?;
+        self.ubvec = Some(ubvec);
+        Ok(self)
+    }
+
+    /// Sets the fine-tuning parameters for this partitioning.
+    ///
+    /// See [`Graph::set_options`].
+    pub fn set_options(mut self, options: &[Idx; NOPTIONS]) -> GraphOwned {
+        self.options.copy_from_slice(options);
+        self
+    }
+
+    /// Sets a fine-tuning parameter for this partitioning.
+    ///
+    /// See [`Graph::set_option`].
+    pub fn set_option<O>(mut self, option: O) -> GraphOwned
+    where
+        O: option::Opt,
+    {
+        self.options[O::INDEX] = option.value();
+        self
+    }
+
+    /// Sets the seed for the random number generator of METIS.
+    ///
+    /// See [`Graph::set_seed`].
+    pub fn set_seed(self, seed: Idx) -> GraphOwned {
+        self.set_option(option::Seed(seed))
+    }
+
+    /// Sets the fine-tuning parameters for the next partitionings, in place.
+    ///
+    /// See [`Graph::set_options_mut`].
+    pub fn set_options_mut(&mut self, options: &[Idx; NOPTIONS]) -> &mut GraphOwned {
+        self.options.copy_from_slice(options);
+        self
+    }
+
+    /// Sets a fine-tuning parameter for the next partitionings, in place.
+    ///
+    /// See [`Graph::set_option_mut`].
+    pub fn set_option_mut<O>(&mut self, option: O) -> &mut GraphOwned
+    where
+        O: option::Opt,
+    {
+        self.options[O::INDEX] = option.value();
+        self
+    }
+
+    /// Partition the graph using multilevel recursive bisection.
+    ///
+    /// See [`Graph::part_recursive`].
     ///
     /// # Panics
     ///
     /// This function panics if the length of `part` is not the number of
     /// vertices.
-    pub fn part_kway(self, part: &mut [Idx]) -> Result<Idx> {
-        let part_len = Idx::try_from(part.len()).expect("part array larger than Idx::MAX");
-        assert_eq!(
-            part_len,
-            self.xadj.len() as Idx - 1,
-            "part.len() must be equal to the number of vertices",
-        );
+    pub fn part_recursive(&self, part: &mut [Idx]) -> Result<Idx> {
+        self.as_graph().part_recursive(part)
+    }
 
-        if self.nparts == 1 {
-            // METIS does not handle this case well.
-            part.fill(0);
-            return Ok(0);
-        }
+    /// Partition the graph using multilevel k-way partitioning.
+    ///
+    /// See [`Graph::part_kway`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `part` is not the number of
+    /// vertices.
+    pub fn part_kway(&self, part: &mut [Idx]) -> Result<Idx> {
+        self.as_graph().part_kway(part)
+    }
 
-        let nvtxs = self.xadj.len() as Idx - 1;
-        let mut edgecut = mem::MaybeUninit::uninit();
-        let part = part.as_mut_ptr();
-        unsafe {
-            m::METIS_PartGraphKway(
-                &nvtxs as *const Idx as *mut Idx,
-                &self.ncon as *const Idx as *mut Idx,
-                slice_to_mut_ptr(self.xadj),
-                slice_to_mut_ptr(self.adjncy),
-                self.vwgt
-                    .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
-                self.vsize
-                    .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
-                self.adjwgt
-                    .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
-                &self.nparts as *const Idx as *mut Idx,
-                self.tpwgts
-                    .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
-                self.ubvec
-                    .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
-                slice_to_mut_ptr(&self.options),
-                edgecut.as_mut_ptr(),
-                part,
-            )
-            .wrap()?;
-            Ok(edgecut.assume_init())
-        }
+    /// Partition the graph using multilevel recursive bisection, on a
+    /// dedicated thread with a stack of `stack_size` bytes.
+    ///
+    /// See [`Graph::part_recursive_spawned`].
+    pub fn part_recursive_spawned(&self, part: &mut [Idx], stack_size: usize) -> Result<Idx> {
+        self.as_graph().part_recursive_spawned(part, stack_size)
+    }
+
+    /// Partition the graph using multilevel recursive bisection, and return
+    /// the partition along with the edge-cut.
+    ///
+    /// See [`Graph::part_recursive_owned`].
+    pub fn part_recursive_owned(&self) -> Result<(PartitionVec, Idx)> {
+        self.as_graph().part_recursive_owned()
+    }
+
+    /// Partition the graph using multilevel k-way partitioning, and return
+    /// the partition along with the edge-cut.
+    ///
+    /// See [`Graph::part_kway_owned`].
+    pub fn part_kway_owned(&self) -> Result<(PartitionVec, Idx)> {
+        self.as_graph().part_kway_owned()
     }
 }
 
@@ -695,12 +3374,46 @@ impl<'a> Graph<'a> {
 /// Mesh data must follow the format described in [`Mesh::new`].
 #[derive(Debug)]
 pub struct InvalidMeshError {
+    kind: StructureErrorKind,
     msg: &'static str,
+    context: Option<Offending>,
 }
 
 impl fmt::Display for InvalidMeshError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.msg.fmt(f)
+        self.msg.fmt(f)?;
+        match &self.context {
+            Some(context) => write!(f, ": {context}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl InvalidMeshError {
+    /// What is wrong with the structure, see [`StructureErrorKind`].
+    pub fn kind(&self) -> StructureErrorKind {
+        self.kind
+    }
+
+    /// The name of the array holding the offending value, if known.
+    pub fn array(&self) -> Option<&'static str> {
+        self.context.as_ref().map(|c| c.array)
+    }
+
+    /// The position of the first offending value in
+    /// [`InvalidMeshError::array`], if known.
+    pub fn position(&self) -> Option<usize> {
+        self.context.as_ref().map(|c| c.position)
+    }
+
+    /// The first offending value, if known.
+    pub fn value(&self) -> Option<Idx> {
+        self.context.as_ref().map(|c| c.value)
+    }
+
+    /// The range the offending value should have been in, if known.
+    pub fn range(&self) -> Option<RangeInclusive<Idx>> {
+        self.context.as_ref().map(|c| c.range.clone())
     }
 }
 
@@ -722,6 +3435,86 @@ pub enum NewMeshError {
     /// be raised during [`Mesh::part_dual`] and [`Mesh::part_nodal`] as
     /// [`Error::Input`].
     InvalidMesh(InvalidMeshError),
+
+    /// An index array, such as `eptr`, holds a negative value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use metis::{Idx, Mesh, NewMeshError};
+    ///
+    /// let eind = &[0, 1, 2];
+    /// let err = Mesh::new(2, &[Idx::MIN, -1, 3], eind).unwrap_err();
+    /// assert!(matches!(
+    ///     err,
+    ///     NewMeshError::NegativeValue { array: "eptr", index: 0, value: Idx::MIN },
+    /// ));
+    ///
+    /// // Node indices that overflow the node count are rejected too.
+    /// assert!(Mesh::new(2, &[0, 1], &[Idx::MAX]).is_err());
+    /// ```
+    NegativeValue {
+        /// The name of the array, as in METIS' manual (e.g. `"eptr"`).
+        array: &'static str,
+
+        /// The position of the first negative value.
+        index: usize,
+
+        /// The first negative value.
+        value: Idx,
+    },
+
+    /// A real-valued array, such as `tpwgts`, holds a value that METIS
+    /// cannot handle, such as a target weight that is zero or negative.
+    ///
+    /// Raised by the `try_set_*` methods of [`Mesh`].
+    InvalidValue {
+        /// The name of the array, as in METIS' manual (e.g. `"tpwgts"`).
+        array: &'static str,
+
+        /// The position of the first offending value.
+        index: usize,
+
+        /// The first offending value.
+        value: Real,
+    },
+
+    /// The target partition weights of a constraint do not sum to 1.0.
+    ///
+    /// Raised by the `try_set_tpwgts` methods of [`Mesh`].
+    TargetWeightSum {
+        /// The index of the constraint.
+        constraint: usize,
+
+        /// The actual sum of its target partition weights.
+        sum: Real,
+    },
+
+    /// Weights were set for the wrong kind of partitioning: node weights are
+    /// only used by [`Mesh::part_nodal`], element weights and sizes only by
+    /// [`Mesh::part_dual`].
+    ///
+    /// Raised as the source of [`Error::Input`] by these functions.
+    WrongWeights {
+        /// The setter of the weights that cannot be used, e.g.
+        /// `"set_node_vwgt"`.
+        setter: &'static str,
+    },
+
+    /// An optional array, such as element weights, does not have the expected
+    /// length.
+    ///
+    /// Raised by the `try_set_*` methods of [`Mesh`].
+    LengthMismatch {
+        /// The name of the array, as in METIS' manual (e.g. `"vwgt"`).
+        array: &'static str,
+
+        /// The expected length of the array.
+        expected: usize,
+
+        /// The actual length of the array.
+        actual: usize,
+    },
 }
 
 impl fmt::Display for NewMeshError {
@@ -730,45 +3523,188 @@ impl fmt::Display for NewMeshError {
             Self::NoParts => write!(f, "there must be at least one part"),
             Self::TooLarge => write!(f, "mesh is too large"),
             Self::InvalidMesh(err) => write!(f, "invalid mesh structure: {err}"),
+            Self::NegativeValue {
+                array,
+                index,
+                value,
+            } => write!(f, "{array}[{index}] is negative ({value})"),
+            Self::InvalidValue {
+                array,
+                index,
+                value,
+            } => write!(f, "{array}[{index}] is out of range ({value})"),
+            Self::TargetWeightSum { constraint, sum } => write!(
+                f,
+                "target partition weights of constraint {constraint} sum to {sum}, expected 1.0",
+            ),
+            Self::WrongWeights { setter } => {
+                write!(
+                    f,
+                    "weights set with {setter} cannot be used by this partitioning"
+                )
+            }
+            Self::LengthMismatch {
+                array,
+                expected,
+                actual,
+            } => write!(f, "{array} has length {actual}, expected {expected}"),
         }
     }
 }
 
 impl std::error::Error for NewMeshError {}
 
+impl From<TpwgtsFault> for NewMeshError {
+    fn from(fault: TpwgtsFault) -> Self {
+        match fault {
+            TpwgtsFault::Value { index, value } => Self::InvalidValue {
+                array: "tpwgts",
+                index,
+                value,
+            },
+            TpwgtsFault::Sum { constraint, sum } => Self::TargetWeightSum { constraint, sum },
+        }
+    }
+}
+
 impl NewMeshError {
-    fn msg(msg: &'static str) -> Self {
-        Self::InvalidMesh(InvalidMeshError { msg })
+    fn msg(kind: StructureErrorKind, msg: &'static str) -> Self {
+        Self::InvalidMesh(InvalidMeshError {
+            kind,
+            msg,
+            context: None,
+        })
+    }
+
+    fn at(
+        kind: StructureErrorKind,
+        msg: &'static str,
+        array: &'static str,
+        position: usize,
+        value: Idx,
+        range: RangeInclusive<Idx>,
+    ) -> Self {
+        let context = Offending {
+            array,
+            position,
+            value,
+            range,
+        };
+        Self::InvalidMesh(InvalidMeshError {
+            kind,
+            msg,
+            context: Some(context),
+        })
+    }
+
+    /// The position of the first offending value in its array, if the error
+    /// is about a specific value.
+    ///
+    /// See [`NewGraphError::position`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use metis::Mesh;
+    ///
+    /// let err = Mesh::new(2, &[0, 4, 2], &[0, 1]).unwrap_err();
+    /// assert_eq!(err.position(), Some(2));
+    /// let err = Mesh::new(2, &[0, 2], &[0, -1]).unwrap_err();
+    /// assert_eq!(err.position(), Some(1));
+    /// ```
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            Self::InvalidMesh(err) => err.position(),
+            Self::NegativeValue { index, .. } | Self::InvalidValue { index, .. } => Some(*index),
+            _ => None,
+        }
+    }
+
+    fn check_len(array: &'static str, expected: usize, actual: usize) -> StdResult<(), Self> {
+        if expected != actual {
+            return Err(Self::LengthMismatch {
+                array,
+                expected,
+                actual,
+            });
+        }
+        Ok(())
     }
 }
 
 /// Returns the number of elements and the number of nodes in the mesh.
 fn check_mesh_structure(eptr: &[Idx], eind: &[Idx]) -> StdResult<(Idx, Idx), NewMeshError> {
-    let last_eptr = *eptr
-        .last()
-        .ok_or(NewMeshError::msg("element index is empty"))?;
+    check_numbered_mesh_structure(eptr, eind, Numbering::C)
+}
+
+/// Same as [`check_mesh_structure`], for a mesh whose indices start at the
+/// given numbering.
+fn check_numbered_mesh_structure(
+    eptr: &[Idx],
+    eind: &[Idx],
+    numbering: Numbering,
+) -> StdResult<(Idx, Idx), NewMeshError> {
+    let base = numbering.value();
+    if let Some((index, value)) = find_negative(eptr) {
+        return Err(NewMeshError::NegativeValue {
+            array: "eptr",
+            index,
+            value,
+        });
+    }
+    let last_eptr = *eptr.last().ok_or(NewMeshError::msg(
+        StructureErrorKind::EmptyIndex,
+        "element index is empty",
+    ))?;
+    if eptr[0] != base {
+        return Err(NewMeshError::at(
+            StructureErrorKind::WrongStart,
+            "element index does not start at the first index",
+            "eptr",
+            0,
+            eptr[0],
+            base..=base,
+        ));
+    }
     let eind_len = Idx::try_from(eind.len()).map_err(|_| NewMeshError::TooLarge)?;
-    if last_eptr != eind_len {
-        return Err(NewMeshError::msg(
+    if last_eptr - base != eind_len {
+        return Err(NewMeshError::at(
+            StructureErrorKind::LengthMismatch,
             "length mismatch between element and node indices",
+            "eptr",
+            eptr.len() - 1,
+            last_eptr,
+            eind_len + base..=eind_len + base,
         ));
     }
 
     let ne = Idx::try_from(eptr.len()).map_err(|_| NewMeshError::TooLarge)? - 1;
 
-    let mut prev = 0;
-    for x in eptr {
+    let mut prev = base;
+    for (i, x) in eptr.iter().enumerate() {
         if prev > *x {
-            return Err(NewMeshError::msg("element index is not sorted"));
+            return Err(NewMeshError::at(
+                StructureErrorKind::NotSorted,
+                "element index is not sorted",
+                "eptr",
+                i,
+                *x,
+                prev..=last_eptr,
+            ));
         }
         prev = *x;
     }
 
-    let mut max_node = 0;
-    for a in eind {
-        if *a < 0 {
-            return Err(NewMeshError::msg(
+    let mut max_node = base;
+    for (i, a) in eind.iter().enumerate() {
+        if *a < base {
+            return Err(NewMeshError::at(
+                StructureErrorKind::OutOfBounds,
                 "values in the node index are out of bounds",
+                "eind",
+                i,
+                *a,
+                base..=Idx::MAX,
             ));
         }
         if *a > max_node {
@@ -776,7 +3712,14 @@ fn check_mesh_structure(eptr: &[Idx], eind: &[Idx]) -> StdResult<(Idx, Idx), New
         }
     }
 
-    Ok((ne, max_node + 1))
+    if eind.is_empty() {
+        // No node at all, rather than a single node 0.
+        return Ok((ne, 0));
+    }
+    let nn = max_node
+        .checked_add(1 - base)
+        .ok_or(NewMeshError::TooLarge)?;
+    Ok((ne, nn))
 }
 
 /// Builder structure to set up a mesh partition computation.
@@ -788,7 +3731,7 @@ fn check_mesh_structure(eptr: &[Idx], eind: &[Idx]) -> StdResult<(Idx, Idx), New
 ///
 /// Usage is fairly similar to [`Graph`].  Refer to its documentation for
 /// details.
-#[derive(Debug, PartialEq)]
+#[derive(PartialEq)]
 pub struct Mesh<'a> {
     /// The number of nodes in the mesh.
     nn: Idx,
@@ -808,6 +3751,11 @@ pub struct Mesh<'a> {
     /// Required size: ne
     vwgt: Option<&'a [Idx]>,
 
+    /// The computational weights of the nodes.
+    ///
+    /// Required size: nn
+    node_vwgt: Option<&'a [Idx]>,
+
     /// The communication weights of the elements.
     ///
     /// Required size: ne
@@ -822,6 +3770,28 @@ pub struct Mesh<'a> {
 
     /// Fine-tuning parameters.
     options: [Idx; NOPTIONS],
+
+    /// Whether indices in `eptr` and `eind` start at zero or one.
+    numbering: Numbering,
+}
+
+impl fmt::Debug for Mesh<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mesh")
+            .field("nn", &self.nn)
+            .field("nparts", &self.nparts)
+            .field("ncommon", &self.ncommon)
+            .field("eptr", &self.eptr)
+            .field("eind", &self.eind)
+            .field("vwgt", &self.vwgt)
+            .field("node_vwgt", &self.node_vwgt)
+            .field("vsize", &self.vsize)
+            .field("tpwgts", &self.tpwgts)
+            .field("options", &self.options)
+            .field("numbering", &self.numbering)
+            .field("metis", &BuildInfo)
+            .finish()
+    }
 }
 
 impl<'a> Mesh<'a> {
@@ -846,8 +3816,10 @@ impl<'a> Mesh<'a> {
     /// - `nparts` is strictly greater than zero,
     /// - `eptr` has at least one element (its length is the one more than the
     ///   number of mesh elements),
+    /// - the first element of `eptr` is zero,
     /// - `eptr` is sorted,
-    /// - elements of `eptr` are positive,
+    /// - elements of `eptr` are positive (reported as
+    ///   [`NewMeshError::NegativeValue`] with the first offending position),
     /// - the last element of `eptr` is the length of `eind`,
     /// - all the arrays have a length that can be held by an [`Idx`].
     ///
@@ -859,8 +3831,138 @@ impl<'a> Mesh<'a> {
         if nparts <= 0 {
             return Err(NewMeshError::NoParts);
         }
-        let (_ne, nn) = check_mesh_structure(eptr, eind)?;
-        Ok(unsafe { Mesh::new_unchecked(nn, nparts, eptr, eind) })
+        let (_ne, nn) = check_mesh_structure(eptr, eind)?;
+        Ok(unsafe { Mesh::new_unchecked(nn, nparts, eptr, eind) })
+    }
+
+    /// Creates a new [`Mesh`] object to be partitioned, with an explicit number
+    /// of nodes.
+    ///
+    /// [`Mesh::new`] infers the number of nodes from the largest node in
+    /// `eind`.  With this function, the mesh can have nodes that belong to no
+    /// element, e.g. after some elements have been filtered out, so that the
+    /// node partition has an entry for each of them.  See [`Mesh::part_dual`]
+    /// and [`Mesh::part_nodal`] for the part these nodes end up in.
+    ///
+    /// # Errors
+    ///
+    /// On top of the invariants listed in the "Errors" section of
+    /// [`Mesh::new`], nodes in `eind` must be less than `nn`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::{Mesh, StructureErrorKind};
+    ///
+    /// // Two quads, nodes 3, 7 and 8 belong to no element.
+    /// // 0 - 1 - 2   3
+    /// // |   |   |
+    /// // 4 - 5 - 6   7   8
+    /// let eptr = &[0, 4, 8];
+    /// let eind = &[0, 1, 5, 4, 1, 2, 6, 5];
+    ///
+    /// let mesh = Mesh::new_with_nn(9, 2, eptr, eind)?;
+    /// assert_eq!(mesh.nn(), 9);
+    /// let (mut epart, mut npart) = ([0; 2], [0; 9]);
+    /// mesh.part_dual(&mut epart, &mut npart)?;
+    /// assert_eq!((npart[3], npart[7], npart[8]), (0, 0, 0));
+    ///
+    /// let mut npart = [-1; 9];
+    /// Mesh::new_with_nn(9, 2, eptr, eind)?.part_nodal(&mut epart, &mut npart)?;
+    /// assert!(npart.iter().all(|p| *p == 0 || *p == 1));
+    ///
+    /// let err = Mesh::new_with_nn(6, 2, eptr, eind).unwrap_err();
+    /// assert_eq!(err.position(), Some(6));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_nn(
+        nn: Idx,
+        nparts: Idx,
+        eptr: &'a [Idx],
+        eind: &'a [Idx],
+    ) -> StdResult<Mesh<'a>, NewMeshError> {
+        if nparts <= 0 {
+            return Err(NewMeshError::NoParts);
+        }
+        if nn < 0 {
+            return Err(NewMeshError::msg(
+                StructureErrorKind::OutOfBounds,
+                "the number of nodes is negative",
+            ));
+        }
+        check_mesh_structure(eptr, eind)?;
+        if let Some(i) = eind.iter().position(|n| nn <= *n) {
+            return Err(NewMeshError::at(
+                StructureErrorKind::OutOfBounds,
+                "values in the node index are out of bounds",
+                "eind",
+                i,
+                eind[i],
+                0..=nn - 1,
+            ));
+        }
+        Ok(unsafe { Mesh::new_unchecked(nn, nparts, eptr, eind) })
+    }
+
+    /// Creates a new [`Mesh`] object to be partitioned, from arrays that use
+    /// Fortran-style numbering: indices start at one instead of zero.
+    ///
+    /// The first element of `eptr` is one, and the last is the length of
+    /// `eind` plus one.  The partition arrays are then also filled with parts
+    /// numbered from one.  Like with [`Graph::new_fortran`], the arrays are
+    /// copied to zero-based arrays before each partitioning.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the arrays do not uphold the
+    /// invariants listed in the "Errors" section of [`Mesh::new`], adjusted
+    /// to one-based numbering.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// // 1 - 2 - 3
+    /// // |   |   |
+    /// // 4 - 5 - 6
+    /// let eptr = &[1, 5, 9];
+    /// let eind = &[1, 2, 5, 4, 2, 3, 6, 5];
+    /// let mesh = metis::Mesh::new_fortran(2, eptr, eind)?;
+    /// assert_eq!(mesh.nn(), 6);
+    /// assert_eq!(mesh.element(2), &[2, 3, 6, 5]);
+    ///
+    /// let mut epart = [0; 2];
+    /// let mut npart = [0; 6];
+    /// mesh.part_dual(&mut epart, &mut npart)?;
+    /// assert!(epart.iter().chain(&npart).all(|p| *p == 1 || *p == 2));
+    /// assert_ne!(epart[0], epart[1]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_fortran(
+        nparts: Idx,
+        eptr: &'a [Idx],
+        eind: &'a [Idx],
+    ) -> StdResult<Mesh<'a>, NewMeshError> {
+        if nparts <= 0 {
+            return Err(NewMeshError::NoParts);
+        }
+        let (_ne, nn) = check_numbered_mesh_structure(eptr, eind, Numbering::Fortran)?;
+        Ok(Mesh {
+            nn,
+            nparts,
+            ncommon: 1,
+            eptr,
+            eind,
+            vwgt: None,
+            node_vwgt: None,
+            vsize: None,
+            tpwgts: None,
+            options: option::initial(),
+            numbering: Numbering::Fortran,
+        })
     }
 
     /// Creates a new [`Mesh`] object to be partitioned (unchecked version).
@@ -883,7 +3985,7 @@ impl<'a> Mesh<'a> {
     ///
     /// This function panics if:
     /// - any of the arrays have a length that cannot be hold by an [`Idx`], or
-    /// - `nn` is not strictly greater than zero, or
+    /// - `nn` is negative, or
     /// - `nparts` is not strictly greater than zero, or
     /// - `eptr` is empty, or
     /// - the length of `eind` is different from the last element of `eptr`.
@@ -899,7 +4001,7 @@ impl<'a> Mesh<'a> {
         eptr: &'a [Idx],
         eind: &'a [Idx],
     ) -> Mesh<'a> {
-        assert!(0 < nn, "nn must be strictly greater than zero");
+        assert!(0 <= nn, "nn must be positive");
         assert!(0 < nparts, "nparts must be strictly greater than zero");
         let _ = Idx::try_from(eptr.len()).expect("eptr array larger than Idx::MAX");
         assert_ne!(eptr.len(), 0);
@@ -913,42 +4015,269 @@ impl<'a> Mesh<'a> {
             eptr,
             eind,
             vwgt: None,
+            node_vwgt: None,
             vsize: None,
             tpwgts: None,
-            options: [-1; NOPTIONS],
+            options: option::initial(),
+            numbering: Numbering::C,
         }
     }
 
-    /// Sets the computational weights of the elements.
+    /// The number of elements in the mesh.
+    pub fn ne(&self) -> Idx {
+        self.eptr.len() as Idx - 1
+    }
+
+    /// The number of nodes in the mesh.
+    pub fn nn(&self) -> Idx {
+        self.nn
+    }
+
+    /// The nodes of element `i`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `i` is not within zero and the number of
+    /// elements.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// // 0 - 1 - 2
+    /// // |   |   |
+    /// // 3 - 4 - 5
+    /// let eptr = &[0, 4, 8];
+    /// let eind = &[0, 1, 4, 3, 1, 2, 5, 4];
+    /// let mesh = metis::Mesh::new(2, eptr, eind)?;
+    ///
+    /// assert_eq!(mesh.ne(), 2);
+    /// assert_eq!(mesh.nn(), 6);
+    /// assert_eq!(mesh.element(1), &[1, 2, 5, 4]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn element(&self, i: Idx) -> &'a [Idx] {
+        let base = self.numbering.value();
+        let i = i - base;
+        assert!(
+            0 <= i && i < self.ne(),
+            "element {} out of bounds",
+            i + base
+        );
+        let i = i as usize;
+        &self.eind[(self.eptr[i] - base) as usize..(self.eptr[i + 1] - base) as usize]
+    }
+
+    /// Consumes the mesh and returns the arrays and parameters it was built
+    /// from.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::Mesh;
+    ///
+    /// // Two quads.
+    /// let eptr = vec![0, 4, 8];
+    /// let mut eind = vec![0, 1, 4, 3, 1, 2, 5, 4];
+    ///
+    /// let parts = Mesh::new(2, &eptr, &eind)?.into_inner();
+    /// assert_eq!(parts.nn, 6);
+    /// assert_eq!(parts.eind, &eind[..]);
+    /// let nparts = parts.nparts;
+    ///
+    /// // Flip the orientation of the second quad and rebuild the mesh.
+    /// eind[4..].reverse();
+    /// let mut epart = [0; 2];
+    /// let mut npart = [0; 6];
+    /// Mesh::new(nparts, &eptr, &eind)?.part_dual(&mut epart, &mut npart)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_inner(self) -> MeshParts<'a> {
+        MeshParts {
+            nn: self.nn,
+            nparts: self.nparts,
+            ncommon: self.ncommon,
+            eptr: self.eptr,
+            eind: self.eind,
+            vwgt: self.vwgt,
+            node_vwgt: self.node_vwgt,
+            vsize: self.vsize,
+            tpwgts: self.tpwgts,
+            options: self.options,
+        }
+    }
+
+    /// Sets the computational weights of the elements, used by
+    /// [`Mesh::part_dual`].
     ///
     /// By default, all elements have the same weight.
     ///
     /// All elements of `vwgt` must be positive.
     ///
+    /// This is a thin wrapper around [`Mesh::try_set_element_vwgt`].
+    ///
     /// # Panics
     ///
     /// This function panics if the length of `vwgt` is not the number of
     /// elements.
-    pub fn set_vwgt(mut self, vwgt: &'a [Idx]) -> Mesh<'a> {
-        let vwgt_len = Idx::try_from(vwgt.len()).expect("vwgt array too large");
-        assert_eq!(vwgt_len, self.eptr.len() as Idx - 1);
+    pub fn set_element_vwgt(self, vwgt: &'a [Idx]) -> Mesh<'a> {
+        self.try_set_element_vwgt(vwgt)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Sets the computational weights of the elements.
+    ///
+    /// Renamed to [`Mesh::set_element_vwgt`], to tell it apart from
+    /// [`Mesh::set_node_vwgt`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `vwgt` is not the number of
+    /// elements.
+    #[deprecated(since = "0.3.0", note = "use `Mesh::set_element_vwgt` instead")]
+    pub fn set_vwgt(self, vwgt: &'a [Idx]) -> Mesh<'a> {
+        self.set_element_vwgt(vwgt)
+    }
+
+    /// Fallible version of [`Mesh::set_element_vwgt`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`NewMeshError::LengthMismatch`] if the length of
+    /// `vwgt` is not the number of elements.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::{Mesh, NewMeshError};
+    ///
+    /// // Two quads, three parts.
+    /// let eptr = &[0, 4, 8];
+    /// let eind = &[0, 1, 4, 3, 1, 2, 5, 4];
+    /// let m = || Mesh::new(3, eptr, eind).unwrap();
+    ///
+    /// let mismatch = |res: Result<Mesh, NewMeshError>| match res {
+    ///     Err(NewMeshError::LengthMismatch {
+    ///         array,
+    ///         expected,
+    ///         actual,
+    ///     }) => Some((array, expected, actual)),
+    ///     _ => None,
+    /// };
+    /// assert_eq!(mismatch(m().try_set_element_vwgt(&[1; 3])), Some(("vwgt", 2, 3)));
+    /// assert_eq!(mismatch(m().try_set_node_vwgt(&[1; 2])), Some(("vwgt", 6, 2)));
+    /// assert_eq!(mismatch(m().try_set_vsize(&[1; 1])), Some(("vsize", 2, 1)));
+    /// assert_eq!(mismatch(m().try_set_tpwgts(&[0.5; 2])), Some(("tpwgts", 3, 2)));
+    /// assert!(m().try_set_element_vwgt(&[1; 2]).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_set_element_vwgt(mut self, vwgt: &'a [Idx]) -> StdResult<Mesh<'a>, NewMeshError> {
+        NewMeshError::check_len("vwgt", self.eptr.len() - 1, vwgt.len())?;
         self.vwgt = Some(vwgt);
-        self
+        Ok(self)
+    }
+
+    /// Sets the computational weights of the nodes, used by
+    /// [`Mesh::part_nodal`].
+    ///
+    /// By default, all nodes have the same weight.
+    ///
+    /// All elements of `vwgt` must be positive.
+    ///
+    /// This is a thin wrapper around [`Mesh::try_set_node_vwgt`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `vwgt` is not the number of
+    /// nodes.
+    pub fn set_node_vwgt(self, vwgt: &'a [Idx]) -> Mesh<'a> {
+        self.try_set_node_vwgt(vwgt)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Mesh::set_node_vwgt`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`NewMeshError::LengthMismatch`] if the length of
+    /// `vwgt` is not the number of nodes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::Mesh;
+    ///
+    /// // A strip of four quads.
+    /// // 0 - 1 - 2 - 3 - 4
+    /// // |   |   |   |   |
+    /// // 5 - 6 - 7 - 8 - 9
+    /// let eptr = &[0, 4, 8, 12, 16];
+    /// let eind = &[0, 1, 6, 5, 1, 2, 7, 6, 2, 3, 8, 7, 3, 4, 9, 8];
+    ///
+    /// // The first element is as heavy as the three others together.
+    /// let (mut epart, mut npart) = ([0; 4], [0; 10]);
+    /// Mesh::new(2, eptr, eind)?
+    ///     .set_element_vwgt(&[3, 1, 1, 1])
+    ///     .part_dual(&mut epart, &mut npart)?;
+    /// assert_ne!(epart[0], epart[1]);
+    /// assert_eq!(epart[1..], [epart[1]; 3]);
+    ///
+    /// // The first two nodes are as heavy as the eight others together.
+    /// let node_vwgt = &[4, 4, 1, 1, 1, 1, 1, 1, 1, 1];
+    /// Mesh::new(2, eptr, eind)?
+    ///     .set_node_vwgt(node_vwgt)
+    ///     .part_nodal(&mut epart, &mut npart)?;
+    /// let heavy = npart[0];
+    /// let weight: metis::Idx = (0..10).filter(|n| npart[*n] == heavy).map(|n| node_vwgt[n]).sum();
+    /// assert!(weight <= 9);
+    /// assert!(npart.iter().filter(|p| **p == heavy).count() < 5);
+    ///
+    /// // Each kind of weights only makes sense for one kind of partitioning.
+    /// let res = Mesh::new(2, eptr, eind)?
+    ///     .set_node_vwgt(node_vwgt)
+    ///     .part_dual(&mut epart, &mut npart);
+    /// assert!(matches!(res, Err(metis::Error::Input(Some(_)))));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_set_node_vwgt(mut self, vwgt: &'a [Idx]) -> StdResult<Mesh<'a>, NewMeshError> {
+        NewMeshError::check_len("vwgt", self.nn as usize, vwgt.len())?;
+        self.node_vwgt = Some(vwgt);
+        Ok(self)
     }
 
-    /// Sets the communication weights of the elements.
+    /// Sets the communication weights of the elements, used by
+    /// [`Mesh::part_dual`].
     ///
     /// By default, all elements have the same communication weight.
     ///
+    /// This is a thin wrapper around [`Mesh::try_set_vsize`].
+    ///
     /// # Panics
     ///
     /// This function panics if the length of `vsize` is not the number of
     /// elements.
-    pub fn set_vsize(mut self, vsize: &'a [Idx]) -> Mesh<'a> {
-        let vsize_len = Idx::try_from(vsize.len()).expect("vsize array too large");
-        assert_eq!(vsize_len, self.eptr.len() as Idx - 1);
+    pub fn set_vsize(self, vsize: &'a [Idx]) -> Mesh<'a> {
+        self.try_set_vsize(vsize)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Mesh::set_vsize`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`NewMeshError::LengthMismatch`] if the length of
+    /// `vsize` is not the number of elements.
+    pub fn try_set_vsize(mut self, vsize: &'a [Idx]) -> StdResult<Mesh<'a>, NewMeshError> {
+        NewMeshError::check_len("vsize", self.eptr.len() - 1, vsize.len())?;
         self.vsize = Some(vsize);
-        self
+        Ok(self)
     }
 
     /// Sets the target partition weights for each part.
@@ -957,14 +4286,30 @@ impl<'a> Mesh<'a> {
     ///
     /// The sum of the target partition weights must be 1.0.
     ///
+    /// This is a thin wrapper around [`Mesh::try_set_tpwgts`].
+    ///
     /// # Panics
     ///
-    /// This function panics if the length of `tpwgts` is not equal to `nparts`.
-    pub fn set_tpwgts(mut self, tpwgts: &'a [Real]) -> Mesh<'a> {
-        let tpwgts_len = Idx::try_from(tpwgts.len()).expect("tpwgts array too large");
-        assert_eq!(tpwgts_len, self.nparts);
+    /// This function panics if the length of `tpwgts` is not equal to `nparts`,
+    /// or if the weights are not valid (see [`Mesh::try_set_tpwgts`]).
+    pub fn set_tpwgts(self, tpwgts: &'a [Real]) -> Mesh<'a> {
+        self.try_set_tpwgts(tpwgts)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`Mesh::set_tpwgts`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`NewMeshError::LengthMismatch`] if the length of
+    /// `tpwgts` is not equal to `nparts`, [`NewMeshError::InvalidValue`] if a
+    /// weight is not strictly positive, and [`NewMeshError::TargetWeightSum`]
+    /// if the weights do not sum to 1.0.
+    pub fn try_set_tpwgts(mut self, tpwgts: &'a [Real]) -> StdResult<Mesh<'a>, NewMeshError> {
+        NewMeshError::check_len("tpwgts", self.nparts as usize, tpwgts.len())?;
+        check_tpwgts(tpwgts, 1)?;
         self.tpwgts = Some(tpwgts);
-        self
+        Ok(self)
     }
 
     /// Sets the fine-tuning parameters for this partitioning.
@@ -1004,6 +4349,13 @@ impl<'a> Mesh<'a> {
         self
     }
 
+    /// Sets the seed for the random number generator of METIS.
+    ///
+    /// See [`Graph::set_seed`].
+    pub fn set_seed(self, seed: Idx) -> Mesh<'a> {
+        self.set_option(option::Seed(seed))
+    }
+
     /// Partition the mesh using its dual graph.
     ///
     /// Returns the edge-cut, the total communication volume of the
@@ -1011,23 +4363,102 @@ impl<'a> Mesh<'a> {
     ///
     /// Equivalent of `METIS_PartMeshDual`.
     ///
+    /// Nodes that belong to no element (see [`Mesh::new_with_nn`]) are put in
+    /// the first part.
+    ///
+    /// When `nparts` is greater than the number of elements, METIS is not
+    /// called.  Instead, the `i`th element is put in the `i`th part, each node
+    /// is put in the part of the first element it belongs to, and the returned
+    /// edge-cut is the number of edges of the dual graph.
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// // Two quads.
+    /// let eptr = &[0, 4, 8];
+    /// let eind = &[0, 1, 4, 3, 1, 2, 5, 4];
+    /// let (mut epart, mut npart) = ([0; 2], [0; 6]);
+    ///
+    /// let edgecut = metis::Mesh::new(3, eptr, eind)?.part_dual(&mut epart, &mut npart)?;
+    /// assert_eq!(edgecut, 1);
+    /// assert_eq!(epart, [0, 1]);
+    /// assert_eq!(npart, [0, 0, 1, 0, 0, 1]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
     /// # Panics
     ///
     /// This function panics if the length of `epart` is not the number of
     /// elements, or if `nparts`'s is not the number of nodes.
-    pub fn part_dual(mut self, epart: &mut [Idx], npart: &mut [Idx]) -> Result<Idx> {
-        self.options[option::Numbering::INDEX] = option::Numbering::C.value();
-        let epart_len = Idx::try_from(epart.len()).expect("epart array larger than Idx::MAX");
+    pub fn part_dual(self, epart: &mut [Idx], npart: &mut [Idx]) -> Result<Idx> {
         assert_eq!(
-            epart_len,
-            self.eptr.len() as Idx - 1,
+            epart.len(),
+            self.eptr.len() - 1,
             "epart.len() must be equal to the number of elements",
         );
-        let npart_len = Idx::try_from(npart.len()).expect("npart array larger than Idx::MAX");
         assert_eq!(
-            npart_len, self.nn,
+            npart.len(),
+            self.nn as usize,
             "npart.len() must be equal to the number of nodes",
         );
+        self.try_part_dual(epart, npart)
+    }
+
+    /// Fallible version of [`Mesh::part_dual`].
+    ///
+    /// # Errors
+    ///
+    /// On top of the errors reported by METIS, this function returns
+    /// [`Error::Input`] instead of panicking if the length of `epart` is not
+    /// the number of elements, or if the length of `npart` is not the number
+    /// of nodes.  It also returns [`Error::Input`] if node weights were set
+    /// (see [`NewMeshError::WrongWeights`]), or if an option holds an illegal
+    /// value (see [`option::InvalidOptionError`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::{Error, Mesh};
+    ///
+    /// // Two quads.
+    /// let eptr = &[0, 4, 8];
+    /// let eind = &[0, 1, 4, 3, 1, 2, 5, 4];
+    /// let mesh = || Mesh::new(2, eptr, eind).unwrap();
+    ///
+    /// let (mut epart, mut npart) = ([0; 2], [0; 6]);
+    /// assert_eq!(mesh().try_part_dual(&mut [0; 3], &mut npart), Err(Error::Input(None)));
+    /// assert_eq!(mesh().try_part_dual(&mut epart, &mut [0; 5]), Err(Error::Input(None)));
+    /// assert_eq!(mesh().try_part_nodal(&mut [], &mut npart), Err(Error::Input(None)));
+    /// mesh().try_part_dual(&mut epart, &mut npart)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_part_dual(mut self, epart: &mut [Idx], npart: &mut [Idx]) -> Result<Idx> {
+        self.options[Numbering::INDEX] = Numbering::C.value();
+        if epart.len() != self.eptr.len() - 1 || npart.len() != self.nn as usize {
+            return Err(Error::Input(None));
+        }
+        option::check(&self.options)?;
+        if self.node_vwgt.is_some() {
+            let err = NewMeshError::WrongWeights {
+                setter: "set_node_vwgt",
+            };
+            return Err(err.into());
+        }
+        if self.numbering == Numbering::Fortran {
+            let eptr = zero_based(self.eptr);
+            let eind = zero_based(self.eind);
+            let mesh = Mesh {
+                eptr: &eptr,
+                eind: &eind,
+                numbering: Numbering::C,
+                ..self
+            };
+            let objval = mesh.try_part_dual(epart, npart)?;
+            epart.iter_mut().chain(npart).for_each(|p| *p += 1);
+            return Ok(objval);
+        }
 
         if self.nparts == 1 {
             // METIS does not handle this case well.
@@ -1037,7 +4468,30 @@ impl<'a> Mesh<'a> {
         }
 
         let ne = self.eptr.len() as Idx - 1;
+        if ne == 0 {
+            // METIS cannot compute the dual of an empty mesh.
+            npart.fill(0);
+            return Ok(0);
+        }
+        if self.nparts > ne {
+            for (e, p) in epart.iter_mut().enumerate() {
+                *p = e as Idx;
+            }
+            npart.fill(-1);
+            for (e, nodes) in self.eptr.windows(2).enumerate() {
+                for n in &self.eind[nodes[0] as usize..nodes[1] as usize] {
+                    if npart[*n as usize] == -1 {
+                        npart[*n as usize] = e as Idx;
+                    }
+                }
+            }
+            // Nodes that belong to no element.
+            npart.iter_mut().filter(|p| **p == -1).for_each(|p| *p = 0);
+            return count_mesh_edges(self.eptr, self.eind, self.nn, Some(self.ncommon));
+        }
+
         let mut edgecut = mem::MaybeUninit::uninit();
+        let _lock = lock_metis();
         unsafe {
             m::METIS_PartMeshDual(
                 &ne as *const Idx as *mut Idx,
@@ -1058,8 +4512,18 @@ impl<'a> Mesh<'a> {
                 npart.as_mut_ptr(),
             )
             .wrap()?;
-            Ok(edgecut.assume_init())
         }
+        // METIS leaves garbage in the part of nodes that belong to no element.
+        let mut referenced = vec![false; npart.len()];
+        for n in self.eind {
+            referenced[*n as usize] = true;
+        }
+        for (p, referenced) in npart.iter_mut().zip(referenced) {
+            if !referenced {
+                *p = 0;
+            }
+        }
+        Ok(unsafe { edgecut.assume_init() })
     }
 
     /// Partition the mesh using its nodal graph.
@@ -1071,23 +4535,98 @@ impl<'a> Mesh<'a> {
     ///
     /// Equivalent of `METIS_PartMeshNodal`.
     ///
+    /// Nodes that belong to no element (see [`Mesh::new_with_nn`]) are
+    /// isolated vertices of the nodal graph: METIS puts them in any part, and
+    /// they count towards its balance.
+    ///
+    /// When `nparts` is greater than the number of nodes, METIS is not called.
+    /// Instead, the `i`th node is put in the `i`th part, each element is put
+    /// in the part of its first node, and the returned edge-cut is the number
+    /// of edges of the nodal graph.
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// // Two quads.
+    /// let eptr = &[0, 4, 8];
+    /// let eind = &[0, 1, 4, 3, 1, 2, 5, 4];
+    /// let (mut epart, mut npart) = ([0; 2], [0; 6]);
+    ///
+    /// let edgecut = metis::Mesh::new(7, eptr, eind)?.part_nodal(&mut epart, &mut npart)?;
+    /// assert_eq!(edgecut, 11);
+    /// assert_eq!(epart, [0, 1]);
+    /// assert_eq!(npart, [0, 1, 2, 3, 4, 5]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Partitioning a mesh without elements (`eptr == [0]`) is a no-op that
+    /// returns an edge-cut of zero, for both this function and
+    /// [`Mesh::part_dual`].
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// let mesh = || metis::Mesh::new(2, &[0], &[]).unwrap();
+    /// assert_eq!(mesh().nn(), 0);
+    /// assert_eq!(mesh().part_nodal(&mut [], &mut [])?, 0);
+    /// assert_eq!(mesh().part_dual(&mut [], &mut [])?, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
     /// # Panics
     ///
     /// This function panics if the length of `epart` is not the number of
     /// elements, or if `nparts`'s is not the number of nodes.
-    pub fn part_nodal(mut self, epart: &mut [Idx], npart: &mut [Idx]) -> Result<Idx> {
-        self.options[option::Numbering::INDEX] = option::Numbering::C.value();
-        let epart_len = Idx::try_from(epart.len()).expect("epart array larger than Idx::MAX");
+    pub fn part_nodal(self, epart: &mut [Idx], npart: &mut [Idx]) -> Result<Idx> {
         assert_eq!(
-            epart_len,
-            self.eptr.len() as Idx - 1,
+            epart.len(),
+            self.eptr.len() - 1,
             "epart.len() must be equal to the number of elements",
         );
-        let npart_len = Idx::try_from(npart.len()).expect("npart array larger than Idx::MAX");
         assert_eq!(
-            npart_len, self.nn,
+            npart.len(),
+            self.nn as usize,
             "npart.len() must be equal to the number of nodes",
         );
+        self.try_part_nodal(epart, npart)
+    }
+
+    /// Fallible version of [`Mesh::part_nodal`].
+    ///
+    /// # Errors
+    ///
+    /// On top of the errors reported by METIS, this function returns
+    /// [`Error::Input`] instead of panicking if the length of `epart` is not
+    /// the number of elements, or if the length of `npart` is not the number
+    /// of nodes.  It also returns [`Error::Input`] if element weights or sizes
+    /// were set (see [`NewMeshError::WrongWeights`]), or if an option holds an
+    /// illegal value (see [`option::InvalidOptionError`]).
+    pub fn try_part_nodal(mut self, epart: &mut [Idx], npart: &mut [Idx]) -> Result<Idx> {
+        self.options[Numbering::INDEX] = Numbering::C.value();
+        if epart.len() != self.eptr.len() - 1 || npart.len() != self.nn as usize {
+            return Err(Error::Input(None));
+        }
+        option::check(&self.options)?;
+        if self.vwgt.is_some() || self.vsize.is_some() {
+            let setter = match self.vwgt {
+                Some(_) => "set_element_vwgt",
+                None => "set_vsize",
+            };
+            return Err(NewMeshError::WrongWeights { setter }.into());
+        }
+        if self.numbering == Numbering::Fortran {
+            let eptr = zero_based(self.eptr);
+            let eind = zero_based(self.eind);
+            let mesh = Mesh {
+                eptr: &eptr,
+                eind: &eind,
+                numbering: Numbering::C,
+                ..self
+            };
+            let objval = mesh.try_part_nodal(epart, npart)?;
+            epart.iter_mut().chain(npart).for_each(|p| *p += 1);
+            return Ok(objval);
+        }
 
         if self.nparts == 1 {
             // METIS does not handle this case well.
@@ -1096,18 +4635,36 @@ impl<'a> Mesh<'a> {
             return Ok(0);
         }
 
+        if self.nn == 0 {
+            // METIS cannot compute the nodal graph of a mesh without nodes.
+            epart.fill(0);
+            return Ok(0);
+        }
+        if self.nparts > self.nn {
+            for (n, p) in npart.iter_mut().enumerate() {
+                *p = n as Idx;
+            }
+            for (p, nodes) in epart.iter_mut().zip(self.eptr.windows(2)) {
+                // Empty elements go to the first part.
+                *p = self.eind[nodes[0] as usize..nodes[1] as usize]
+                    .first()
+                    .map_or(0, |n| *n);
+            }
+            return count_mesh_edges(self.eptr, self.eind, self.nn, None);
+        }
+
         let ne = self.eptr.len() as Idx - 1;
         let mut edgecut = mem::MaybeUninit::uninit();
+        let _lock = lock_metis();
         unsafe {
             m::METIS_PartMeshNodal(
                 &ne as *const Idx as *mut Idx,
                 &self.nn as *const Idx as *mut Idx,
                 slice_to_mut_ptr(self.eptr),
                 slice_to_mut_ptr(self.eind),
-                self.vwgt
-                    .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
-                self.vsize
+                self.node_vwgt
                     .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
+                ptr::null_mut(),
                 &self.nparts as *const Idx as *mut Idx,
                 self.tpwgts
                     .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
@@ -1120,6 +4677,189 @@ impl<'a> Mesh<'a> {
             Ok(edgecut.assume_init())
         }
     }
+
+    /// Computes a fill-reducing ordering of the nodes of the mesh.
+    ///
+    /// The nodal graph of the mesh is computed, and then ordered with
+    /// multilevel nested dissection, as in [`Ordering::nested_dissection`].
+    /// Fine-tuning parameters set on the mesh, such as
+    /// [`option::NSeps`], [`option::CCOrder`] or [`option::PFactor`], are
+    /// forwarded to the ordering.  Weights and `nparts` are not used.
+    ///
+    /// Equivalent of `METIS_MeshToNodal` followed by `METIS_NodeND`.
+    ///
+    /// The permutation is zero-based, even for meshes created with
+    /// [`Mesh::new_fortran`].  See [`Permutation::to_fortran`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// // A 3x3 grid of quadrangles.
+    /// let eptr: Vec<metis::Idx> = (0..=9).map(|e| 4 * e).collect();
+    /// let eind: Vec<metis::Idx> = (0..9)
+    ///     .flat_map(|e| {
+    ///         let n = e / 3 * 4 + e % 3;
+    ///         [n, n + 1, n + 5, n + 4]
+    ///     })
+    ///     .collect();
+    ///
+    /// let p = metis::Mesh::new(1, &eptr, &eind)?
+    ///     .set_option(metis::option::CCOrder(true))
+    ///     .order_nodes()?;
+    ///
+    /// assert_eq!(p.len(), 16);
+    /// assert!(p.is_valid());
+    /// assert_ne!(p, metis::Permutation::identity(16));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn order_nodes(self) -> Result<Permutation> {
+        if self.numbering == Numbering::Fortran {
+            let eptr = zero_based(self.eptr);
+            let eind = zero_based(self.eind);
+            let mesh = Mesh {
+                eptr: &eptr,
+                eind: &eind,
+                numbering: Numbering::C,
+                ..self
+            };
+            return mesh.order_nodes();
+        }
+
+        let nodal = self.nodal_graph()?;
+
+        // SAFETY: the nodal graph generated by METIS is well-formed.
+        unsafe { Ordering::new_unchecked(nodal.xadj(), nodal.adjncy()) }
+            .set_options(&self.options)
+            .nested_dissection()
+    }
+
+    /// Computes the nodal graph of a mesh with C-style numbering.
+    fn nodal_graph(&self) -> Result<Dual> {
+        let ne = self.eptr.len() as Idx - 1;
+        let numbering_flag = 0;
+        let mut xadj = mem::MaybeUninit::uninit();
+        let mut adjncy = mem::MaybeUninit::uninit();
+
+        // SAFETY: METIS_MeshToNodal allocates the xadj and adjncy arrays.
+        // SAFETY: hopefully those arrays are of correct length.
+        unsafe {
+            m::METIS_MeshToNodal(
+                &ne as *const Idx as *mut Idx,
+                &self.nn as *const Idx as *mut Idx,
+                slice_to_mut_ptr(self.eptr),
+                slice_to_mut_ptr(self.eind),
+                &numbering_flag as *const Idx as *mut Idx,
+                xadj.as_mut_ptr(),
+                adjncy.as_mut_ptr(),
+            )
+            .wrap()?;
+            let xadj = xadj.assume_init();
+            let xadj = slice::from_raw_parts_mut(xadj, self.nn as usize + 1);
+            let adjncy = adjncy.assume_init();
+            let adjncy = slice::from_raw_parts_mut(adjncy, xadj[xadj.len() - 1] as usize);
+            // Not a dual graph, but Dual takes care of freeing the arrays.
+            Ok(Dual { xadj, adjncy })
+        }
+    }
+
+    /// Partition a mesh into `nparts` based on a partitioning of the mesh's
+    /// dual graph, and return the partitions of elements and nodes along with
+    /// the edge-cut.
+    ///
+    /// Unlike [`Mesh::part_dual`], this allocates the partition arrays, so
+    /// they cannot have the wrong length.  Parts are numbered from zero, even
+    /// for meshes created with [`Mesh::new_fortran`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// // Two quads.
+    /// let eptr = &[0, 4, 8];
+    /// let eind = &[0, 1, 4, 3, 1, 2, 5, 4];
+    ///
+    /// let (epart, npart, objval) = metis::Mesh::new(2, eptr, eind)?.part_dual_owned()?;
+    /// assert_eq!(epart.len(), 2);
+    /// assert_eq!(npart.len(), 6);
+    /// assert_ne!(epart.part_of(0), epart.part_of(1));
+    /// assert_eq!(objval, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn part_dual_owned(self) -> Result<(PartitionVec, PartitionVec, Idx)> {
+        let nparts = self.nparts;
+        let base = self.numbering.value();
+        let mut epart = vec![0; self.eptr.len() - 1];
+        let mut npart = vec![0; self.nn as usize];
+        let objval = self.try_part_dual(&mut epart, &mut npart)?;
+        epart.iter_mut().chain(&mut npart).for_each(|p| *p -= base);
+        Ok((
+            PartitionVec::new_unchecked(epart, nparts),
+            PartitionVec::new_unchecked(npart, nparts),
+            objval,
+        ))
+    }
+
+    /// Partition a mesh into `nparts` based on a partitioning of the mesh's
+    /// nodal graph, and return the partitions of elements and nodes along
+    /// with the edge-cut.
+    ///
+    /// Unlike [`Mesh::part_nodal`], this allocates the partition arrays, so
+    /// they cannot have the wrong length.  Parts are numbered from zero, even
+    /// for meshes created with [`Mesh::new_fortran`].
+    pub fn part_nodal_owned(self) -> Result<(PartitionVec, PartitionVec, Idx)> {
+        let nparts = self.nparts;
+        let base = self.numbering.value();
+        let mut epart = vec![0; self.eptr.len() - 1];
+        let mut npart = vec![0; self.nn as usize];
+        let objval = self.try_part_nodal(&mut epart, &mut npart)?;
+        epart.iter_mut().chain(&mut npart).for_each(|p| *p -= base);
+        Ok((
+            PartitionVec::new_unchecked(epart, nparts),
+            PartitionVec::new_unchecked(npart, nparts),
+            objval,
+        ))
+    }
+}
+
+/// The arrays and parameters of a [`Mesh`], returned by [`Mesh::into_inner`].
+///
+/// See the setters of [`Mesh`] for the meaning of each field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct MeshParts<'a> {
+    /// The number of nodes in the mesh.
+    pub nn: Idx,
+
+    /// The number of parts to partition the mesh.
+    pub nparts: Idx,
+
+    /// The number of nodes two elements must share for an edge to appear in
+    /// the dual graph.
+    pub ncommon: Idx,
+
+    /// The element index array.
+    pub eptr: &'a [Idx],
+
+    /// The element array.
+    pub eind: &'a [Idx],
+
+    /// The computational weights of the elements, if set.
+    pub vwgt: Option<&'a [Idx]>,
+
+    /// The computational weights of the nodes, if set.
+    pub node_vwgt: Option<&'a [Idx]>,
+
+    /// The communication weights of the elements, if set.
+    pub vsize: Option<&'a [Idx]>,
+
+    /// The target partition weights, if set.
+    pub tpwgts: Option<&'a [Real]>,
+
+    /// The fine-tuning parameters.
+    pub options: [Idx; NOPTIONS],
 }
 
 /// The dual of a mesh.
@@ -1146,8 +4886,65 @@ impl Dual {
     pub fn as_mut(&mut self) -> (&mut [Idx], &mut [Idx]) {
         (self.xadj, self.adjncy)
     }
+
+    /// Creates a [`Graph`] out of the dual, to be partitioned.
+    ///
+    /// The graph borrows the arrays allocated by METIS, so no copy is made.
+    /// See [`Graph::new`] for the meaning of `ncon` and `nparts`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `ncon` or `nparts` are not strictly
+    /// greater than zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// // A 3x3 grid of quadrangles.
+    /// let eptr: Vec<metis::Idx> = (0..=9).map(|e| 4 * e).collect();
+    /// let eind: Vec<metis::Idx> = (0..9)
+    ///     .flat_map(|e| {
+    ///         let n = e / 3 * 4 + e % 3;
+    ///         [n, n + 1, n + 5, n + 4]
+    ///     })
+    ///     .collect();
+    ///
+    /// let dual = metis::mesh_to_dual(&eptr, &eind, 2)?;
+    ///
+    /// let mut part = [0; 9];
+    /// let edgecut = dual
+    ///     .as_graph(1, 3)?
+    ///     .set_option(metis::option::Seed(7))
+    ///     .part_kway(&mut part)?;
+    ///
+    /// // Same as copying the arrays beforehand.
+    /// let xadj = dual.xadj().to_vec();
+    /// let adjncy = dual.adjncy().to_vec();
+    /// let mut part_copy = [0; 9];
+    /// let edgecut_copy = metis::Graph::new(1, 3, &xadj, &adjncy)?
+    ///     .set_option(metis::option::Seed(7))
+    ///     .part_kway(&mut part_copy)?;
+    ///
+    /// assert_eq!(edgecut, edgecut_copy);
+    /// assert_eq!(part, part_copy);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_graph(&self, ncon: Idx, nparts: Idx) -> StdResult<Graph<'_>, NewGraphError> {
+        Graph::new(ncon, nparts, self.xadj, self.adjncy)
+    }
 }
 
+// SAFETY: the arrays are only reachable through `Dual`, which owns them as
+// much as a `Vec` would.  They are freed with `METIS_Free`, which is `free`
+// and can be called from any thread.
+unsafe impl Send for Dual {}
+
+// SAFETY: `Dual` only hands out shared references to its arrays through
+// `&self`.
+unsafe impl Sync for Dual {}
+
 impl Drop for Dual {
     fn drop(&mut self) {
         unsafe {
@@ -1163,6 +4960,18 @@ impl Drop for Dual {
 ///
 /// This function returns an error if `eptr` and `eind` don't follow the mesh
 /// format given in [`Mesh::new`].
+///
+/// ```rust
+/// use metis::{mesh_to_dual, Error};
+///
+/// assert!(matches!(mesh_to_dual(&[], &[], 1), Err(Error::Input(Some(_)))));
+/// assert!(matches!(mesh_to_dual(&[3, 5], &[0, 1, 2, 3, 4], 1), Err(Error::Input(Some(_)))));
+///
+/// // A mesh without elements is valid, its dual is empty.
+/// let dual = mesh_to_dual(&[0], &[], 1).unwrap();
+/// assert_eq!(dual.xadj(), [0]);
+/// assert!(dual.adjncy().is_empty());
+/// ```
 pub fn mesh_to_dual(eptr: &[Idx], eind: &[Idx], ncommon: Idx) -> Result<Dual> {
     let (ne, nn) = check_mesh_structure(eptr, eind)?;
     let mut xadj = mem::MaybeUninit::uninit();
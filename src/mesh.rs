@@ -0,0 +1,270 @@
+//! Utilities for partitions of meshes.
+//!
+//! Meshes are given in the format of [`crate::Mesh::new`].
+
+use crate::check_mesh_structure;
+use crate::find_negative;
+use crate::quality;
+use crate::Idx;
+use crate::NewMeshError;
+use crate::Result;
+use crate::StructureErrorKind;
+use std::cmp;
+
+/// Derives a node partition from an element partition: each node goes to the
+/// part most of its elements are in.
+///
+/// Ties are broken toward the lowest part.  Nodes that belong to no element go
+/// to part zero.  This is useful to post-process element partitions computed
+/// by other tools, like the `npart` array of [`crate::Mesh::part_dual`].
+///
+/// METIS itself also balances node counts, so its `npart` may differ from this
+/// function on nodes that are tied between several parts.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `eptr` and `eind` are not
+/// a valid mesh structure of at most `nn` nodes, if the length of `epart` is
+/// not the number of elements, or if some labels are negative.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::mesh::npart_from_epart;
+/// use metis::Mesh;
+///
+/// // A 8x8 grid of quadrangles.
+/// let n = 8;
+/// let mut eptr = vec![0];
+/// let mut eind = Vec::new();
+/// for i in 0..n {
+///     for j in 0..n {
+///         let a = i * (n + 1) + j;
+///         eind.extend([a, a + 1, a + n + 2, a + n + 1]);
+///         eptr.push(eind.len() as metis::Idx);
+///     }
+/// }
+/// let nn = (n + 1) * (n + 1);
+/// let mut epart = vec![0; (n * n) as usize];
+/// let mut npart = vec![0; nn as usize];
+/// Mesh::new(4, &eptr, &eind)?.part_dual(&mut epart, &mut npart)?;
+///
+/// // Nodes only differ when METIS picks another part among the most common
+/// // ones.
+/// let majority = npart_from_epart(&eptr, &eind, &epart, nn)?;
+/// for node in 0..nn {
+///     let count = |p| {
+///         let elements = eptr.windows(2).zip(&epart);
+///         elements
+///             .filter(|(w, q)| **q == p && eind[w[0] as usize..w[1] as usize].contains(&node))
+///             .count()
+///     };
+///     let node = node as usize;
+///     assert_eq!(count(majority[node]), count(npart[node]));
+/// }
+///
+/// // A node shared by parts 0, 1 and 1 goes to part 1.
+/// let npart = npart_from_epart(&[0, 2, 4, 6], &[0, 1, 0, 2, 0, 3], &[0, 1, 1], 5)?;
+/// assert_eq!(npart, [1, 0, 1, 1, 0]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn npart_from_epart(eptr: &[Idx], eind: &[Idx], epart: &[Idx], nn: Idx) -> Result<Vec<Idx>> {
+    vote(eptr, eind, epart, nn, None)
+}
+
+/// Same as [`npart_from_epart`], except that ties are broken toward the part
+/// whose elements around the node have the largest total weight, and then
+/// toward the lowest part.
+///
+/// # Errors
+///
+/// This function returns the same errors as [`npart_from_epart`], and also
+/// fails if the length of `ewgt` is not the number of elements, or if some
+/// weights are negative.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::mesh::{npart_from_epart, npart_from_epart_weighted};
+///
+/// // Two triangles sharing the edge 1-2, in different parts.
+/// let eptr = &[0, 3, 6];
+/// let eind = &[0, 1, 2, 1, 2, 3];
+/// let epart = &[0, 1];
+///
+/// assert_eq!(npart_from_epart(eptr, eind, epart, 4)?, [0, 0, 0, 1]);
+/// let npart = npart_from_epart_weighted(eptr, eind, epart, 4, &[1, 5])?;
+/// assert_eq!(npart, [0, 1, 1, 1]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn npart_from_epart_weighted(
+    eptr: &[Idx],
+    eind: &[Idx],
+    epart: &[Idx],
+    nn: Idx,
+    ewgt: &[Idx],
+) -> Result<Vec<Idx>> {
+    NewMeshError::check_len("ewgt", epart.len(), ewgt.len())?;
+    if let Some((index, value)) = find_negative(ewgt) {
+        return Err(NewMeshError::NegativeValue {
+            array: "ewgt",
+            index,
+            value,
+        }
+        .into());
+    }
+    vote(eptr, eind, epart, nn, Some(ewgt))
+}
+
+/// Assigns each node to the part with the most elements around it, then the
+/// largest element weight when `ewgt` is given, then the lowest label.
+fn vote(
+    eptr: &[Idx],
+    eind: &[Idx],
+    epart: &[Idx],
+    nn: Idx,
+    ewgt: Option<&[Idx]>,
+) -> Result<Vec<Idx>> {
+    let (ne, _) = check_mesh_structure(eptr, eind)?;
+    NewMeshError::check_len("epart", ne as usize, epart.len())?;
+    if let Some((index, value)) = find_negative(epart) {
+        return Err(NewMeshError::NegativeValue {
+            array: "epart",
+            index,
+            value,
+        }
+        .into());
+    }
+    if nn < 0 {
+        return Err(NewMeshError::msg(
+            StructureErrorKind::OutOfBounds,
+            "the number of nodes is negative",
+        )
+        .into());
+    }
+    if let Some(index) = eind.iter().position(|node| nn <= *node) {
+        return Err(NewMeshError::at(
+            StructureErrorKind::OutOfBounds,
+            "values in the node index are out of bounds",
+            "eind",
+            index,
+            eind[index],
+            0..=nn - 1,
+        )
+        .into());
+    }
+    let nn = nn as usize;
+
+    // Elements around each node, in CSR format.
+    let mut nptr = vec![0; nn + 1];
+    for node in eind {
+        nptr[*node as usize + 1] += 1;
+    }
+    for i in 1..nptr.len() {
+        nptr[i] += nptr[i - 1];
+    }
+    let mut next = nptr.clone();
+    let mut nind = vec![0; eind.len()];
+    for (e, w) in eptr.windows(2).enumerate() {
+        for node in &eind[w[0] as usize..w[1] as usize] {
+            nind[next[*node as usize]] = e;
+            next[*node as usize] += 1;
+        }
+    }
+
+    let nparts = epart.iter().max().map_or(0, |max| *max as usize + 1);
+    let mut votes = vec![(0, 0); nparts];
+    let mut touched = Vec::new();
+    let mut npart = vec![0; nn];
+    for (node, p) in npart.iter_mut().enumerate() {
+        for e in &nind[nptr[node]..nptr[node + 1]] {
+            let q = epart[*e] as usize;
+            if votes[q] == (0, 0) {
+                touched.push(q);
+            }
+            votes[q].0 += 1;
+            votes[q].1 += ewgt.map_or(0, |ewgt| i64::from(ewgt[*e]));
+        }
+        let best = touched
+            .drain(..)
+            .max_by_key(|q| (votes[*q], cmp::Reverse(*q)));
+        if let Some(best) = best {
+            *p = best as Idx;
+        }
+        for e in &nind[nptr[node]..nptr[node + 1]] {
+            votes[epart[*e] as usize] = (0, 0);
+        }
+    }
+    Ok(npart)
+}
+
+/// Reorders the elements of a mesh so that the elements of each part are
+/// contiguous, for example after [`crate::Mesh::part_dual`].
+///
+/// Elements are sorted by part, and keep their original order within a part.
+/// Returns the new `eptr` and `eind`, and the new index of each element.
+/// Elements may have different numbers of nodes.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `eptr` and `eind` are not
+/// a valid mesh structure, if the length of `epart` is not the number of
+/// elements, if `nparts` is not strictly positive, or if some labels are not
+/// within zero and `nparts`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::mesh::reorder_elements;
+///
+/// // Two quadrangles and two triangles.
+/// //  3 --- 4 --- 5
+/// //  | 0 / |  1  |
+/// //  | / 2 |     |
+/// //  0 --- 1 --- 2
+/// //        |  3 /
+/// //        | /
+/// //        6
+/// let eptr = &[0, 3, 7, 10, 13];
+/// let eind = &[0, 4, 3, 1, 2, 5, 4, 0, 1, 4, 1, 2, 6];
+/// let epart = &[1, 0, 1, 0];
+///
+/// let (new_eptr, new_eind, map) = reorder_elements(eptr, eind, epart, 2)?;
+/// assert_eq!(map, [2, 0, 3, 1]);
+/// assert_eq!(new_eptr, [0, 4, 7, 10, 13]);
+/// assert_eq!(new_eind, [1, 2, 5, 4, 1, 2, 6, 0, 4, 3, 0, 1, 4]);
+///
+/// // Labels must be within zero and the number of parts.
+/// assert!(reorder_elements(eptr, eind, &[1, 0, 2, 0], 2).is_err());
+/// assert!(reorder_elements(eptr, eind, &[1, 0, metis::Idx::MAX, 0], 2).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn reorder_elements(
+    eptr: &[Idx],
+    eind: &[Idx],
+    epart: &[Idx],
+    nparts: Idx,
+) -> Result<(Vec<Idx>, Vec<Idx>, Vec<Idx>)> {
+    let (ne, _) = check_mesh_structure(eptr, eind)?;
+    NewMeshError::check_len("epart", ne as usize, epart.len())?;
+    let index = quality::invert_partition(epart, nparts)?;
+
+    let mut new_eptr = Vec::with_capacity(eptr.len());
+    let mut new_eind = Vec::with_capacity(eind.len());
+    let mut map = vec![0; epart.len()];
+    new_eptr.push(0);
+    for (new, old) in index.pind().iter().enumerate() {
+        let old = *old as usize;
+        map[old] = new as Idx;
+        new_eind.extend_from_slice(&eind[eptr[old] as usize..eptr[old + 1] as usize]);
+        new_eptr.push(new_eind.len() as Idx);
+    }
+    Ok((new_eptr, new_eind, map))
+}
@@ -0,0 +1,325 @@
+//! Conversion of [nalgebra-sparse](https://docs.rs/nalgebra-sparse) matrices.
+//!
+//! This mirrors the [`crate::sprs`] integration: the sparsity pattern of a
+//! square matrix is a graph, whose vertices are the rows (or columns) of the
+//! matrix.  Indices are converted to [`Idx`] into arrays owned by the returned
+//! graph, and diagonal entries are dropped.
+//!
+//! [`CsrMatrix`] and [`CscMatrix`] implement [`Partition`], re-exported here
+//! from the crate root, which ignores their values.  They are only used as
+//! edge weights by [`PartitionWeighted::setup_partition_weighted`] and
+//! [`PartitionWeighted::setup_partition_sym`].
+//!
+//! Both matrix types also implement [`crate::PartitionExt`], to set the number
+//! of constraints, the options and the weights of the graph from a
+//! [`crate::PartitionConfig`].
+
+use crate::csr;
+use crate::csr::WeightMerge;
+use crate::sparse;
+use crate::GraphOwned;
+use crate::Idx;
+use crate::NewGraphError;
+use crate::PartitionConfig;
+use crate::PartitionExt;
+use crate::Result;
+use nalgebra_sparse::CscMatrix;
+use nalgebra_sparse::CsrMatrix;
+use std::result::Result as StdResult;
+
+pub use crate::Partition;
+
+/// The rows of the matrix are the vertices of the graph, which has no edge
+/// weights.  Its pattern must be symmetric, see
+/// [`PartitionWeighted::setup_partition_sym`] otherwise.
+///
+/// # Errors
+///
+/// [`Partition::setup_partition`] returns an error if the matrix is not
+/// square, if it is too large for its indices to fit in [`Idx`], or if
+/// `nparts` is not strictly positive.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::nalgebra_sparse::{Partition, PartitionWeighted};
+/// use nalgebra_sparse::{CooMatrix, CsrMatrix};
+///
+/// // The 2D Laplacian of a 8x8 grid.
+/// let n = 8;
+/// let mut coo = CooMatrix::new(n * n, n * n);
+/// for i in 0..n {
+///     for j in 0..n {
+///         let v = n * i + j;
+///         coo.push(v, v, 4.0);
+///         if i > 0 {
+///             coo.push(v, v - n, -1.0);
+///         }
+///         if j > 0 {
+///             coo.push(v, v - 1, -1.0);
+///         }
+///         if j < n - 1 {
+///             coo.push(v, v + 1, -1.0);
+///         }
+///         if i < n - 1 {
+///             coo.push(v, v + n, -1.0);
+///         }
+///     }
+/// }
+/// let laplacian = CsrMatrix::from(&coo);
+///
+/// let graph = laplacian.setup_partition(4)?;
+/// assert_eq!(graph.adjncy().len(), laplacian.nnz() - n * n);
+///
+/// let mut part = vec![0; n * n];
+/// let edgecut = graph.part_kway(&mut part)?;
+/// assert!(edgecut <= 2 * n as metis::Idx);
+///
+/// // Off-diagonal values are all -1.
+/// let weighted = laplacian.setup_partition_weighted(4, |v| -v as metis::Idx)?;
+/// assert!(weighted.adjwgt().unwrap().iter().all(|w| *w == 1));
+/// # Ok(())
+/// # }
+/// ```
+impl<T> Partition for CsrMatrix<T> {
+    fn setup_partition(&self, nparts: Idx) -> StdResult<GraphOwned, NewGraphError> {
+        let shape = (self.nrows(), self.ncols());
+        let (offsets, indices) = (self.row_offsets(), self.col_indices());
+        Ok(sparse::pattern_graph(shape, offsets, indices, nparts)?.0)
+    }
+}
+
+/// The columns of the matrix are the vertices of the graph, likewise.
+impl<T> Partition for CscMatrix<T> {
+    fn setup_partition(&self, nparts: Idx) -> StdResult<GraphOwned, NewGraphError> {
+        let shape = (self.ncols(), self.nrows());
+        let (offsets, indices) = (self.col_offsets(), self.row_indices());
+        Ok(sparse::pattern_graph(shape, offsets, indices, nparts)?.0)
+    }
+}
+
+/// Partitioning of the rows of [`CsrMatrix`] and the columns of
+/// [`CscMatrix`], with their values as edge weights.
+pub trait PartitionWeighted<T> {
+    /// Same as [`Partition::setup_partition`], with the edge weights given by
+    /// applying `weight` to the values of the matrix.
+    ///
+    /// `weight` is not called on the values of diagonal entries.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the matrix is not square, if some
+    /// indices do not fit in [`Idx`] (see [`crate::convert::ConvertError`]),
+    /// if `nparts` is not strictly positive, or if some weights are not
+    /// strictly positive.
+    fn setup_partition_weighted<F>(&self, nparts: Idx, weight: F) -> Result<GraphOwned>
+    where
+        F: FnMut(&T) -> Idx;
+
+    /// Same as [`PartitionWeighted::setup_partition_weighted`], for matrices
+    /// whose sparsity pattern is not symmetric.
+    ///
+    /// The graph is the pattern of `A + Aᵀ` without its diagonal, and the
+    /// weights of entries `(i, j)` and `(j, i)` are combined according to
+    /// `merge`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as
+    /// [`PartitionWeighted::setup_partition_weighted`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::csr::WeightMerge;
+    /// use metis::nalgebra_sparse::PartitionWeighted;
+    /// use nalgebra_sparse::CsrMatrix;
+    ///
+    /// // Upper bidiagonal matrix.
+    /// // 1 2 . .
+    /// // . 1 3 .
+    /// // . . 1 4
+    /// // . . . 1
+    /// let upper = CsrMatrix::try_from_csr_data(
+    ///     4,
+    ///     4,
+    ///     vec![0, 2, 4, 6, 7],
+    ///     vec![0, 1, 1, 2, 2, 3, 3],
+    ///     vec![1, 2, 1, 3, 1, 4, 1],
+    /// )
+    /// .unwrap();
+    ///
+    /// let graph = upper.setup_partition_sym(2, WeightMerge::Sum, |v| *v)?;
+    /// assert_eq!(graph.xadj(), &[0, 1, 3, 5, 6]);
+    /// assert_eq!(graph.adjncy(), &[1, 0, 2, 1, 3, 2]);
+    /// assert_eq!(graph.adjwgt(), Some(&[2, 2, 3, 3, 4, 4][..]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn setup_partition_sym<F>(
+        &self,
+        nparts: Idx,
+        merge: WeightMerge,
+        weight: F,
+    ) -> Result<GraphOwned>
+    where
+        F: FnMut(&T) -> Idx;
+}
+
+impl<T> PartitionWeighted<T> for CsrMatrix<T> {
+    fn setup_partition_weighted<F>(&self, nparts: Idx, weight: F) -> Result<GraphOwned>
+    where
+        F: FnMut(&T) -> Idx,
+    {
+        let shape = (self.nrows(), self.ncols());
+        let (offsets, indices) = (self.row_offsets(), self.col_indices());
+        weighted(shape, offsets, indices, self.values(), nparts, None, weight)
+    }
+
+    fn setup_partition_sym<F>(
+        &self,
+        nparts: Idx,
+        merge: WeightMerge,
+        weight: F,
+    ) -> Result<GraphOwned>
+    where
+        F: FnMut(&T) -> Idx,
+    {
+        let shape = (self.nrows(), self.ncols());
+        let (offsets, indices) = (self.row_offsets(), self.col_indices());
+        weighted(
+            shape,
+            offsets,
+            indices,
+            self.values(),
+            nparts,
+            Some(merge),
+            weight,
+        )
+    }
+}
+
+impl<T> PartitionWeighted<T> for CscMatrix<T> {
+    fn setup_partition_weighted<F>(&self, nparts: Idx, weight: F) -> Result<GraphOwned>
+    where
+        F: FnMut(&T) -> Idx,
+    {
+        let shape = (self.ncols(), self.nrows());
+        let (offsets, indices) = (self.col_offsets(), self.row_indices());
+        weighted(shape, offsets, indices, self.values(), nparts, None, weight)
+    }
+
+    fn setup_partition_sym<F>(
+        &self,
+        nparts: Idx,
+        merge: WeightMerge,
+        weight: F,
+    ) -> Result<GraphOwned>
+    where
+        F: FnMut(&T) -> Idx,
+    {
+        let shape = (self.ncols(), self.nrows());
+        let (offsets, indices) = (self.col_offsets(), self.row_indices());
+        weighted(
+            shape,
+            offsets,
+            indices,
+            self.values(),
+            nparts,
+            Some(merge),
+            weight,
+        )
+    }
+}
+
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::option::Opt;
+/// use metis::option::Seed;
+/// use metis::PartitionConfig;
+/// use metis::PartitionExt;
+/// use nalgebra_sparse::CsrMatrix;
+///
+/// // 0 - 1 - 2 - 3, with a heavy edge in the middle.
+/// let matrix = CsrMatrix::try_from_csr_data(
+///     4,
+///     4,
+///     vec![0, 1, 3, 5, 6],
+///     vec![1, 0, 2, 1, 3, 2],
+///     vec![1.0, 1.0, 9.0, 9.0, 1.0, 1.0],
+/// )
+/// .unwrap();
+///
+/// let config = PartitionConfig::new(2)
+///     .set_option(Seed(3))
+///     .set_adjwgt(|v: &f64| *v as metis::Idx);
+/// let graph = matrix.setup_partition_with(&config)?;
+/// assert_eq!(graph.adjwgt(), Some(&[1, 1, 9, 9, 1, 1][..]));
+/// assert_eq!(graph.as_graph().into_inner().options[Seed::INDEX], 3);
+///
+/// let mut part = [0; 4];
+/// graph.part_recursive(&mut part)?;
+/// # Ok(())
+/// # }
+/// ```
+impl<T> PartitionExt<T> for CsrMatrix<T> {
+    fn setup_partition_with(&self, config: &PartitionConfig<'_, T>) -> Result<GraphOwned> {
+        let shape = (self.nrows(), self.ncols());
+        let (offsets, indices) = (self.row_offsets(), self.col_indices());
+        with_config(shape, offsets, indices, self.values(), config)
+    }
+}
+
+impl<T> PartitionExt<T> for CscMatrix<T> {
+    fn setup_partition_with(&self, config: &PartitionConfig<'_, T>) -> Result<GraphOwned> {
+        let shape = (self.ncols(), self.nrows());
+        let (offsets, indices) = (self.col_offsets(), self.row_indices());
+        with_config(shape, offsets, indices, self.values(), config)
+    }
+}
+
+/// Builds the graph of a compressed matrix according to `config`.
+fn with_config<T>(
+    shape: (usize, usize),
+    offsets: &[usize],
+    indices: &[usize],
+    values: &[T],
+    config: &PartitionConfig<'_, T>,
+) -> Result<GraphOwned> {
+    let (xadj, adjncy, kept) = sparse::pattern(shape, offsets, indices, true)?;
+    let adjwgt = config
+        .adjwgt()
+        .map(|weight| kept.into_iter().map(|e| weight(&values[e])).collect());
+    Ok(config.build(xadj, adjncy, adjwgt)?)
+}
+
+/// Builds the weighted graph of a compressed matrix, symmetrized when `merge`
+/// is given.
+fn weighted<T, F>(
+    shape: (usize, usize),
+    offsets: &[usize],
+    indices: &[usize],
+    values: &[T],
+    nparts: Idx,
+    merge: Option<WeightMerge>,
+    mut weight: F,
+) -> Result<GraphOwned>
+where
+    F: FnMut(&T) -> Idx,
+{
+    let (xadj, adjncy, kept) = sparse::pattern(shape, offsets, indices, true)?;
+    let adjwgt: Vec<Idx> = kept.into_iter().map(|e| weight(&values[e])).collect();
+    let (xadj, adjncy, adjwgt) = match merge {
+        Some(merge) => {
+            let (xadj, adjncy, adjwgt) = csr::symmetrize(&xadj, &adjncy, Some(&adjwgt), merge)?;
+            (xadj, adjncy, adjwgt.unwrap_or_default())
+        }
+        None => (xadj, adjncy, adjwgt),
+    };
+    let graph = GraphOwned::new(1, nparts, xadj, adjncy)?;
+    Ok(graph.try_set_adjwgt(adjwgt)?)
+}
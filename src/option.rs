@@ -9,6 +9,10 @@
 
 use crate::m;
 use crate::Idx;
+use crate::NOPTIONS;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
 
 mod private {
     pub trait Sealed {}
@@ -28,6 +32,99 @@ pub trait Opt: private::Sealed {
     fn value(self) -> Idx;
 }
 
+/// Returns the options array as initialized by METIS.
+///
+/// Equivalent of `METIS_SetDefaultOptions`.  METIS marks every option as
+/// unset (`-1`), and each routine then picks its own default value, which is
+/// why defaults are documented per option (and sometimes per routine) in this
+/// module.  The returned array can be tweaked and passed to
+/// [`crate::Graph::set_options`] or [`crate::Mesh::set_options`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// # use metis::Graph;
+/// use metis::option::Opt as _;
+///
+/// let xadj = &[0, 2, 5, 8, 11, 13, 16, 20, 24, 28, 31, 33, 36, 39, 42, 44];
+/// let adjncy = &[
+///     1, 5, 0, 2, 6, 1, 3, 7, 2, 4, 8, 3, 9, 0, 6, 10, 1, 5, 7, 11, 2, 6, 8,
+///     12, 3, 7, 9, 13, 4, 8, 14, 5, 11, 6, 10, 12, 7, 11, 13, 8, 12, 14, 9,
+///     13,
+/// ];
+///
+/// let mut options = metis::option::defaults();
+/// options[metis::option::Seed::INDEX] = 42;
+/// let mut part1 = [0; 15];
+/// Graph::new(1, 3, xadj, adjncy)?
+///     .set_options(&options)
+///     .part_kway(&mut part1)?;
+///
+/// let mut part2 = [0; 15];
+/// Graph::new(1, 3, xadj, adjncy)?
+///     .set_option(metis::option::Seed(42))
+///     .part_kway(&mut part2)?;
+///
+/// assert_eq!(part1, part2);
+/// # Ok(())
+/// # }
+/// ```
+pub fn defaults() -> [Idx; NOPTIONS] {
+    let mut options = [0; NOPTIONS];
+    // SAFETY: options has the length METIS expects, and this function cannot
+    // fail.
+    unsafe {
+        m::METIS_SetDefaultOptions(options.as_mut_ptr());
+    }
+    options
+}
+
+/// The seed of new graphs, meshes and orderings when the `deterministic`
+/// feature is enabled.
+///
+/// It is the seed the vendored METIS uses when none is set.  See [`Seed`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::option::DETERMINISTIC_SEED;
+/// use metis::Mesh;
+///
+/// // A 3x3 grid of quadrangles.
+/// let eptr: Vec<metis::Idx> = (0..=9).map(|e| 4 * e).collect();
+/// let eind: Vec<metis::Idx> = (0..9)
+///     .flat_map(|e| {
+///         let n = e / 3 * 4 + e % 3;
+///         [n, n + 1, n + 5, n + 4]
+///     })
+///     .collect();
+///
+/// let (mut epart1, mut npart1) = ([0; 9], [0; 16]);
+/// let (mut epart2, mut npart2) = ([0; 9], [0; 16]);
+/// Mesh::new(3, &eptr, &eind)?.part_dual(&mut epart1, &mut npart1)?;
+/// Mesh::new(3, &eptr, &eind)?
+///     .set_seed(DETERMINISTIC_SEED)
+///     .part_dual(&mut epart2, &mut npart2)?;
+/// assert_eq!(epart1, epart2);
+/// assert_eq!(npart1, npart2);
+/// # Ok(())
+/// # }
+/// ```
+pub const DETERMINISTIC_SEED: Idx = 4321;
+
+/// The options of new graphs, meshes and orderings.
+///
+/// All options are unset, except the seed with the `deterministic` feature.
+pub(crate) fn initial() -> [Idx; NOPTIONS] {
+    let mut options = [-1; NOPTIONS];
+    if cfg!(feature = "deterministic") {
+        options[Seed::INDEX] = DETERMINISTIC_SEED;
+    }
+    options
+}
+
 /// Specifies the partitioning method.
 pub enum PType {
     /// Multilevel recursive bisection.
@@ -50,6 +147,8 @@ impl Opt for PType {
 }
 
 /// Specifies the type of objective.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ObjType {
     /// Edge-cut minimization.
     Cut,
@@ -180,7 +279,7 @@ impl Opt for NSeps {
 
 /// Used to indicate which numbering scheme is used for the adjacency structure
 /// of a graph or the element-node structure of a mesh.
-#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum Numbering {
     /// C-style numbering which is assumed to start from 0.
     C,
@@ -217,6 +316,13 @@ impl Opt for NIter {
 }
 
 /// Specifies the seed for the random number generator.
+///
+/// By default, the seed is left to METIS, which picks a fixed value when it
+/// is unset (at least the vendored version does).  With the `deterministic`
+/// feature of this crate, new graphs, meshes and orderings have their seed
+/// pinned to [`DETERMINISTIC_SEED`] instead, so that results do not depend on
+/// how METIS was built.  Setting the seed explicitly, with this option or with
+/// [`crate::Graph::set_seed`], takes precedence in both cases.
 pub struct Seed(pub Idx);
 
 impl private::Sealed for Seed {}
@@ -357,6 +463,7 @@ impl Opt for UFactor {
 ///
 /// The default value is false for every field (no debugging/progress
 /// information).
+#[derive(Default)]
 pub struct DbgLvl {
     /// Prints various diagnostic messages.
     pub info: bool,
@@ -423,3 +530,1547 @@ impl Opt for DbgLvl {
         dbglvl
     }
 }
+
+/// Builder of options arrays with typed setters.
+///
+/// Unlike a raw `[Idx; NOPTIONS]` array, the builder knows which value goes
+/// at which index, and does not accept illegal values: integer setters panic
+/// on them.  Options that are not set stay `-1`, which stands for the default
+/// value of METIS.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::option::{CType, OptionsBuilder};
+/// use metis::Graph;
+///
+/// let xadj = &[0, 1, 2];
+/// let adjncy = &[1, 0];
+///
+/// let options = OptionsBuilder::new().ctype(CType::Rm).niter(4).seed(42);
+/// let array = options.build();
+/// assert_eq!(array.iter().filter(|o| **o != -1).count(), 3);
+///
+/// // Set the whole array...
+/// let mut part = [0; 2];
+/// Graph::new(1, 2, xadj, adjncy)?
+///     .set_options(&array)
+///     .part_recursive(&mut part)?;
+///
+/// // ...or only the options of the builder.
+/// let mut graph = Graph::new(1, 2, xadj, adjncy)?;
+/// options.apply_to(&mut graph);
+/// graph.part_recursive(&mut part)?;
+/// assert_ne!(part[0], part[1]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Illegal values are rejected when set:
+///
+/// ```should_panic
+/// metis::option::OptionsBuilder::new().niter(0);
+/// ```
+///
+/// The `try_` setters return an error instead:
+///
+/// ```rust
+/// use metis::option::{InvalidOptionError, NIter, Opt as _, OptionsBuilder};
+///
+/// let options = OptionsBuilder::new().try_niter(4).unwrap();
+/// assert_eq!(options.build()[NIter::INDEX], 4);
+///
+/// let err: InvalidOptionError = options.try_niter(0).unwrap_err();
+/// assert_eq!(err.index(), NIter::INDEX);
+/// assert_eq!(err.value(), 0);
+/// assert!(options.try_ufactor(-2).is_err());
+/// assert!(options.try_ncuts(0).is_err());
+/// assert!(options.try_nseps(-3).is_err());
+/// assert!(options.try_pfactor(-2).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionsBuilder {
+    options: [Idx; NOPTIONS],
+}
+
+impl Default for OptionsBuilder {
+    fn default() -> OptionsBuilder {
+        OptionsBuilder::new()
+    }
+}
+
+impl OptionsBuilder {
+    /// Creates a builder where all options are unset.
+    pub fn new() -> OptionsBuilder {
+        OptionsBuilder {
+            options: [-1; NOPTIONS],
+        }
+    }
+
+    /// Sets `option`, which must be legal.
+    fn set<O: Opt>(self, option: O) -> OptionsBuilder {
+        self.try_set(option).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Sets `option`, or fails if it is illegal.
+    fn try_set<O: Opt>(mut self, option: O) -> Result<OptionsBuilder, InvalidOptionError> {
+        let value = option.value();
+        legal(O::INDEX, value)?;
+        self.options[O::INDEX] = value;
+        Ok(self)
+    }
+
+    /// Sets the partitioning method, see [`PType`].
+    pub fn ptype(self, ptype: PType) -> OptionsBuilder {
+        self.set(ptype)
+    }
+
+    /// Sets the type of objective, see [`ObjType`].
+    pub fn objtype(self, objtype: ObjType) -> OptionsBuilder {
+        self.set(objtype)
+    }
+
+    /// Sets the matching scheme used during coarsening, see [`CType`].
+    pub fn ctype(self, ctype: CType) -> OptionsBuilder {
+        self.set(ctype)
+    }
+
+    /// Sets the algorithm used during initial partitioning, see [`IpType`].
+    pub fn iptype(self, iptype: IpType) -> OptionsBuilder {
+        self.set(iptype)
+    }
+
+    /// Sets the algorithm used for refinement, see [`RType`].
+    pub fn rtype(self, rtype: RType) -> OptionsBuilder {
+        self.set(rtype)
+    }
+
+    /// Sets the number of partitions to compute, see [`NCuts`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `ncuts` is zero or less than `-1`.
+    pub fn ncuts(self, ncuts: Idx) -> OptionsBuilder {
+        self.set(NCuts(ncuts))
+    }
+
+    /// Same as [`OptionsBuilder::ncuts`], but fails instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `ncuts` is zero or less than `-1`.
+    pub fn try_ncuts(self, ncuts: Idx) -> Result<OptionsBuilder, InvalidOptionError> {
+        self.try_set(NCuts(ncuts))
+    }
+
+    /// Sets the number of separators to compute at each level, see [`NSeps`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `nseps` is zero or less than `-1`.
+    pub fn nseps(self, nseps: Idx) -> OptionsBuilder {
+        self.set(NSeps(nseps))
+    }
+
+    /// Same as [`OptionsBuilder::nseps`], but fails instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `nseps` is zero or less than `-1`.
+    pub fn try_nseps(self, nseps: Idx) -> Result<OptionsBuilder, InvalidOptionError> {
+        self.try_set(NSeps(nseps))
+    }
+
+    /// Sets the number of refinement iterations, see [`NIter`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `niter` is zero or less than `-1`.
+    pub fn niter(self, niter: Idx) -> OptionsBuilder {
+        self.set(NIter(niter))
+    }
+
+    /// Same as [`OptionsBuilder::niter`], but fails instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `niter` is zero or less than `-1`.
+    pub fn try_niter(self, niter: Idx) -> Result<OptionsBuilder, InvalidOptionError> {
+        self.try_set(NIter(niter))
+    }
+
+    /// Sets the seed of the random number generator, see [`Seed`].
+    pub fn seed(self, seed: Idx) -> OptionsBuilder {
+        self.set(Seed(seed))
+    }
+
+    /// Sets whether to minimize the maximum degree of the subdomain graph,
+    /// see [`MinConn`].
+    pub fn minconn(self, minconn: bool) -> OptionsBuilder {
+        self.set(MinConn(minconn))
+    }
+
+    /// Sets whether to skip 2-hop matching, see [`No2Hop`].
+    pub fn no2hop(self, no2hop: bool) -> OptionsBuilder {
+        self.set(No2Hop(no2hop))
+    }
+
+    /// Sets whether to produce contiguous partitions, see [`Contig`].
+    pub fn contig(self, contig: bool) -> OptionsBuilder {
+        self.set(Contig(contig))
+    }
+
+    /// Sets whether to compress the graph, see [`Compress`].
+    pub fn compress(self, compress: bool) -> OptionsBuilder {
+        self.set(Compress(compress))
+    }
+
+    /// Sets whether to order connected components separately, see
+    /// [`CCOrder`].
+    pub fn ccorder(self, ccorder: bool) -> OptionsBuilder {
+        self.set(CCOrder(ccorder))
+    }
+
+    /// Sets the minimum degree of the vertices ordered last, see [`PFactor`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `pfactor` is less than `-1`.
+    pub fn pfactor(self, pfactor: Idx) -> OptionsBuilder {
+        self.set(PFactor(pfactor))
+    }
+
+    /// Same as [`OptionsBuilder::pfactor`], but fails instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `pfactor` is less than `-1`.
+    pub fn try_pfactor(self, pfactor: Idx) -> Result<OptionsBuilder, InvalidOptionError> {
+        self.try_set(PFactor(pfactor))
+    }
+
+    /// Sets the maximum allowed load imbalance, see [`UFactor`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `ufactor` is zero or less than `-1`.
+    pub fn ufactor(self, ufactor: Idx) -> OptionsBuilder {
+        self.set(UFactor(ufactor))
+    }
+
+    /// Same as [`OptionsBuilder::ufactor`], but fails instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `ufactor` is zero or less than `-1`.
+    pub fn try_ufactor(self, ufactor: Idx) -> Result<OptionsBuilder, InvalidOptionError> {
+        self.try_set(UFactor(ufactor))
+    }
+
+    /// Sets the amount of debugging information, see [`DbgLvl`].
+    pub fn dbglvl(self, dbglvl: DbgLvl) -> OptionsBuilder {
+        self.set(dbglvl)
+    }
+
+    /// Returns the options array, for [`crate::Graph::set_options`] and
+    /// [`crate::Mesh::set_options`].
+    pub fn build(&self) -> [Idx; NOPTIONS] {
+        self.options
+    }
+
+    /// Sets the options of `graph` that are set in `self`, and leaves its
+    /// other options unchanged.
+    pub fn apply_to(&self, graph: &mut crate::Graph<'_>) {
+        self.apply(&mut graph.options);
+    }
+
+    /// Sets the options of `mesh` that are set in `self`, and leaves its
+    /// other options unchanged.
+    pub fn apply_to_mesh(&self, mesh: &mut crate::Mesh<'_>) {
+        self.apply(&mut mesh.options);
+    }
+
+    /// Copies the options that are set in `self` into `options`.
+    fn apply(&self, options: &mut [Idx; NOPTIONS]) {
+        for (option, value) in options.iter_mut().zip(&self.options) {
+            if *value != -1 {
+                *option = *value;
+            }
+        }
+    }
+}
+
+/// Options of [`crate::Graph::part_kway`], i.e. `METIS_PartGraphKway`.
+///
+/// Only the options that METIS reads for k-way partitioning can be set, with
+/// the setters of [`OptionsBuilder`].  Use them with
+/// [`crate::Graph::part_kway_with`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::option::*;
+/// use metis::Graph;
+///
+/// // 0 - 1 - 2 - 3
+/// let xadj = &[0, 1, 3, 5, 6];
+/// let adjncy = &[1, 0, 2, 1, 3, 2];
+///
+/// let options = KwayOptions::new().objtype(ObjType::Vol).contig(true).seed(1);
+/// let mut part = [0; 4];
+/// Graph::new(1, 2, xadj, adjncy)?.part_kway_with(&options, &mut part)?;
+///
+/// // Ordering options cannot be expressed, nor can the refinement algorithm,
+/// // which is always greedy.
+/// let all = KwayOptions::new()
+///     .objtype(ObjType::Cut)
+///     .ctype(CType::Shem)
+///     .iptype(IpType::Grow)
+///     .no2hop(true)
+///     .ncuts(2)
+///     .niter(5)
+///     .ufactor(10)
+///     .minconn(true)
+///     .contig(true)
+///     .seed(3)
+///     .dbglvl(DbgLvl::default())
+///     .build();
+/// let inapplicable = [
+///     PType::INDEX,
+///     RType::INDEX,
+///     NSeps::INDEX,
+///     Compress::INDEX,
+///     CCOrder::INDEX,
+///     PFactor::INDEX,
+/// ];
+/// for index in inapplicable {
+///     assert_eq!(all[index], -1);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// metis::option::KwayOptions::new().pfactor(60);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KwayOptions(OptionsBuilder);
+
+impl KwayOptions {
+    /// Creates a set of k-way partitioning options, all unset.
+    pub fn new() -> KwayOptions {
+        KwayOptions(OptionsBuilder::new())
+    }
+
+    /// See [`OptionsBuilder::objtype`].
+    pub fn objtype(self, objtype: ObjType) -> KwayOptions {
+        KwayOptions(self.0.objtype(objtype))
+    }
+
+    /// See [`OptionsBuilder::ctype`].
+    pub fn ctype(self, ctype: CType) -> KwayOptions {
+        KwayOptions(self.0.ctype(ctype))
+    }
+
+    /// See [`OptionsBuilder::iptype`].
+    pub fn iptype(self, iptype: IpType) -> KwayOptions {
+        KwayOptions(self.0.iptype(iptype))
+    }
+
+    /// See [`OptionsBuilder::no2hop`].
+    pub fn no2hop(self, no2hop: bool) -> KwayOptions {
+        KwayOptions(self.0.no2hop(no2hop))
+    }
+
+    /// See [`OptionsBuilder::ncuts`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `ncuts` is illegal.
+    pub fn ncuts(self, ncuts: Idx) -> KwayOptions {
+        KwayOptions(self.0.ncuts(ncuts))
+    }
+
+    /// See [`OptionsBuilder::try_ncuts`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `ncuts` is illegal.
+    pub fn try_ncuts(self, ncuts: Idx) -> Result<KwayOptions, InvalidOptionError> {
+        self.0.try_ncuts(ncuts).map(KwayOptions)
+    }
+
+    /// See [`OptionsBuilder::niter`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `niter` is illegal.
+    pub fn niter(self, niter: Idx) -> KwayOptions {
+        KwayOptions(self.0.niter(niter))
+    }
+
+    /// See [`OptionsBuilder::try_niter`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `niter` is illegal.
+    pub fn try_niter(self, niter: Idx) -> Result<KwayOptions, InvalidOptionError> {
+        self.0.try_niter(niter).map(KwayOptions)
+    }
+
+    /// See [`OptionsBuilder::ufactor`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `ufactor` is illegal.
+    pub fn ufactor(self, ufactor: Idx) -> KwayOptions {
+        KwayOptions(self.0.ufactor(ufactor))
+    }
+
+    /// See [`OptionsBuilder::try_ufactor`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `ufactor` is illegal.
+    pub fn try_ufactor(self, ufactor: Idx) -> Result<KwayOptions, InvalidOptionError> {
+        self.0.try_ufactor(ufactor).map(KwayOptions)
+    }
+
+    /// See [`OptionsBuilder::minconn`].
+    pub fn minconn(self, minconn: bool) -> KwayOptions {
+        KwayOptions(self.0.minconn(minconn))
+    }
+
+    /// See [`OptionsBuilder::contig`].
+    pub fn contig(self, contig: bool) -> KwayOptions {
+        KwayOptions(self.0.contig(contig))
+    }
+
+    /// See [`OptionsBuilder::seed`].
+    pub fn seed(self, seed: Idx) -> KwayOptions {
+        KwayOptions(self.0.seed(seed))
+    }
+
+    /// See [`OptionsBuilder::dbglvl`].
+    pub fn dbglvl(self, dbglvl: DbgLvl) -> KwayOptions {
+        KwayOptions(self.0.dbglvl(dbglvl))
+    }
+
+    /// Returns the options array, where inapplicable options are unset.
+    pub fn build(&self) -> [Idx; NOPTIONS] {
+        self.0.build()
+    }
+
+    /// Copies the options that are set in `self` into `options`.
+    pub(crate) fn apply(&self, options: &mut [Idx; NOPTIONS]) {
+        self.0.apply(options);
+    }
+}
+
+/// Options of [`crate::Graph::part_recursive`], i.e.
+/// `METIS_PartGraphRecursive`.
+///
+/// Only the options that METIS reads for recursive bisection can be set, with
+/// the setters of [`OptionsBuilder`].  Use them with
+/// [`crate::Graph::part_recursive_with`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::option::*;
+/// use metis::Graph;
+///
+/// // 0 - 1 - 2 - 3
+/// let xadj = &[0, 1, 3, 5, 6];
+/// let adjncy = &[1, 0, 2, 1, 3, 2];
+///
+/// let options = RbOptions::new().ctype(CType::Rm).ncuts(3);
+/// let mut part = [0; 4];
+/// let edgecut = Graph::new(1, 2, xadj, adjncy)?.part_recursive_with(&options, &mut part)?;
+/// assert_eq!(edgecut, 1);
+///
+/// // The objective, connectivity and ordering options cannot be expressed,
+/// // nor can the refinement algorithm, which is always FM.
+/// let all = RbOptions::new()
+///     .ctype(CType::Shem)
+///     .iptype(IpType::Grow)
+///     .no2hop(true)
+///     .ncuts(2)
+///     .niter(5)
+///     .ufactor(10)
+///     .seed(3)
+///     .dbglvl(DbgLvl::default())
+///     .build();
+/// let inapplicable = [
+///     PType::INDEX,
+///     ObjType::INDEX,
+///     RType::INDEX,
+///     MinConn::INDEX,
+///     Contig::INDEX,
+///     NSeps::INDEX,
+///     Compress::INDEX,
+///     CCOrder::INDEX,
+///     PFactor::INDEX,
+/// ];
+/// for index in inapplicable {
+///     assert_eq!(all[index], -1);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// metis::option::RbOptions::new().minconn(true);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RbOptions(OptionsBuilder);
+
+impl RbOptions {
+    /// Creates a set of recursive bisection options, all unset.
+    pub fn new() -> RbOptions {
+        RbOptions(OptionsBuilder::new())
+    }
+
+    /// See [`OptionsBuilder::ctype`].
+    pub fn ctype(self, ctype: CType) -> RbOptions {
+        RbOptions(self.0.ctype(ctype))
+    }
+
+    /// See [`OptionsBuilder::iptype`].
+    pub fn iptype(self, iptype: IpType) -> RbOptions {
+        RbOptions(self.0.iptype(iptype))
+    }
+
+    /// See [`OptionsBuilder::no2hop`].
+    pub fn no2hop(self, no2hop: bool) -> RbOptions {
+        RbOptions(self.0.no2hop(no2hop))
+    }
+
+    /// See [`OptionsBuilder::ncuts`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `ncuts` is illegal.
+    pub fn ncuts(self, ncuts: Idx) -> RbOptions {
+        RbOptions(self.0.ncuts(ncuts))
+    }
+
+    /// See [`OptionsBuilder::try_ncuts`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `ncuts` is illegal.
+    pub fn try_ncuts(self, ncuts: Idx) -> Result<RbOptions, InvalidOptionError> {
+        self.0.try_ncuts(ncuts).map(RbOptions)
+    }
+
+    /// See [`OptionsBuilder::niter`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `niter` is illegal.
+    pub fn niter(self, niter: Idx) -> RbOptions {
+        RbOptions(self.0.niter(niter))
+    }
+
+    /// See [`OptionsBuilder::try_niter`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `niter` is illegal.
+    pub fn try_niter(self, niter: Idx) -> Result<RbOptions, InvalidOptionError> {
+        self.0.try_niter(niter).map(RbOptions)
+    }
+
+    /// See [`OptionsBuilder::ufactor`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `ufactor` is illegal.
+    pub fn ufactor(self, ufactor: Idx) -> RbOptions {
+        RbOptions(self.0.ufactor(ufactor))
+    }
+
+    /// See [`OptionsBuilder::try_ufactor`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `ufactor` is illegal.
+    pub fn try_ufactor(self, ufactor: Idx) -> Result<RbOptions, InvalidOptionError> {
+        self.0.try_ufactor(ufactor).map(RbOptions)
+    }
+
+    /// See [`OptionsBuilder::seed`].
+    pub fn seed(self, seed: Idx) -> RbOptions {
+        RbOptions(self.0.seed(seed))
+    }
+
+    /// See [`OptionsBuilder::dbglvl`].
+    pub fn dbglvl(self, dbglvl: DbgLvl) -> RbOptions {
+        RbOptions(self.0.dbglvl(dbglvl))
+    }
+
+    /// Returns the options array, where inapplicable options are unset.
+    pub fn build(&self) -> [Idx; NOPTIONS] {
+        self.0.build()
+    }
+
+    /// Copies the options that are set in `self` into `options`.
+    pub(crate) fn apply(&self, options: &mut [Idx; NOPTIONS]) {
+        self.0.apply(options);
+    }
+}
+
+/// Options of [`crate::Ordering::nested_dissection`], i.e. `METIS_NodeND`.
+///
+/// Only the options that METIS reads for orderings can be set, with the
+/// setters of [`OptionsBuilder`].  Use them with
+/// [`crate::Ordering::nested_dissection_with`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::option::*;
+/// use metis::Ordering;
+///
+/// // 0 - 1 - 2 - 3
+/// let xadj = &[0, 1, 3, 5, 6];
+/// let adjncy = &[1, 0, 2, 1, 3, 2];
+///
+/// let options = OrderOptions::new().nseps(2).ccorder(true);
+/// let p = Ordering::new(xadj, adjncy)?.nested_dissection_with(&options)?;
+/// assert!(p.is_valid());
+///
+/// // Partitioning options cannot be expressed.
+/// let all = OrderOptions::new()
+///     .ctype(CType::Shem)
+///     .iptype(IpType::Node)
+///     .rtype(RType::Sep1Sided)
+///     .no2hop(true)
+///     .nseps(2)
+///     .niter(5)
+///     .ufactor(10)
+///     .compress(true)
+///     .ccorder(true)
+///     .pfactor(60)
+///     .seed(3)
+///     .dbglvl(DbgLvl::default())
+///     .build();
+/// let inapplicable = [
+///     PType::INDEX,
+///     ObjType::INDEX,
+///     NCuts::INDEX,
+///     MinConn::INDEX,
+///     Contig::INDEX,
+/// ];
+/// for index in inapplicable {
+///     assert_eq!(all[index], -1);
+/// }
+///
+/// // Initial separators are derived from edge cuts or grown from nodes.
+/// assert!(OrderOptions::new().try_iptype(IpType::Edge).is_ok());
+/// assert!(OrderOptions::new().try_iptype(IpType::Grow).is_err());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// metis::option::OrderOptions::new().contig(true);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OrderOptions(OptionsBuilder);
+
+impl OrderOptions {
+    /// Creates a set of nested dissection orderings options, all unset.
+    pub fn new() -> OrderOptions {
+        OrderOptions(OptionsBuilder::new())
+    }
+
+    /// See [`OptionsBuilder::ctype`].
+    pub fn ctype(self, ctype: CType) -> OrderOptions {
+        OrderOptions(self.0.ctype(ctype))
+    }
+
+    /// See [`OptionsBuilder::iptype`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `iptype` is neither [`IpType::Edge`] nor
+    /// [`IpType::Node`], the only algorithms METIS accepts for orderings.
+    pub fn iptype(self, iptype: IpType) -> OrderOptions {
+        self.try_iptype(iptype)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as [`OrderOptions::iptype`], but fails instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `iptype` is neither [`IpType::Edge`]
+    /// nor [`IpType::Node`].
+    pub fn try_iptype(self, iptype: IpType) -> Result<OrderOptions, InvalidOptionError> {
+        if !matches!(iptype, IpType::Edge | IpType::Node) {
+            return Err(InvalidOptionError {
+                index: IpType::INDEX,
+                name: "IpType",
+                value: iptype.value(),
+                range: IpType::Edge.value()..=IpType::Node.value(),
+            });
+        }
+        Ok(OrderOptions(self.0.iptype(iptype)))
+    }
+
+    /// See [`OptionsBuilder::rtype`].
+    pub fn rtype(self, rtype: RType) -> OrderOptions {
+        OrderOptions(self.0.rtype(rtype))
+    }
+
+    /// See [`OptionsBuilder::no2hop`].
+    pub fn no2hop(self, no2hop: bool) -> OrderOptions {
+        OrderOptions(self.0.no2hop(no2hop))
+    }
+
+    /// See [`OptionsBuilder::nseps`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `nseps` is illegal.
+    pub fn nseps(self, nseps: Idx) -> OrderOptions {
+        OrderOptions(self.0.nseps(nseps))
+    }
+
+    /// See [`OptionsBuilder::try_nseps`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `nseps` is illegal.
+    pub fn try_nseps(self, nseps: Idx) -> Result<OrderOptions, InvalidOptionError> {
+        self.0.try_nseps(nseps).map(OrderOptions)
+    }
+
+    /// See [`OptionsBuilder::niter`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `niter` is illegal.
+    pub fn niter(self, niter: Idx) -> OrderOptions {
+        OrderOptions(self.0.niter(niter))
+    }
+
+    /// See [`OptionsBuilder::try_niter`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `niter` is illegal.
+    pub fn try_niter(self, niter: Idx) -> Result<OrderOptions, InvalidOptionError> {
+        self.0.try_niter(niter).map(OrderOptions)
+    }
+
+    /// See [`OptionsBuilder::ufactor`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `ufactor` is illegal.
+    pub fn ufactor(self, ufactor: Idx) -> OrderOptions {
+        OrderOptions(self.0.ufactor(ufactor))
+    }
+
+    /// See [`OptionsBuilder::try_ufactor`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `ufactor` is illegal.
+    pub fn try_ufactor(self, ufactor: Idx) -> Result<OrderOptions, InvalidOptionError> {
+        self.0.try_ufactor(ufactor).map(OrderOptions)
+    }
+
+    /// See [`OptionsBuilder::compress`].
+    pub fn compress(self, compress: bool) -> OrderOptions {
+        OrderOptions(self.0.compress(compress))
+    }
+
+    /// See [`OptionsBuilder::ccorder`].
+    pub fn ccorder(self, ccorder: bool) -> OrderOptions {
+        OrderOptions(self.0.ccorder(ccorder))
+    }
+
+    /// See [`OptionsBuilder::pfactor`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `pfactor` is illegal.
+    pub fn pfactor(self, pfactor: Idx) -> OrderOptions {
+        OrderOptions(self.0.pfactor(pfactor))
+    }
+
+    /// See [`OptionsBuilder::try_pfactor`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `pfactor` is illegal.
+    pub fn try_pfactor(self, pfactor: Idx) -> Result<OrderOptions, InvalidOptionError> {
+        self.0.try_pfactor(pfactor).map(OrderOptions)
+    }
+
+    /// See [`OptionsBuilder::seed`].
+    pub fn seed(self, seed: Idx) -> OrderOptions {
+        OrderOptions(self.0.seed(seed))
+    }
+
+    /// See [`OptionsBuilder::dbglvl`].
+    pub fn dbglvl(self, dbglvl: DbgLvl) -> OrderOptions {
+        OrderOptions(self.0.dbglvl(dbglvl))
+    }
+
+    /// Returns the options array, where inapplicable options are unset.
+    pub fn build(&self) -> [Idx; NOPTIONS] {
+        self.0.build()
+    }
+
+    /// Copies the options that are set in `self` into `options`.
+    pub(crate) fn apply(&self, options: &mut [Idx; NOPTIONS]) {
+        self.0.apply(options);
+    }
+}
+
+/// The legal values of each option, as `(index, name, range)`.
+///
+/// Values outside these ranges are not always caught by METIS, which then
+/// aborts the process instead of returning an error.  `-1` is always legal and
+/// stands for the default value.  Some values are legal for some routines
+/// only (e.g. [`RType::Sep1Sided`] for orderings); METIS reports those as
+/// input errors itself.
+const LEGAL_VALUES: [(usize, &str, RangeInclusive<Idx>); 16] = [
+    (PType::INDEX, "PType", 0..=1),
+    (ObjType::INDEX, "ObjType", 0..=2),
+    (CType::INDEX, "CType", 0..=1),
+    (IpType::INDEX, "IpType", 0..=4),
+    (RType::INDEX, "RType", 0..=3),
+    (DbgLvl::INDEX, "DbgLvl", 0..=(511 | 2048)),
+    (NIter::INDEX, "NIter", 1..=Idx::MAX),
+    (NCuts::INDEX, "NCuts", 1..=Idx::MAX),
+    (Seed::INDEX, "Seed", Idx::MIN..=Idx::MAX),
+    (MinConn::INDEX, "MinConn", 0..=1),
+    (Contig::INDEX, "Contig", 0..=1),
+    (Compress::INDEX, "Compress", 0..=1),
+    (CCOrder::INDEX, "CCOrder", 0..=1),
+    (PFactor::INDEX, "PFactor", 0..=Idx::MAX),
+    (NSeps::INDEX, "NSeps", 1..=Idx::MAX),
+    (UFactor::INDEX, "UFactor", 1..=Idx::MAX),
+];
+
+/// How the values of an option are named.
+enum Values {
+    /// Integers.
+    Int,
+
+    /// Booleans, zero or one.
+    Bool,
+
+    /// Enumerations, as `(value, variant, command-line name)`.
+    Variants(&'static [(Idx, &'static str, &'static str)]),
+
+    /// Bit sets, as `(bit, field)`.  They are integers on the command line.
+    Flags(&'static [(Idx, &'static str)]),
+}
+
+/// The names of each option and of its values, as `(index, type, command-line
+/// name, values)`.
+///
+/// The type is the one of this module, and the command-line name the one of
+/// `gpmetis` and `ndmetis`, if any.
+const NAMES: [(usize, &str, Option<&str>, Values); 18] = [
+    (
+        PType::INDEX,
+        "PType",
+        Some("ptype"),
+        Values::Variants(&[
+            (m::mptype_et_METIS_PTYPE_RB as Idx, "Rb", "rb"),
+            (m::mptype_et_METIS_PTYPE_KWAY as Idx, "Kway", "kway"),
+        ]),
+    ),
+    (
+        ObjType::INDEX,
+        "ObjType",
+        Some("objtype"),
+        Values::Variants(&[
+            (m::mobjtype_et_METIS_OBJTYPE_CUT as Idx, "Cut", "cut"),
+            (m::mobjtype_et_METIS_OBJTYPE_VOL as Idx, "Vol", "vol"),
+        ]),
+    ),
+    (
+        CType::INDEX,
+        "CType",
+        Some("ctype"),
+        Values::Variants(&[
+            (m::mctype_et_METIS_CTYPE_RM as Idx, "Rm", "rm"),
+            (m::mctype_et_METIS_CTYPE_SHEM as Idx, "Shem", "shem"),
+        ]),
+    ),
+    (
+        IpType::INDEX,
+        "IpType",
+        Some("iptype"),
+        Values::Variants(&[
+            (m::miptype_et_METIS_IPTYPE_GROW as Idx, "Grow", "grow"),
+            (m::miptype_et_METIS_IPTYPE_RANDOM as Idx, "Random", "random"),
+            (m::miptype_et_METIS_IPTYPE_EDGE as Idx, "Edge", "edge"),
+            (m::miptype_et_METIS_IPTYPE_NODE as Idx, "Node", "node"),
+        ]),
+    ),
+    (
+        RType::INDEX,
+        "RType",
+        Some("rtype"),
+        Values::Variants(&[
+            (m::mrtype_et_METIS_RTYPE_FM as Idx, "Fm", "fm"),
+            (m::mrtype_et_METIS_RTYPE_GREEDY as Idx, "Greedy", "greedy"),
+            (
+                m::mrtype_et_METIS_RTYPE_SEP2SIDED as Idx,
+                "Sep2Sided",
+                "sep2sided",
+            ),
+            (
+                m::mrtype_et_METIS_RTYPE_SEP1SIDED as Idx,
+                "Sep1Sided",
+                "sep1sided",
+            ),
+        ]),
+    ),
+    (
+        DbgLvl::INDEX,
+        "DbgLvl",
+        Some("dbglvl"),
+        Values::Flags(&[
+            (1, "info"),
+            (2, "time"),
+            (4, "coarsen"),
+            (8, "refine"),
+            (16, "ipart"),
+            (32, "move_info"),
+            (64, "sep_info"),
+            (128, "conn_info"),
+            (256, "contig_info"),
+        ]),
+    ),
+    (NIter::INDEX, "NIter", Some("niter"), Values::Int),
+    (NCuts::INDEX, "NCuts", Some("ncuts"), Values::Int),
+    (Seed::INDEX, "Seed", Some("seed"), Values::Int),
+    (MinConn::INDEX, "MinConn", Some("minconn"), Values::Bool),
+    (No2Hop::INDEX, "No2Hop", Some("no2hop"), Values::Bool),
+    (Contig::INDEX, "Contig", Some("contig"), Values::Bool),
+    (Compress::INDEX, "Compress", Some("compress"), Values::Bool),
+    (CCOrder::INDEX, "CCOrder", Some("ccorder"), Values::Bool),
+    (PFactor::INDEX, "PFactor", Some("pfactor"), Values::Int),
+    (NSeps::INDEX, "NSeps", Some("nseps"), Values::Int),
+    (UFactor::INDEX, "UFactor", Some("ufactor"), Values::Int),
+    (
+        Numbering::INDEX,
+        "Numbering",
+        None,
+        Values::Variants(&[(0, "C", "c"), (1, "Fortran", "fortran")]),
+    ),
+];
+
+/// Error raised when an option of the array given to
+/// [`crate::Graph::set_options`] or [`crate::Mesh::set_options`] holds an
+/// illegal value.
+///
+/// It is the source of the [`crate::Error::Input`] returned by partitioning
+/// routines.
+///
+/// # Example
+///
+/// ```rust
+/// # use metis::Graph;
+/// use metis::option::{InvalidOptionError, Opt as _};
+///
+/// let xadj = &[0, 1, 2];
+/// let adjncy = &[1, 0];
+/// let mut options = metis::option::defaults();
+/// options[metis::option::PType::INDEX] = 7;
+///
+/// let mut part = [0; 2];
+/// let err = Graph::new(1, 2, xadj, adjncy)
+///     .unwrap()
+///     .set_options(&options)
+///     .part_kway(&mut part)
+///     .unwrap_err();
+/// let err = std::error::Error::source(&err)
+///     .and_then(|source| source.downcast_ref::<InvalidOptionError>())
+///     .unwrap();
+/// assert_eq!(err.name(), "PType");
+/// assert_eq!(err.value(), 7);
+/// assert_eq!(err.to_string(), "option PType is 7, expected -1 or within 0..=1");
+///
+/// // Every routine checks every option.
+/// use metis::option::*;
+/// let illegal = [
+///     (PType::INDEX, 2),
+///     (ObjType::INDEX, 3),
+///     (CType::INDEX, -2),
+///     (IpType::INDEX, 5),
+///     (RType::INDEX, 4),
+///     (DbgLvl::INDEX, 4096),
+///     (NIter::INDEX, 0),
+///     (NCuts::INDEX, 0),
+///     (MinConn::INDEX, 2),
+///     (Contig::INDEX, 2),
+///     (Compress::INDEX, 2),
+///     (CCOrder::INDEX, 2),
+///     (PFactor::INDEX, -2),
+///     (NSeps::INDEX, 0),
+///     (UFactor::INDEX, 0),
+/// ];
+/// let (eptr, eind) = (&[0, 3, 6], &[0, 1, 2, 1, 2, 3]);
+/// for (index, value) in illegal {
+///     let mut options = metis::option::defaults();
+///     options[index] = value;
+///     let is_illegal = |res: Result<_, metis::Error>| {
+///         let err = res.unwrap_err();
+///         let err = std::error::Error::source(&err).unwrap();
+///         let err = err.downcast_ref::<InvalidOptionError>().unwrap();
+///         err.index() == index && err.value() == value
+///     };
+///     let mut graph = Graph::new(1, 2, xadj, adjncy).unwrap().set_options(&options);
+///     assert!(is_illegal(graph.part_recursive(&mut part)));
+///     assert!(is_illegal(graph.part_kway(&mut part)));
+///     let mesh = || metis::Mesh::new(2, eptr, eind).unwrap().set_options(&options);
+///     let (mut epart, mut npart) = ([0; 2], [0; 4]);
+///     assert!(is_illegal(mesh().part_dual(&mut epart, &mut npart)));
+///     assert!(is_illegal(mesh().part_nodal(&mut epart, &mut npart)));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidOptionError {
+    index: usize,
+    name: &'static str,
+    value: Idx,
+    range: RangeInclusive<Idx>,
+}
+
+impl InvalidOptionError {
+    /// The index of the option in the options array.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The name of the option type, e.g. `"PType"`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The illegal value.
+    pub fn value(&self) -> Idx {
+        self.value
+    }
+
+    /// The legal values, besides `-1`.
+    pub fn range(&self) -> RangeInclusive<Idx> {
+        self.range.clone()
+    }
+}
+
+impl fmt::Display for InvalidOptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "option {} is {}, expected -1 or within {}..={}",
+            self.name,
+            self.value,
+            self.range.start(),
+            self.range.end(),
+        )
+    }
+}
+
+impl std::error::Error for InvalidOptionError {}
+
+/// Checks that every known option is either unset or legal.
+pub(crate) fn check(options: &[Idx; NOPTIONS]) -> Result<(), InvalidOptionError> {
+    for (index, _, _) in &LEGAL_VALUES {
+        legal(*index, options[*index])?;
+    }
+    Ok(())
+}
+
+/// Checks that `value` is either `-1` or legal for the option at `index`.
+fn legal(index: usize, value: Idx) -> Result<(), InvalidOptionError> {
+    let legal_values = LEGAL_VALUES.iter().find(|(i, _, _)| *i == index);
+    if let Some((index, name, range)) = legal_values {
+        if value != -1 && !range.contains(&value) {
+            return Err(InvalidOptionError {
+                index: *index,
+                name,
+                value,
+                range: range.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Describes an options array, for debugging.
+///
+/// Options are listed in the order of the array, with the names of their types
+/// in this module, and the names of their values when known.  Unset options
+/// (`-1`) are left out.  Unknown options are shown by their index, and values
+/// that have no name are shown as is.
+///
+/// # Example
+///
+/// ```rust
+/// use metis::option::{self, Opt as _};
+///
+/// assert_eq!(option::describe(&option::defaults()), "(all default)");
+///
+/// let mut options = option::defaults();
+/// options[option::PType::INDEX] = option::PType::Kway.value();
+/// options[option::CType::INDEX] = option::CType::Shem.value();
+/// options[option::Seed::INDEX] = 42;
+/// options[option::NIter::INDEX] = 10;
+/// assert_eq!(
+///     option::describe(&options),
+///     "PType=Kway, CType=Shem, NIter=10, Seed=42 (rest default)",
+/// );
+///
+/// // Every option set, some to values that have no name.
+/// use option::*;
+/// let mut options = option::defaults();
+/// options[PType::INDEX] = PType::Rb.value();
+/// options[ObjType::INDEX] = 2;
+/// options[CType::INDEX] = CType::Rm.value();
+/// options[IpType::INDEX] = IpType::Edge.value();
+/// options[RType::INDEX] = RType::Sep1Sided.value();
+/// options[DbgLvl::INDEX] = 1 | 8 | 2048;
+/// options[NIter::INDEX] = NIter(20).value();
+/// options[NCuts::INDEX] = NCuts(3).value();
+/// options[Seed::INDEX] = Seed(0).value();
+/// options[MinConn::INDEX] = MinConn(true).value();
+/// options[No2Hop::INDEX] = No2Hop(false).value();
+/// options[Contig::INDEX] = 3;
+/// options[Compress::INDEX] = Compress(true).value();
+/// options[CCOrder::INDEX] = CCOrder(false).value();
+/// options[PFactor::INDEX] = PFactor(60).value();
+/// options[NSeps::INDEX] = NSeps(2).value();
+/// options[UFactor::INDEX] = UFactor(30).value();
+/// assert_eq!(
+///     option::describe(&options),
+///     "PType=Rb, ObjType=2, CType=Rm, IpType=Edge, RType=Sep1Sided, \
+///      DbgLvl=info|refine|2048, NIter=20, NCuts=3, Seed=0, MinConn=true, \
+///      Contig=3, Compress=true, CCOrder=false, PFactor=60, NSeps=2, \
+///      UFactor=30, No2Hop=false (rest default)",
+/// );
+///
+/// // Options without a type in this module.
+/// let description = option::describe(&[0; metis::NOPTIONS]);
+/// let last = format!(", options[{}]=0", metis::NOPTIONS - 1);
+/// assert!(description.ends_with(&last));
+/// assert!(!description.contains("default"));
+/// ```
+pub fn describe(options: &[Idx; NOPTIONS]) -> String {
+    let mut described = Vec::new();
+    for (index, value) in options.iter().enumerate() {
+        let value = *value;
+        if value == -1 {
+            continue;
+        }
+        let Some((_, name, _, values)) = NAMES.iter().find(|(i, _, _, _)| *i == index) else {
+            described.push(format!("options[{index}]={value}"));
+            continue;
+        };
+        let value = match values {
+            Values::Bool if value == 0 => "false".to_string(),
+            Values::Bool if value == 1 => "true".to_string(),
+            Values::Variants(variants) => match variants.iter().find(|(v, _, _)| *v == value) {
+                Some((_, variant, _)) => variant.to_string(),
+                None => value.to_string(),
+            },
+            Values::Flags(flags) if value > 0 => {
+                let mut names: Vec<String> = Vec::new();
+                let mut rest = value;
+                for (bit, field) in *flags {
+                    if value & bit != 0 {
+                        names.push(field.to_string());
+                        rest &= !bit;
+                    }
+                }
+                if rest != 0 {
+                    names.push(rest.to_string());
+                }
+                names.join("|")
+            }
+            _ => value.to_string(),
+        };
+        described.push(format!("{name}={value}"));
+    }
+    if described.is_empty() {
+        return "(all default)".to_string();
+    }
+    let mut description = described.join(", ");
+    if options.contains(&-1) {
+        description.push_str(" (rest default)");
+    }
+    description
+}
+
+/// An option and its value, parsed from the spelling of the command-line
+/// programs of METIS, e.g. `ctype=shem` or `-niter=20`.
+///
+/// Names and enumeration values are those of `gpmetis` and `ndmetis`, in
+/// lower case.  Boolean options are set by their name alone, or with `=0` and
+/// `=1`, and `nocompress` is the opposite of `compress`.  Values are checked
+/// like the partitioning routines do, and `-1` leaves any option to its
+/// default.  Settings are written back in the same format, see
+/// [`apply_settings`] for an example.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Setting {
+    index: usize,
+    value: Idx,
+}
+
+impl Setting {
+    /// The index of the option in the options array.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The value of the option, in METIS' format.
+    pub fn value(&self) -> Idx {
+        self.value
+    }
+
+    /// The name of the option, e.g. `"ctype"`.
+    ///
+    /// Options that the command-line programs do not set are named after
+    /// their type in this module.
+    pub fn name(&self) -> &'static str {
+        NAMES
+            .iter()
+            .find(|(i, _, _, _)| *i == self.index)
+            .map_or("", |(_, ty, name, _)| name.unwrap_or(ty))
+    }
+}
+
+impl<O: Opt> From<O> for Setting {
+    fn from(option: O) -> Setting {
+        Setting {
+            index: O::INDEX,
+            value: option.value(),
+        }
+    }
+}
+
+impl fmt::Display for Setting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, values) = match cli_name(self.index) {
+            Some(name) => name,
+            None => return write!(f, "options[{}]={}", self.index, self.value),
+        };
+        if let Values::Variants(variants) = values {
+            if let Some((_, _, variant)) = variants.iter().find(|(v, _, _)| *v == self.value) {
+                return write!(f, "{name}={variant}");
+            }
+        }
+        write!(f, "{name}={}", self.value)
+    }
+}
+
+impl FromStr for Setting {
+    type Err = ParseOptionError;
+
+    fn from_str(s: &str) -> Result<Setting, ParseOptionError> {
+        let s = s.strip_prefix('-').unwrap_or(s);
+        let (name, value) = match s.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (s, None),
+        };
+        let (name, inverted) = match name {
+            "nocompress" => ("compress", true),
+            name => (name, false),
+        };
+        let (index, name, values) = NAMES
+            .iter()
+            .find_map(|(index, _, n, values)| match n {
+                Some(n) if *n == name => Some((index, *n, values)),
+                _ => None,
+            })
+            .ok_or_else(|| ParseOptionError::UnknownOption {
+                name: name.to_string(),
+            })?;
+        let invalid = |expected: String| ParseOptionError::InvalidValue {
+            option: if inverted { "nocompress" } else { name },
+            value: value.unwrap_or("").to_string(),
+            expected,
+        };
+        let parsed = match (values, value) {
+            (_, Some("-1")) => -1,
+            (Values::Bool, None) if inverted => 0,
+            (Values::Bool, None) => 1,
+            (Values::Bool, Some("0")) => inverted as Idx,
+            (Values::Bool, Some("1")) => !inverted as Idx,
+            (Values::Bool, Some(_)) => return Err(invalid("nothing, -1, 0 or 1".to_string())),
+            (Values::Variants(variants), Some(value)) => {
+                match variants.iter().find(|(_, _, v)| *v == value) {
+                    Some((v, _, _)) => *v,
+                    None => {
+                        let names: Vec<&str> = variants.iter().map(|(_, _, v)| *v).collect();
+                        return Err(invalid(format!("-1 or one of {}", names.join(", "))));
+                    }
+                }
+            }
+            (Values::Variants(variants), None) => {
+                let names: Vec<&str> = variants.iter().map(|(_, _, v)| *v).collect();
+                return Err(invalid(format!("-1 or one of {}", names.join(", "))));
+            }
+            (Values::Int | Values::Flags(_), value) => {
+                let range = LEGAL_VALUES
+                    .iter()
+                    .find(|(i, _, _)| i == index)
+                    .map_or(Idx::MIN..=Idx::MAX, |(_, _, range)| range.clone());
+                let expected = || {
+                    format!(
+                        "-1 or an integer within {}..={}",
+                        range.start(),
+                        range.end()
+                    )
+                };
+                let value: Idx = value
+                    .and_then(|value| value.parse().ok())
+                    .ok_or_else(|| invalid(expected()))?;
+                if legal(*index, value).is_err() {
+                    return Err(invalid(expected()));
+                }
+                value
+            }
+        };
+        Ok(Setting {
+            index: *index,
+            value: parsed,
+        })
+    }
+}
+
+/// Looks up the command-line name of the option at `index`.
+fn cli_name(index: usize) -> Option<(&'static str, &'static Values)> {
+    NAMES
+        .iter()
+        .find(|(i, _, _, _)| *i == index)
+        .and_then(|(_, _, name, values)| name.map(|name| (name, values)))
+}
+
+/// Parses a [`Setting`], e.g. `"ctype=shem"`.
+///
+/// Same as `s.parse::<Setting>()`.
+///
+/// # Errors
+///
+/// This function returns an error if the option is unknown, or if its value
+/// is not legal.
+pub fn parse(s: &str) -> Result<Setting, ParseOptionError> {
+    s.parse()
+}
+
+/// Sets the options of `settings` in `options`.
+///
+/// # Errors
+///
+/// This function returns [`ParseOptionError::Duplicate`] if an option appears
+/// more than once in `settings`, in which case `options` is left unchanged.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::option::ParseOptionError> {
+/// use metis::option::{self, Opt as _, Setting};
+///
+/// let args = ["-ctype=shem", "-niter=20", "-ufactor=30", "-contig"];
+/// let settings = args
+///     .iter()
+///     .map(|arg| arg.parse())
+///     .collect::<Result<Vec<Setting>, _>>()?;
+/// let mut options = option::defaults();
+/// option::apply_settings(&mut options, &settings)?;
+/// assert_eq!(options[option::CType::INDEX], option::CType::Shem.value());
+/// assert_eq!(options[option::NIter::INDEX], 20);
+/// assert_eq!(options[option::UFactor::INDEX], 30);
+/// assert_eq!(options[option::Contig::INDEX], 1);
+///
+/// // Errors name the option and its legal values.
+/// let err = option::parse("ctype=hem").unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     r#"invalid value "hem" for option ctype, expected -1 or one of rm, shem"#,
+/// );
+/// let err = option::parse("niter=0").unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     format!(
+///         r#"invalid value "0" for option niter, expected -1 or an integer within 1..={}"#,
+///         metis::Idx::MAX,
+///     ),
+/// );
+/// let err = option::parse("contig=yes").unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     r#"invalid value "yes" for option contig, expected nothing, -1, 0 or 1"#,
+/// );
+/// let err = option::parse("nocompress=2").unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     r#"invalid value "2" for option nocompress, expected nothing, -1, 0 or 1"#,
+/// );
+/// let err = option::parse("nparts=2").unwrap_err();
+/// assert!(err.to_string().starts_with(r#"unknown option "nparts", expected one of ptype, "#));
+///
+/// let settings = [option::parse("seed=1")?, option::parse("ncuts=2")?, option::parse("seed=2")?];
+/// let err = option::apply_settings(&mut options, &settings).unwrap_err();
+/// assert_eq!(err.to_string(), "option seed is set more than once");
+/// assert_eq!(options[option::Seed::INDEX], -1);
+///
+/// // Every option value is written back as it is parsed.
+/// use option::*;
+/// let all = [
+///     (Setting::from(PType::Rb), "ptype=rb"),
+///     (Setting::from(PType::Kway), "ptype=kway"),
+///     (Setting::from(ObjType::Cut), "objtype=cut"),
+///     (Setting::from(ObjType::Vol), "objtype=vol"),
+///     (Setting::from(CType::Rm), "ctype=rm"),
+///     (Setting::from(CType::Shem), "ctype=shem"),
+///     (Setting::from(IpType::Grow), "iptype=grow"),
+///     (Setting::from(IpType::Random), "iptype=random"),
+///     (Setting::from(IpType::Edge), "iptype=edge"),
+///     (Setting::from(IpType::Node), "iptype=node"),
+///     (Setting::from(RType::Fm), "rtype=fm"),
+///     (Setting::from(RType::Greedy), "rtype=greedy"),
+///     (Setting::from(RType::Sep2Sided), "rtype=sep2sided"),
+///     (Setting::from(RType::Sep1Sided), "rtype=sep1sided"),
+///     (Setting::from(DbgLvl { info: true, refine: true, ..DbgLvl::default() }), "dbglvl=9"),
+///     (Setting::from(NIter(20)), "niter=20"),
+///     (Setting::from(NCuts(3)), "ncuts=3"),
+///     (Setting::from(Seed(-42)), "seed=-42"),
+///     (Setting::from(MinConn(true)), "minconn=1"),
+///     (Setting::from(MinConn(false)), "minconn=0"),
+///     (Setting::from(No2Hop(true)), "no2hop=1"),
+///     (Setting::from(No2Hop(false)), "no2hop=0"),
+///     (Setting::from(Contig(true)), "contig=1"),
+///     (Setting::from(Contig(false)), "contig=0"),
+///     (Setting::from(Compress(true)), "compress=1"),
+///     (Setting::from(Compress(false)), "compress=0"),
+///     (Setting::from(CCOrder(true)), "ccorder=1"),
+///     (Setting::from(CCOrder(false)), "ccorder=0"),
+///     (Setting::from(PFactor(60)), "pfactor=60"),
+///     (Setting::from(NSeps(2)), "nseps=2"),
+///     (Setting::from(UFactor(-1)), "ufactor=-1"),
+/// ];
+/// for (setting, s) in all {
+///     assert_eq!(setting.to_string(), s);
+///     assert_eq!(option::parse(s)?, setting);
+///     assert_eq!(option::parse(&format!("-{s}"))?, setting);
+/// }
+///
+/// // So is -1, which leaves any option to its default.
+/// let unset = [
+///     (PType::INDEX, "ptype=-1"),
+///     (ObjType::INDEX, "objtype=-1"),
+///     (CType::INDEX, "ctype=-1"),
+///     (IpType::INDEX, "iptype=-1"),
+///     (RType::INDEX, "rtype=-1"),
+///     (DbgLvl::INDEX, "dbglvl=-1"),
+///     (MinConn::INDEX, "minconn=-1"),
+///     (No2Hop::INDEX, "no2hop=-1"),
+///     (Contig::INDEX, "contig=-1"),
+///     (Compress::INDEX, "compress=-1"),
+///     (CCOrder::INDEX, "ccorder=-1"),
+/// ];
+/// for (index, s) in unset {
+///     let setting = option::parse(s)?;
+///     assert_eq!((setting.index(), setting.value()), (index, -1));
+///     assert_eq!(setting.to_string(), s);
+///     assert_eq!(option::parse(&format!("-{s}"))?, setting);
+/// }
+///
+/// // ndmetis spells compress=0 as nocompress.
+/// assert_eq!(option::parse("-nocompress")?, Setting::from(Compress(false)));
+/// assert_eq!(option::parse("nocompress=0")?, Setting::from(Compress(true)));
+/// assert_eq!(option::parse("nocompress=-1")?.value(), -1);
+/// # Ok(())
+/// # }
+/// ```
+pub fn apply_settings(
+    options: &mut [Idx; NOPTIONS],
+    settings: &[Setting],
+) -> Result<(), ParseOptionError> {
+    for (i, setting) in settings.iter().enumerate() {
+        if settings[..i].iter().any(|s| s.index == setting.index) {
+            return Err(ParseOptionError::Duplicate {
+                option: setting.name(),
+            });
+        }
+    }
+    for setting in settings {
+        options[setting.index] = setting.value;
+    }
+    Ok(())
+}
+
+/// Error raised when parsing or applying [`Setting`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseOptionError {
+    /// The option name is not known.
+    UnknownOption {
+        /// The name, without leading dash.
+        name: String,
+    },
+
+    /// The value is not legal for the option.
+    InvalidValue {
+        /// The name of the option.
+        option: &'static str,
+
+        /// The value, empty if missing.
+        value: String,
+
+        /// A description of the legal values.
+        expected: String,
+    },
+
+    /// The option is set more than once.
+    Duplicate {
+        /// The name of the option.
+        option: &'static str,
+    },
+}
+
+impl fmt::Display for ParseOptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseOptionError::UnknownOption { name } => {
+                let names: Vec<&str> = NAMES.iter().filter_map(|(_, _, name, _)| *name).collect();
+                write!(
+                    f,
+                    "unknown option {name:?}, expected one of {}",
+                    names.join(", "),
+                )
+            }
+            ParseOptionError::InvalidValue {
+                option,
+                value,
+                expected,
+            } => write!(
+                f,
+                "invalid value {value:?} for option {option}, expected {expected}",
+            ),
+            ParseOptionError::Duplicate { option } => {
+                write!(f, "option {option} is set more than once")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseOptionError {}
@@ -0,0 +1,501 @@
+//! Fill-reducing orderings of sparse matrices.
+//!
+//! See [`Ordering`] for a usage example.
+
+use crate::check_graph_structure;
+use crate::check_no_self_loops;
+use crate::lock_metis;
+use crate::m;
+use crate::option;
+use crate::option::Opt as _;
+use crate::slice_to_mut_ptr;
+use crate::BuildInfo;
+use crate::ErrorCode as _;
+use crate::Idx;
+use crate::NewGraphError;
+use crate::Result;
+use crate::NOPTIONS;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ptr;
+use std::result::Result as StdResult;
+
+/// Builder structure to set up a fill-reducing ordering computation.
+///
+/// This structure holds the required arguments for METIS to compute a
+/// nested-dissection ordering of the vertices of a graph, typically the
+/// adjacency graph of a sparse symmetric matrix.  It also offers methods to
+/// easily set any optional argument.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// # use metis::Ordering;
+/// // A 3x3 grid graph:
+/// // 0 - 1 - 2
+/// // |   |   |
+/// // 3 - 4 - 5
+/// // |   |   |
+/// // 6 - 7 - 8
+/// let xadj = &[0, 2, 5, 7, 10, 14, 17, 19, 22, 24];
+/// let adjncy = &[
+///     1, 3,
+///     0, 2, 4,
+///     1, 5,
+///     0, 4, 6,
+///     1, 3, 5, 7,
+///     2, 4, 8,
+///     3, 7,
+///     4, 6, 8,
+///     5, 7,
+/// ];
+///
+/// let p = Ordering::new(xadj, adjncy)?.nested_dissection()?;
+///
+/// // `perm` and `iperm` are inverse permutations of each other.
+/// let (perm, iperm) = (p.perm(), p.iperm());
+/// for i in 0..9 {
+///     assert_eq!(perm[iperm[i] as usize], i as metis::Idx);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(PartialEq)]
+pub struct Ordering<'a> {
+    /// The adjency structure of the graph (part 1).
+    xadj: &'a [Idx],
+
+    /// The adjency structure of the graph (part 2).
+    ///
+    /// Required size: xadj.last()
+    adjncy: &'a [Idx],
+
+    /// The weights of the vertices.
+    ///
+    /// Required size: xadj.len()-1
+    vwgt: Option<&'a [Idx]>,
+
+    /// Fine-tuning parameters.
+    options: [Idx; NOPTIONS],
+}
+
+impl fmt::Debug for Ordering<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ordering")
+            .field("xadj", &self.xadj)
+            .field("adjncy", &self.adjncy)
+            .field("vwgt", &self.vwgt)
+            .field("options", &self.options)
+            .field("metis", &BuildInfo)
+            .finish()
+    }
+}
+
+impl<'a> Ordering<'a> {
+    /// Creates a new [`Ordering`] object.
+    ///
+    /// # Input format
+    ///
+    /// `xadj` and `adjncy` are the CSR encoding of the adjacency matrix that
+    /// represents the graph, as described in [`crate::Graph::new`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the graph does not uphold the
+    /// invariants listed in the "Errors" section of [`crate::Graph::new`].
+    ///
+    /// # Mutability
+    ///
+    /// [`Ordering::nested_dissection`] may mutate the contents of `xadj` and
+    /// `adjncy`, but should revert all changes before returning.
+    pub fn new(xadj: &'a [Idx], adjncy: &'a [Idx]) -> StdResult<Ordering<'a>, NewGraphError> {
+        check_graph_structure(xadj, adjncy)?;
+        check_no_self_loops(xadj, adjncy, option::Numbering::C)?;
+        Ok(unsafe { Ordering::new_unchecked(xadj, adjncy) })
+    }
+
+    /// Creates a new [`Ordering`] object (unchecked version).
+    ///
+    /// # Safety
+    ///
+    /// This function still does some checks listed in "Panics" below. However,
+    /// the caller is reponsible for upholding all invariants listed in the
+    /// "Errors" section of [`crate::Graph::new`]. Otherwise, the behavior of
+    /// this function is undefined.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if:
+    /// - any of the arrays have a length that cannot be held by an [`Idx`], or
+    /// - `xadj` is empty, or
+    /// - the length of `adjncy` is different from the last element of `xadj`.
+    pub unsafe fn new_unchecked(xadj: &'a [Idx], adjncy: &'a [Idx]) -> Ordering<'a> {
+        let _ = Idx::try_from(xadj.len()).expect("xadj array larger than Idx::MAX");
+        assert_ne!(xadj.len(), 0);
+        let adjncy_len = Idx::try_from(adjncy.len()).expect("adjncy array larger than Idx::MAX");
+        assert_eq!(adjncy_len, *xadj.last().unwrap());
+
+        Ordering {
+            xadj,
+            adjncy,
+            vwgt: None,
+            options: option::initial(),
+        }
+    }
+
+    /// Sets the weights of the vertices.
+    ///
+    /// By default, all vertices have the same weight.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `vwgt` is not the number of
+    /// vertices.
+    pub fn set_vwgt(mut self, vwgt: &'a [Idx]) -> Ordering<'a> {
+        let vwgt_len = Idx::try_from(vwgt.len()).expect("vwgt array too large");
+        assert_eq!(vwgt_len, self.xadj.len() as Idx - 1);
+        self.vwgt = Some(vwgt);
+        self
+    }
+
+    /// Sets the fine-tuning parameters for this ordering.
+    ///
+    /// When few options are to be set, [`Ordering::set_option`] might be a
+    /// better fit.
+    ///
+    /// See the [option] module for the list of available parameters.  Note that
+    /// not all are applicable to orderings.  Refer to the documentation of
+    /// METIS ([link]) for more info on this.
+    ///
+    /// See [`crate::Graph::set_options`] for a usage example.
+    ///
+    /// [link]: http://glaros.dtc.umn.edu/gkhome/fetch/sw/metis/manual.pdf
+    pub fn set_options(mut self, options: &[Idx; NOPTIONS]) -> Ordering<'a> {
+        self.options.copy_from_slice(options);
+        self
+    }
+
+    /// Sets a fine-tuning parameter for this ordering.
+    ///
+    /// When options are to be set in batches, [`Ordering::set_options`] might
+    /// be a better fit.
+    ///
+    /// See the [option] module for the list of available parameters.  Note that
+    /// not all are applicable to orderings.  Refer to the documentation of
+    /// METIS ([link]) for more info on this.
+    ///
+    /// See [`crate::Graph::set_option`] for a usage example.
+    ///
+    /// [link]: http://glaros.dtc.umn.edu/gkhome/fetch/sw/metis/manual.pdf
+    pub fn set_option<O>(mut self, option: O) -> Ordering<'a>
+    where
+        O: option::Opt,
+    {
+        self.options[O::INDEX] = option.value();
+        self
+    }
+
+    /// Computes a fill-reducing ordering using multilevel nested dissection.
+    ///
+    /// See [`Permutation`] for the meaning of the result.  The input and the
+    /// result always use C-style numbering, the numbering option is
+    /// overridden.  Use [`Permutation::to_fortran`] to get 1-based arrays.
+    ///
+    /// Equivalent of `METIS_NodeND`.
+    pub fn nested_dissection(mut self) -> Result<Permutation> {
+        self.options[option::Numbering::INDEX] = option::Numbering::C.value();
+        let nvtxs = self.xadj.len() as Idx - 1;
+        let mut perm = vec![0; nvtxs as usize];
+        let mut iperm = vec![0; nvtxs as usize];
+
+        let _lock = lock_metis();
+        unsafe {
+            m::METIS_NodeND(
+                &nvtxs as *const Idx as *mut Idx,
+                slice_to_mut_ptr(self.xadj),
+                slice_to_mut_ptr(self.adjncy),
+                self.vwgt
+                    .map_or_else(ptr::null_mut, |s| slice_to_mut_ptr(s)),
+                slice_to_mut_ptr(&self.options),
+                perm.as_mut_ptr(),
+                iperm.as_mut_ptr(),
+            )
+            .wrap()?;
+        }
+
+        Ok(Permutation { perm, iperm })
+    }
+
+    /// Computes a fill-reducing ordering using multilevel nested dissection,
+    /// with the given options.
+    ///
+    /// The options set in `options` take precedence over the ones set
+    /// previously.  See [`option::OrderOptions`] for an example.
+    pub fn nested_dissection_with(mut self, options: &option::OrderOptions) -> Result<Permutation> {
+        options.apply(&mut self.options);
+        self.nested_dissection()
+    }
+}
+
+/// Error raised when the arrays fed to [`Permutation::new`] and friends do not
+/// describe a permutation.
+#[derive(Debug)]
+pub struct InvalidPermutationError {
+    msg: &'static str,
+}
+
+impl fmt::Display for InvalidPermutationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.msg.fmt(f)
+    }
+}
+
+impl std::error::Error for InvalidPermutationError {}
+
+impl InvalidPermutationError {
+    fn msg(msg: &'static str) -> Self {
+        Self { msg }
+    }
+}
+
+/// Computes the inverse of `perm`, checking along the way that it is a
+/// bijection on `0..perm.len()`.
+pub(crate) fn invert(perm: &[Idx]) -> StdResult<Vec<Idx>, InvalidPermutationError> {
+    let n = Idx::try_from(perm.len())
+        .map_err(|_| InvalidPermutationError::msg("permutation is too large"))?;
+    let mut iperm = vec![-1; perm.len()];
+    for (i, p) in perm.iter().enumerate() {
+        if *p < 0 || *p >= n {
+            return Err(InvalidPermutationError::msg(
+                "some values of the permutation are out of bounds",
+            ));
+        }
+        if iperm[*p as usize] != -1 {
+            return Err(InvalidPermutationError::msg(
+                "some values of the permutation appear twice",
+            ));
+        }
+        iperm[*p as usize] = i as Idx;
+    }
+    Ok(iperm)
+}
+
+/// A permutation of the vertices of a graph, along with its inverse.
+///
+/// Let `A` be the original matrix and `A'` the permuted matrix.  Row (column)
+/// `i` of `A'` is the `perm[i]`th row (column) of `A`, and row (column) `i` of
+/// `A` is the `iperm[i]`th row (column) of `A'`.  In other words, `perm` maps
+/// new indices to old ones, and `iperm` maps old indices to new ones.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::order::InvalidPermutationError> {
+/// use metis::order::Permutation;
+///
+/// let p = Permutation::from_perm(vec![2, 0, 1])?;
+/// assert_eq!(p.iperm(), &[1, 2, 0]);
+/// assert_eq!(p.apply_to(&['a', 'b', 'c']), ['c', 'a', 'b']);
+///
+/// // Applying the inverse restores the original order.
+/// let data = p.apply_to(&['a', 'b', 'c']);
+/// assert_eq!(p.inverse().apply_to(&data), ['a', 'b', 'c']);
+///
+/// // Composition applies `p`, then its argument.
+/// let q = Permutation::from_perm(vec![1, 0, 2])?;
+/// assert_eq!(
+///     p.compose(&q).apply_to(&['a', 'b', 'c']),
+///     q.apply_to(&p.apply_to(&['a', 'b', 'c'])),
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Permutation {
+    perm: Vec<Idx>,
+    iperm: Vec<Idx>,
+}
+
+impl Permutation {
+    /// Creates a new [`Permutation`] from both of its directions.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `perm` is not a permutation of
+    /// `0..perm.len()`, or if `iperm` is not its inverse.
+    pub fn new(perm: Vec<Idx>, iperm: Vec<Idx>) -> StdResult<Permutation, InvalidPermutationError> {
+        if invert(&perm)? != iperm {
+            return Err(InvalidPermutationError::msg(
+                "perm and iperm are not inverse of each other",
+            ));
+        }
+        Ok(Permutation { perm, iperm })
+    }
+
+    /// Creates a new [`Permutation`] from the new-to-old mapping.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `perm` is not a permutation of
+    /// `0..perm.len()`.
+    pub fn from_perm(perm: Vec<Idx>) -> StdResult<Permutation, InvalidPermutationError> {
+        let iperm = invert(&perm)?;
+        Ok(Permutation { perm, iperm })
+    }
+
+    /// Creates a new [`Permutation`] from the old-to-new mapping.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `iperm` is not a permutation of
+    /// `0..iperm.len()`.
+    pub fn from_iperm(iperm: Vec<Idx>) -> StdResult<Permutation, InvalidPermutationError> {
+        let perm = invert(&iperm)?;
+        Ok(Permutation { perm, iperm })
+    }
+
+    /// Creates a new [`Permutation`] from both of its directions, given with
+    /// Fortran-style numbering, i.e. on `1..=perm.len()`.
+    ///
+    /// The permutation is stored with C-style numbering, so that
+    /// [`Permutation::perm`] and [`Permutation::iperm`] are 0-based.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `perm` is not a permutation of
+    /// `1..=perm.len()`, or if `iperm` is not its inverse.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::order::InvalidPermutationError> {
+    /// use metis::order::Permutation;
+    ///
+    /// let p = Permutation::from_fortran(vec![3, 1, 2], vec![2, 3, 1])?;
+    /// assert_eq!(p.perm(), &[2, 0, 1]);
+    /// assert_eq!(p.to_fortran(), (vec![3, 1, 2], vec![2, 3, 1]));
+    ///
+    /// // 0 is out of bounds with Fortran-style numbering.
+    /// assert!(Permutation::from_fortran(vec![0, 1, 2], vec![0, 1, 2]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_fortran(
+        mut perm: Vec<Idx>,
+        mut iperm: Vec<Idx>,
+    ) -> StdResult<Permutation, InvalidPermutationError> {
+        for p in perm.iter_mut().chain(&mut iperm) {
+            *p = p.saturating_sub(1);
+        }
+        Permutation::new(perm, iperm)
+    }
+
+    /// The identity permutation on `0..n`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `n` cannot be held by an [`Idx`].
+    pub fn identity(n: usize) -> Permutation {
+        let n = Idx::try_from(n).expect("permutation larger than Idx::MAX");
+        let perm: Vec<Idx> = (0..n).collect();
+        Permutation {
+            iperm: perm.clone(),
+            perm,
+        }
+    }
+
+    /// The number of permuted elements.
+    pub fn len(&self) -> usize {
+        self.perm.len()
+    }
+
+    /// Whether the permutation is on an empty set.
+    pub fn is_empty(&self) -> bool {
+        self.perm.is_empty()
+    }
+
+    /// The new-to-old mapping.
+    pub fn perm(&self) -> &[Idx] {
+        &self.perm
+    }
+
+    /// The old-to-new mapping.
+    pub fn iperm(&self) -> &[Idx] {
+        &self.iperm
+    }
+
+    /// The new-to-old and old-to-new mappings, in that order.
+    pub fn into_inner(self) -> (Vec<Idx>, Vec<Idx>) {
+        (self.perm, self.iperm)
+    }
+
+    /// The new-to-old and old-to-new mappings, in that order, with
+    /// Fortran-style numbering (1-based).
+    ///
+    /// This is the format expected by Fortran solvers such as MUMPS.  See
+    /// [`Permutation::from_fortran`] for the reverse conversion.
+    pub fn to_fortran(&self) -> (Vec<Idx>, Vec<Idx>) {
+        (
+            self.perm.iter().map(|p| p + 1).collect(),
+            self.iperm.iter().map(|p| p + 1).collect(),
+        )
+    }
+
+    /// Whether `perm` is a bijection on `0..len()` and `iperm` is its inverse.
+    ///
+    /// This always holds for permutations built through the checked
+    /// constructors, but is a cheap sanity check on results coming from
+    /// METIS.
+    pub fn is_valid(&self) -> bool {
+        self.perm.len() == self.iperm.len()
+            && matches!(invert(&self.perm), Ok(iperm) if iperm == self.iperm)
+    }
+
+    /// The inverse permutation, which swaps `perm` and `iperm`.
+    pub fn inverse(&self) -> Permutation {
+        Permutation {
+            perm: self.iperm.clone(),
+            iperm: self.perm.clone(),
+        }
+    }
+
+    /// The permutation that applies `self`, then `other`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if both permutations do not have the same length.
+    pub fn compose(&self, other: &Permutation) -> Permutation {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "cannot compose permutations of different lengths",
+        );
+        let perm: Vec<Idx> = other.perm.iter().map(|p| self.perm[*p as usize]).collect();
+        let mut iperm = vec![0; perm.len()];
+        for (i, p) in perm.iter().enumerate() {
+            iperm[*p as usize] = i as Idx;
+        }
+        Permutation { perm, iperm }
+    }
+
+    /// Reorders `data`, given in the old order, into the new order.
+    ///
+    /// The `i`th element of the result is `data[perm[i]]`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `data` is not the length of the
+    /// permutation.
+    pub fn apply_to<T: Clone>(&self, data: &[T]) -> Vec<T> {
+        assert_eq!(
+            data.len(),
+            self.len(),
+            "data.len() must be equal to the length of the permutation",
+        );
+        self.perm
+            .iter()
+            .map(|p| data[*p as usize].clone())
+            .collect()
+    }
+}
@@ -0,0 +1,130 @@
+//! Partitioning routines that run METIS on several threads.
+//!
+//! Only available with the `rayon` feature.
+
+use crate::serialize_metis;
+use crate::Error;
+use crate::Graph;
+use crate::Idx;
+use crate::Result;
+use rayon::prelude::*;
+
+impl Graph<'_> {
+    /// Partition the graph using multilevel k-way partitioning, keeping the
+    /// best of `n_seeds` runs.
+    ///
+    /// METIS is run on several threads with the seeds `0` to `n_seeds - 1`
+    /// (see [`crate::option::Seed`]), and the partition with the lowest
+    /// objective is written to `part`.  The objective is the edge-cut, or the
+    /// communication volume when [`crate::option::ObjType::Vol`] is set.
+    /// Among runs with the same objective, the one with the lowest seed wins.
+    /// Each run works on its own copy of `xadj` and `adjncy`, which METIS may
+    /// permute while partitioning (see [`Graph::new`]).
+    ///
+    /// METIS draws random numbers from a process-wide generator (see
+    /// [`crate#thread-safety`]), so concurrent runs interleave their random
+    /// sequences.  Each run still gives a valid partition, but which one
+    /// depends on the scheduling of threads: the result is not reproducible,
+    /// and the run with a given seed might not match a sequential
+    /// partitioning with that seed.  With the `global-lock` feature, runs are
+    /// sequential, and the result is reproducible.  Without it, a fatal error
+    /// of METIS might also not be trapped, as explained there.
+    ///
+    /// Returns the objective of the best partition.  Only available with the
+    /// `rayon` feature.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::Input`] if `n_seeds` is zero, or if the
+    /// length of `part` is not the number of vertices.  Otherwise, it returns
+    /// the error of the run with the lowest seed, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::GraphBuilder;
+    ///
+    /// // A 6x6 grid.
+    /// let mut builder = GraphBuilder::new(36)?;
+    /// for i in 0..6 {
+    ///     for j in 0..5 {
+    ///         builder.add_edge(6 * i + j, 6 * i + j + 1)?;
+    ///         builder.add_edge(6 * j + i, 6 * j + i + 6)?;
+    ///     }
+    /// }
+    /// let owned = builder.build(1, 5)?;
+    ///
+    /// let mut part = [0; 36];
+    /// let best = owned.as_graph().part_kway_best_of(16, &mut part)?;
+    /// assert!(part.iter().all(|p| (0..5).contains(p)));
+    /// let edgecut = metis::quality::edge_cut(owned.xadj(), owned.adjncy(), None, &part)?;
+    /// assert_eq!(edgecut, i64::from(best));
+    ///
+    /// // Runs are sequential with the `global-lock` feature, so the run with
+    /// // seed 0 is the same as a single partitioning with that seed, and the
+    /// // result is the same from one call to another.
+    /// # #[cfg(feature = "global-lock")]
+    /// # {
+    /// let single = owned.as_graph().set_seed(0).part_kway(&mut [0; 36])?;
+    /// assert!(best <= single);
+    /// let mut part2 = [0; 36];
+    /// assert_eq!(owned.as_graph().part_kway_best_of(16, &mut part2)?, best);
+    /// assert_eq!(part, part2);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn part_kway_best_of(&mut self, n_seeds: usize, part: &mut [Idx]) -> Result<Idx> {
+        if n_seeds == 0 || part.len() != self.xadj.len() - 1 {
+            return Err(Error::Input(None));
+        }
+        let n_seeds = Idx::try_from(n_seeds).map_err(|_| Error::Input(None))?;
+        let this = &*self;
+        let runs: Vec<_> = (0..n_seeds)
+            .into_par_iter()
+            .map(|seed| -> Result<(Idx, Vec<Idx>)> {
+                let xadj = this.xadj.to_vec();
+                let adjncy = this.adjncy.to_vec();
+                let mut graph = Graph {
+                    xadj: &xadj,
+                    adjncy: &adjncy,
+                    ..*this
+                }
+                .set_seed(seed);
+                let mut part = vec![0; part.len()];
+                let objval = graph.try_part_kway(&mut part)?;
+                Ok((objval, part))
+            })
+            .collect();
+
+        // Runs are in seed order, so only a strictly lower objective replaces
+        // the best run.
+        let mut best: Option<(Idx, Vec<Idx>)> = None;
+        for run in runs {
+            let (objval, run_part) = run?;
+            match &best {
+                Some((best_objval, _)) if *best_objval <= objval => {}
+                _ => best = Some((objval, run_part)),
+            }
+        }
+        let (objval, best_part) = best.expect("n_seeds is not zero");
+        part.copy_from_slice(&best_part);
+        Ok(objval)
+    }
+}
+
+/// Maps `f` over `items` in parallel, keeping their order.
+///
+/// Calls to METIS made by `f` are serialized.
+pub(crate) fn map<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Send + Sync,
+{
+    items
+        .into_par_iter()
+        .map(|item| serialize_metis(|| f(item)))
+        .collect()
+}
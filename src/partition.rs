@@ -0,0 +1,596 @@
+//! Partition results, and the [`Partition`] and [`PartitionExt`] traits for
+//! graph types.
+//!
+//! See [`PartitionVec`] for a usage example.
+
+use crate::option;
+use crate::Graph;
+use crate::GraphOwned;
+use crate::Idx;
+use crate::NewGraphError;
+use crate::Result;
+use crate::NOPTIONS;
+use std::convert::TryFrom;
+use std::fmt;
+use std::result::Result as StdResult;
+
+/// Graph types that can be turned into a METIS graph.
+///
+/// The returned graph owns its arrays, so that the conversion is free to
+/// build them.
+///
+/// It is implemented for adjacency lists, and for the matrices of the
+/// [`crate::sprs`] and [`crate::nalgebra_sparse`] integrations.
+pub trait Partition {
+    /// Makes a [`GraphOwned`] of `nparts` parts out of `self`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `self` is not a valid graph, or if
+    /// `nparts` is not strictly positive.
+    fn setup_partition(&self, nparts: Idx) -> StdResult<GraphOwned, NewGraphError>;
+}
+
+/// Adjacency lists: `self[v]` contains the neighbors of vertex `v`.
+///
+/// This is the same as [`Graph::from_adjacency_lists`] with one constraint.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::Partition;
+///
+/// // 5 - 3 - 4 - 0
+/// //     |   | /
+/// //     2 - 1
+/// let lists = vec![
+///     vec![1, 4],
+///     vec![0, 2, 4],
+///     vec![1, 3],
+///     vec![2, 4, 5],
+///     vec![0, 1, 3],
+///     vec![3],
+/// ];
+/// let mut part = [0; 6];
+/// lists.setup_partition(2)?.part_kway(&mut part)?;
+///
+/// // Neighbors are checked.
+/// let lists = vec![vec![1], vec![2]];
+/// assert!(lists.setup_partition(2).is_err());
+/// # Ok(())
+/// # }
+/// ```
+impl Partition for [Vec<Idx>] {
+    fn setup_partition(&self, nparts: Idx) -> StdResult<GraphOwned, NewGraphError> {
+        Graph::from_adjacency_lists(1, nparts, self)
+    }
+}
+
+/// Parameters of the graphs made by [`PartitionExt::setup_partition_with`].
+///
+/// `T` is the type of the values carried by the edges of the source graph,
+/// for example the entries of a sparse matrix.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::option::Opt;
+/// use metis::option::Seed;
+/// use metis::PartitionConfig;
+/// use metis::PartitionExt;
+///
+/// // 0 - 1 - 2 - 3
+/// let lists = vec![vec![1], vec![0, 2], vec![1, 3], vec![2]];
+///
+/// // Two constraints: the first vertex weight is one for all vertices, the
+/// // second one is the index of the vertex.
+/// let config = PartitionConfig::new(2)
+///     .set_ncon(2)
+///     .set_option(Seed(42))
+///     .set_vwgt(|v, c| if c == 0 { 1 } else { v + 1 });
+/// let graph = lists.setup_partition_with(&config)?;
+///
+/// let parts = graph.as_graph().into_inner();
+/// assert_eq!(parts.ncon, 2);
+/// assert_eq!(parts.vwgt, Some(&[1, 1, 1, 2, 1, 3, 1, 4][..]));
+/// assert_eq!(parts.options[Seed::INDEX], 42);
+/// # Ok(())
+/// # }
+/// ```
+pub struct PartitionConfig<'a, T> {
+    nparts: Idx,
+    ncon: Idx,
+    options: [Idx; NOPTIONS],
+    vwgt: Option<VertexWeight<'a>>,
+    adjwgt: Option<EdgeWeight<'a, T>>,
+}
+
+/// Weight of a vertex for a constraint.
+type VertexWeight<'a> = Box<dyn Fn(Idx, Idx) -> Idx + 'a>;
+
+/// Weight of an edge, given its value.
+type EdgeWeight<'a, T> = Box<dyn Fn(&T) -> Idx + 'a>;
+
+impl<T> fmt::Debug for PartitionConfig<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartitionConfig")
+            .field("nparts", &self.nparts)
+            .field("ncon", &self.ncon)
+            .field("options", &self.options)
+            .field("vwgt", &self.vwgt.is_some())
+            .field("adjwgt", &self.adjwgt.is_some())
+            .finish()
+    }
+}
+
+impl<'a, T> PartitionConfig<'a, T> {
+    /// Creates the parameters of a graph of `nparts` parts and one constraint,
+    /// with default options and no weights.
+    pub fn new(nparts: Idx) -> PartitionConfig<'a, T> {
+        PartitionConfig {
+            nparts,
+            ncon: 1,
+            options: option::initial(),
+            vwgt: None,
+            adjwgt: None,
+        }
+    }
+
+    /// The number of parts.
+    pub fn nparts(&self) -> Idx {
+        self.nparts
+    }
+
+    /// The number of balancing constraints.
+    pub fn ncon(&self) -> Idx {
+        self.ncon
+    }
+
+    /// Sets the number of balancing constraints.
+    ///
+    /// Vertex weights, if any, are queried for each constraint.
+    pub fn set_ncon(mut self, ncon: Idx) -> PartitionConfig<'a, T> {
+        self.ncon = ncon;
+        self
+    }
+
+    /// Sets the fine-tuning parameters of the graphs.
+    ///
+    /// See [`Graph::set_options`].
+    pub fn set_options(mut self, options: &[Idx; NOPTIONS]) -> PartitionConfig<'a, T> {
+        self.options.copy_from_slice(options);
+        self
+    }
+
+    /// Sets a fine-tuning parameter of the graphs.
+    ///
+    /// See [`Graph::set_option`].
+    pub fn set_option<O>(mut self, option: O) -> PartitionConfig<'a, T>
+    where
+        O: option::Opt,
+    {
+        self.options[O::INDEX] = option.value();
+        self
+    }
+
+    /// Sets the vertex weights: `vwgt(v, c)` is the weight of vertex `v` for
+    /// the constraint `c`.
+    pub fn set_vwgt<F>(mut self, vwgt: F) -> PartitionConfig<'a, T>
+    where
+        F: Fn(Idx, Idx) -> Idx + 'a,
+    {
+        self.vwgt = Some(Box::new(vwgt));
+        self
+    }
+
+    /// Sets the edge weights, given by applying `adjwgt` to the values of the
+    /// edges.
+    pub fn set_adjwgt<F>(mut self, adjwgt: F) -> PartitionConfig<'a, T>
+    where
+        F: Fn(&T) -> Idx + 'a,
+    {
+        self.adjwgt = Some(Box::new(adjwgt));
+        self
+    }
+
+    /// The edge weight mapping, if set.
+    pub(crate) fn adjwgt(&self) -> Option<&(dyn Fn(&T) -> Idx + 'a)> {
+        self.adjwgt.as_deref()
+    }
+
+    /// Makes a graph of the given arrays, with the parameters of `self`.
+    #[cfg_attr(
+        not(any(feature = "sprs", feature = "nalgebra-sparse", feature = "petgraph")),
+        allow(dead_code)
+    )]
+    pub(crate) fn build(
+        &self,
+        xadj: Vec<Idx>,
+        adjncy: Vec<Idx>,
+        adjwgt: Option<Vec<Idx>>,
+    ) -> StdResult<GraphOwned, NewGraphError> {
+        let graph = GraphOwned::new(self.ncon, self.nparts, xadj, adjncy)?;
+        self.finish(graph, adjwgt)
+    }
+
+    /// Sets the options and weights of `graph`, which must have been made
+    /// with the number of parts and constraints of `self`.
+    fn finish(
+        &self,
+        mut graph: GraphOwned,
+        adjwgt: Option<Vec<Idx>>,
+    ) -> StdResult<GraphOwned, NewGraphError> {
+        graph = graph.set_options(&self.options);
+        if let Some(vwgt) = &self.vwgt {
+            let nvtxs = graph.xadj().len() as Idx - 1;
+            let weights = (0..nvtxs)
+                .flat_map(|v| (0..self.ncon).map(move |c| vwgt(v, c)))
+                .collect();
+            graph = graph.try_set_vwgt(weights)?;
+        }
+        match adjwgt {
+            Some(adjwgt) => graph.try_set_adjwgt(adjwgt),
+            None => Ok(graph),
+        }
+    }
+}
+
+/// Graph types that can be turned into a METIS graph, with several
+/// constraints, options or weights.
+///
+/// This extends [`Partition`] to the parameters held by a [`PartitionConfig`].
+/// `T` is the type of the edge values fed to [`PartitionConfig::set_adjwgt`].
+/// Without edge weight mapping, the graphs are unweighted.
+///
+/// It is implemented for adjacency lists, and for the graphs and matrices of
+/// the optional integrations:
+///
+/// - [`crate::sprs`]: `CsMatBase`, whose pattern must be symmetric, and whose
+///   diagonal is dropped,
+/// - [`crate::nalgebra_sparse`]: `CsrMatrix` and `CscMatrix`, likewise,
+/// - [`crate::petgraph`]: `Graph`, `StableGraph` and `Csr`, whose edges are
+///   merged as with [`crate::csr::WeightMerge::Sum`].
+pub trait PartitionExt<T> {
+    /// Makes a [`GraphOwned`] out of `self`, according to `config`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the errors of the underlying conversion, and
+    /// fails if some weights are not strictly positive, or if the number of
+    /// parts or constraints is not strictly positive.
+    fn setup_partition_with(&self, config: &PartitionConfig<'_, T>) -> Result<GraphOwned>;
+}
+
+/// Adjacency lists carry no edge values: the edge weight mapping is called on
+/// `&()` for each edge.
+impl PartitionExt<()> for [Vec<Idx>] {
+    fn setup_partition_with(&self, config: &PartitionConfig<'_, ()>) -> Result<GraphOwned> {
+        let graph = Graph::from_adjacency_lists(config.ncon, config.nparts, self)?;
+        let adjwgt = config
+            .adjwgt()
+            .map(|weight| graph.adjncy().iter().map(|_| weight(&())).collect());
+        Ok(config.finish(graph, adjwgt)?)
+    }
+}
+
+/// Error raised when the labels fed to [`PartitionVec::new`] are not within
+/// zero and the number of parts.
+#[derive(Debug)]
+pub struct InvalidPartitionError {
+    msg: &'static str,
+}
+
+impl fmt::Display for InvalidPartitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.msg.fmt(f)
+    }
+}
+
+impl std::error::Error for InvalidPartitionError {}
+
+impl InvalidPartitionError {
+    fn msg(msg: &'static str) -> Self {
+        Self { msg }
+    }
+}
+
+/// The part of each vertex (or element) of a partitioned graph (or mesh).
+///
+/// Labels are always zero-based: they are within zero and
+/// [`PartitionVec::nparts`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// // 0 - 1 - 2 - 3 - 4 - 5
+/// let xadj = &[0, 1, 3, 5, 7, 9, 10];
+/// let adjncy = &[1, 0, 2, 1, 3, 2, 4, 3, 5, 4];
+///
+/// let (partition, edgecut) = metis::Graph::new(1, 2, xadj, adjncy)?.part_recursive_owned()?;
+/// assert_eq!(edgecut, 1);
+/// assert_eq!(partition.nparts(), 2);
+/// assert_eq!(partition.counts(), [3, 3]);
+///
+/// let p = partition.part_of(0);
+/// assert_eq!(partition.len_of_part(p), 3);
+/// assert_eq!(partition.iter_part(p).collect::<Vec<_>>(), [0, 1, 2]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawPartitionVec")
+)]
+pub struct PartitionVec {
+    labels: Vec<Idx>,
+    nparts: Idx,
+}
+
+/// Unchecked form of [`PartitionVec`], validated on deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawPartitionVec {
+    labels: Vec<Idx>,
+    nparts: Idx,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawPartitionVec> for PartitionVec {
+    type Error = InvalidPartitionError;
+
+    fn try_from(raw: RawPartitionVec) -> StdResult<Self, Self::Error> {
+        PartitionVec::new(raw.labels, raw.nparts)
+    }
+}
+
+impl PartitionVec {
+    /// Creates a new [`PartitionVec`] from the part of each vertex.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `nparts` is not strictly greater than
+    /// zero, or if some labels are not within zero and `nparts`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use metis::PartitionVec;
+    ///
+    /// assert!(PartitionVec::new(vec![0, 2, 1, 1], 3).is_ok());
+    /// assert!(PartitionVec::new(vec![0, 3], 3).is_err());
+    /// assert!(PartitionVec::new(vec![-1], 3).is_err());
+    /// ```
+    pub fn new(labels: Vec<Idx>, nparts: Idx) -> StdResult<PartitionVec, InvalidPartitionError> {
+        if nparts <= 0 {
+            return Err(InvalidPartitionError::msg(
+                "there must be at least one part",
+            ));
+        }
+        if labels.iter().any(|p| *p < 0 || nparts <= *p) {
+            return Err(InvalidPartitionError::msg("some labels are out of bounds"));
+        }
+        Ok(PartitionVec { labels, nparts })
+    }
+
+    /// Creates a new [`PartitionVec`] without checking the labels.
+    ///
+    /// Labels must be within zero and `nparts`.
+    pub(crate) fn new_unchecked(labels: Vec<Idx>, nparts: Idx) -> PartitionVec {
+        debug_assert!(labels.iter().all(|p| 0 <= *p && *p < nparts));
+        PartitionVec { labels, nparts }
+    }
+
+    /// The number of parts.
+    ///
+    /// Some parts might be empty.
+    pub fn nparts(&self) -> Idx {
+        self.nparts
+    }
+
+    /// The number of vertices.
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Whether there are no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// The part of each vertex.
+    pub fn labels(&self) -> &[Idx] {
+        &self.labels
+    }
+
+    /// The part of vertex `v`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `v` is not within zero and the number of
+    /// vertices.
+    pub fn part_of(&self, v: Idx) -> Idx {
+        let v = usize::try_from(v).expect("vertex index is negative");
+        self.labels[v]
+    }
+
+    /// The number of vertices in part `p`.
+    ///
+    /// This runs in linear time; use [`PartitionVec::counts`] to get the size
+    /// of all parts at once.
+    pub fn len_of_part(&self, p: Idx) -> usize {
+        self.labels.iter().filter(|q| **q == p).count()
+    }
+
+    /// Iterates over the vertices of part `p`, in increasing order.
+    pub fn iter_part(&self, p: Idx) -> impl Iterator<Item = Idx> + '_ {
+        self.labels
+            .iter()
+            .enumerate()
+            .filter(move |(_, q)| **q == p)
+            .map(|(v, _)| v as Idx)
+    }
+
+    /// The number of vertices in each part.
+    pub fn counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.nparts as usize];
+        for p in &self.labels {
+            counts[*p as usize] += 1;
+        }
+        counts
+    }
+
+    /// Returns the part of each vertex.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use metis::PartitionVec;
+    ///
+    /// let partition = PartitionVec::new(vec![1, 0, 1], 2).unwrap();
+    /// assert_eq!(partition.counts(), [1, 2]);
+    /// assert_eq!(partition.len_of_part(0), 1);
+    /// assert_eq!(partition.iter_part(1).collect::<Vec<_>>(), [0, 2]);
+    ///
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// // With the "serde" feature, labels are checked on deserialization.
+    /// let json = serde_json::to_string(&partition).unwrap();
+    /// assert_eq!(serde_json::from_str::<PartitionVec>(&json).unwrap(), partition);
+    /// assert!(serde_json::from_str::<PartitionVec>(r#"{"labels":[2],"nparts":2}"#).is_err());
+    /// # }
+    ///
+    /// assert_eq!(partition.into_inner(), [1, 0, 1]);
+    /// ```
+    pub fn into_inner(self) -> Vec<Idx> {
+        self.labels
+    }
+}
+
+/// A graph partitioning method of METIS.
+///
+/// Used to pick the method of routines that partition graphs on their own,
+/// such as [`crate::diagnostics::check_reproducible`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    /// Multilevel recursive bisection, see [`crate::Graph::part_recursive`].
+    Recursive,
+
+    /// Multilevel k-way partitioning, see [`crate::Graph::part_kway`].
+    #[default]
+    Kway,
+}
+
+impl Method {
+    /// Partitions `graph` with this method.
+    pub(crate) fn part(self, graph: &GraphOwned) -> Result<(PartitionVec, Idx)> {
+        match self {
+            Method::Recursive => graph.part_recursive_owned(),
+            Method::Kway => graph.part_kway_owned(),
+        }
+    }
+}
+
+/// Partitions each graph of `graphs` with `method`, one after the other.
+///
+/// Results are in the same order as `graphs`, and are those of
+/// [`GraphOwned::part_recursive_owned`] or [`GraphOwned::part_kway_owned`],
+/// with parts numbered from zero.  Graphs are independent: an error on one of
+/// them does not prevent the others from being partitioned.
+///
+/// Graphs are not partitioned on several threads.  METIS draws random numbers
+/// from a process-wide generator (see [`crate#thread-safety`]), so concurrent
+/// calls would make the result of each graph depend on the others, while here
+/// it is the same as if the graph was partitioned alone.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::{GraphBuilder, Method};
+///
+/// // Pseudo-random small graphs.
+/// let mut state = 42_u32;
+/// let mut random = |max: metis::Idx| {
+///     state = state.wrapping_mul(1103515245).wrapping_add(12345);
+///     (state >> 16) as metis::Idx % max
+/// };
+/// let mut graphs = Vec::new();
+/// for _ in 0..100 {
+///     let nvtxs = 2 + random(30);
+///     let mut builder = GraphBuilder::new(nvtxs)?;
+///     for _ in 0..2 * nvtxs {
+///         builder.add_edge(random(nvtxs), random(nvtxs))?;
+///     }
+///     graphs.push(builder.build(1, 1 + random(4))?);
+/// }
+///
+/// for method in [Method::Recursive, Method::Kway] {
+///     let batch = metis::partition_batch(graphs.clone(), method);
+///     let sequential: Vec<_> = graphs
+///         .iter()
+///         .map(|graph| match method {
+///             Method::Recursive => graph.part_recursive_owned(),
+///             Method::Kway => graph.part_kway_owned(),
+///         })
+///         .map(|res| res.map(|(part, edgecut)| (part.into_inner(), edgecut)))
+///         .collect();
+///
+///     assert_eq!(batch, sequential);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn partition_batch(graphs: Vec<GraphOwned>, method: Method) -> Vec<Result<(Vec<Idx>, Idx)>> {
+    graphs
+        .iter()
+        .map(|graph| {
+            let (part, edgecut) = method.part(graph)?;
+            Ok((part.into_inner(), edgecut))
+        })
+        .collect()
+}
+
+/// The outcome of partitioning a graph into a given number of parts.
+///
+/// Returned by [`crate::Graph::sweep_nparts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SweepEntry {
+    /// The number of parts.
+    pub nparts: Idx,
+
+    /// The edge-cut of the partition.
+    pub edgecut: Idx,
+
+    /// The weight of the heaviest part, on the first constraint.
+    pub max_part_weight: i64,
+
+    /// The weight of the lightest part, on the first constraint.
+    pub min_part_weight: i64,
+
+    /// The partition itself.
+    pub part: PartitionVec,
+}
+
+impl SweepEntry {
+    /// Computes the part weights of `part`, given the vertex weights of the
+    /// graph (one per vertex and constraint).
+    pub(crate) fn new(edgecut: Idx, part: PartitionVec, vwgt: Option<&[Idx]>, ncon: Idx) -> Self {
+        let mut weights = vec![0; part.nparts() as usize];
+        for (v, p) in part.labels().iter().enumerate() {
+            weights[*p as usize] += vwgt.map_or(1, |vwgt| i64::from(vwgt[v * ncon as usize]));
+        }
+        SweepEntry {
+            nparts: part.nparts(),
+            edgecut,
+            max_part_weight: weights.iter().copied().max().unwrap_or(0),
+            min_part_weight: weights.iter().copied().min().unwrap_or(0),
+            part,
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! Conversion of [petgraph](https://docs.rs/petgraph) graphs.
+//!
+//! METIS only partitions undirected graphs without self-loops.  The functions
+//! of this module turn the edges of petgraph graphs into such graphs, by the
+//! following policy:
+//!
+//! - self-loops are dropped,
+//! - an edge from `u` to `v` connects both vertices, whatever its direction,
+//! - the weights of all the edges between two vertices, in both directions,
+//!   are combined with the given [`WeightMerge`].
+//!
+//! For example, the directed edges `u -> v` of weight 2 and `v -> u` of weight
+//! 3 become one edge of weight 5 with [`WeightMerge::Sum`].  Edge weights are
+//! given by a closure, which must return strictly positive values.  Vertex
+//! weights can be set afterwards, with [`GraphOwned::try_set_vwgt`].
+//!
+//! [`petgraph::Graph`], [`StableGraph`] and [`Csr`] also implement
+//! [`crate::PartitionExt`], which merges edges with [`WeightMerge::Sum`], and
+//! sets the number of constraints, the options and the weights from a
+//! [`crate::PartitionConfig`].
+//! Without edge weight mapping, the graph is unweighted.
+
+use crate::csr;
+use crate::csr::WeightMerge;
+use crate::GraphOwned;
+use crate::Idx;
+use crate::NewGraphError;
+use crate::PartitionConfig;
+use crate::PartitionExt;
+use crate::Result;
+use petgraph::csr::Csr;
+use petgraph::graph::IndexType;
+use petgraph::graph::NodeIndex;
+use petgraph::stable_graph::StableGraph;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::NodeIndexable;
+use petgraph::EdgeType;
+use std::result::Result as StdResult;
+
+/// Converts a [`petgraph::Graph`] into a METIS graph of `nparts` parts.
+///
+/// Vertex `i` of the result is the node of index `i`.
+///
+/// # Errors
+///
+/// This function returns an error if `nparts` is not strictly positive, if the
+/// graph is too large for [`Idx`], or if some merged edge weights are not
+/// strictly positive.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::csr::WeightMerge;
+/// use petgraph::Graph;
+///
+/// // A directed triangle, with a way back from 1 to 0 and a self-loop.
+/// let mut graph = Graph::<(), metis::Idx>::new();
+/// let [a, b, c] = [graph.add_node(()), graph.add_node(()), graph.add_node(())];
+/// graph.extend_with_edges([(a, b, 2), (b, a, 3), (b, c, 1), (c, a, 1), (c, c, 9)]);
+///
+/// let metis_graph = metis::petgraph::from_graph(&graph, 2, WeightMerge::Sum, |w| *w)?;
+/// assert_eq!(metis_graph.xadj(), &[0, 2, 4, 6]);
+/// assert_eq!(metis_graph.adjncy(), &[1, 2, 0, 2, 0, 1]);
+/// assert_eq!(metis_graph.adjwgt(), Some(&[5, 1, 5, 1, 1, 1][..]));
+///
+/// let max = metis::petgraph::from_graph(&graph, 2, WeightMerge::Max, |w| *w)?;
+/// assert_eq!(max.adjwgt(), Some(&[3, 1, 3, 1, 1, 1][..]));
+///
+/// let mut part = [0; 3];
+/// metis_graph.part_recursive(&mut part)?;
+/// assert_eq!(part[0], part[1]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_graph<N, E, Ty, Ix, F>(
+    graph: &petgraph::Graph<N, E, Ty, Ix>,
+    nparts: Idx,
+    merge: WeightMerge,
+    mut weight: F,
+) -> StdResult<GraphOwned, NewGraphError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    F: FnMut(&E) -> Idx,
+{
+    let edges = graph
+        .edge_references()
+        .map(|e| (e.source().index(), e.target().index(), weight(e.weight())));
+    build(graph.node_count(), edges, true, nparts, merge)
+}
+
+/// Converts a [`StableGraph`] into a METIS graph of `nparts` parts.
+///
+/// Node indices of a [`StableGraph`] may have holes, left by removed nodes.
+/// Vertices of the result are numbered densely instead, in the order of node
+/// indices, and the returned vector maps each vertex to its node.
+///
+/// # Errors
+///
+/// This function returns the same errors as [`from_graph`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::csr::WeightMerge;
+/// use petgraph::stable_graph::StableUnGraph;
+///
+/// // A path of 5 nodes, whose ends are removed.
+/// let mut graph = StableUnGraph::<(), ()>::default();
+/// let nodes: Vec<_> = (0..5).map(|_| graph.add_node(())).collect();
+/// for w in nodes.windows(2) {
+///     graph.add_edge(w[0], w[1], ());
+/// }
+/// graph.remove_node(nodes[0]);
+/// graph.remove_node(nodes[4]);
+///
+/// let (metis_graph, map) =
+///     metis::petgraph::from_stable_graph(&graph, 2, WeightMerge::Sum, |_| 1)?;
+/// assert_eq!(map, &nodes[1..4]);
+/// assert_eq!(metis_graph.xadj(), &[0, 1, 3, 4]);
+/// assert_eq!(metis_graph.adjncy(), &[1, 0, 2, 1]);
+///
+/// // Parts of the vertices are the parts of the nodes in the map.
+/// let mut part = [0; 3];
+/// assert_eq!(metis_graph.part_recursive(&mut part)?, 1);
+/// let part_of = |node| part[map.iter().position(|n| *n == node).unwrap()];
+/// assert_ne!(part_of(nodes[1]), part_of(nodes[3]));
+/// assert!(!map.contains(&nodes[0]) && !map.contains(&nodes[4]));
+/// # Ok(())
+/// # }
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn from_stable_graph<N, E, Ty, Ix, F>(
+    graph: &StableGraph<N, E, Ty, Ix>,
+    nparts: Idx,
+    merge: WeightMerge,
+    mut weight: F,
+) -> StdResult<(GraphOwned, Vec<NodeIndex<Ix>>), NewGraphError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    F: FnMut(&E) -> Idx,
+{
+    let map: Vec<NodeIndex<Ix>> = graph.node_indices().collect();
+    let dense = dense_indices(graph);
+    let edges = graph.edge_references().map(|e| {
+        let u = dense[e.source().index()];
+        let v = dense[e.target().index()];
+        (u, v, weight(e.weight()))
+    });
+    let metis_graph = build(map.len(), edges, true, nparts, merge)?;
+    Ok((metis_graph, map))
+}
+
+/// Maps the index of each node of `graph` to its rank among the nodes, in
+/// the order of node indices.  Holes are mapped to `usize::MAX`.
+fn dense_indices<N, E, Ty, Ix>(graph: &StableGraph<N, E, Ty, Ix>) -> Vec<usize>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    let mut dense = vec![usize::MAX; graph.node_bound()];
+    for (vertex, node) in graph.node_indices().enumerate() {
+        dense[node.index()] = vertex;
+    }
+    dense
+}
+
+/// Converts a [`petgraph::csr::Csr`] into a METIS graph of `nparts` parts.
+///
+/// Vertex `i` of the result is the node of index `i`.  Undirected [`Csr`]
+/// graphs store each edge in both directions, which count as one edge here.
+///
+/// Arrays are always copied: [`Csr`] does not expose its row offsets, and its
+/// indices are unsigned while [`Idx`] is signed.
+///
+/// # Errors
+///
+/// This function returns the same errors as [`from_graph`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::csr::WeightMerge;
+/// use petgraph::csr::Csr;
+/// use petgraph::Undirected;
+///
+/// // 0 - 1 - 2 - 3
+/// let mut graph = Csr::<(), metis::Idx, Undirected>::with_nodes(4);
+/// graph.add_edge(0, 1, 4);
+/// graph.add_edge(1, 2, 1);
+/// graph.add_edge(2, 3, 4);
+///
+/// let metis_graph = metis::petgraph::from_csr(&graph, 2, WeightMerge::Sum, |w| *w)?;
+/// assert_eq!(metis_graph.xadj(), &[0, 1, 3, 5, 6]);
+/// assert_eq!(metis_graph.adjncy(), &[1, 0, 2, 1, 3, 2]);
+/// assert_eq!(metis_graph.adjwgt(), Some(&[4, 4, 1, 1, 4, 4][..]));
+///
+/// let mut part = [0; 4];
+/// let edgecut = metis_graph.part_recursive(&mut part)?;
+/// assert_eq!(edgecut, 1);
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_csr<N, E, Ty, Ix, F>(
+    graph: &Csr<N, E, Ty, Ix>,
+    nparts: Idx,
+    merge: WeightMerge,
+    mut weight: F,
+) -> StdResult<GraphOwned, NewGraphError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    F: FnMut(&E) -> Idx,
+{
+    let edges = graph
+        .edge_references()
+        .map(|e| (e.source().index(), e.target().index(), weight(e.weight())));
+    build(graph.node_count(), edges, Ty::is_directed(), nparts, merge)
+}
+
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::option::Opt;
+/// use metis::option::Seed;
+/// use metis::PartitionConfig;
+/// use metis::PartitionExt;
+/// use petgraph::Graph;
+///
+/// // Two triangles joined by a light edge.
+/// let graph = Graph::<(), metis::Idx>::from_edges([
+///     (0, 1, 5),
+///     (1, 2, 5),
+///     (2, 0, 5),
+///     (2, 3, 1),
+///     (3, 4, 5),
+///     (4, 5, 5),
+///     (5, 3, 5),
+/// ]);
+///
+/// let config = PartitionConfig::new(2).set_option(Seed(11)).set_adjwgt(|w| *w);
+/// let metis_graph = graph.setup_partition_with(&config)?;
+/// assert_eq!(metis_graph.as_graph().into_inner().options[Seed::INDEX], 11);
+///
+/// let mut part = [0; 6];
+/// assert_eq!(metis_graph.part_recursive(&mut part)?, 1);
+///
+/// // Without edge weight mapping, the graph is unweighted.
+/// let unweighted = graph.setup_partition_with(&PartitionConfig::new(2))?;
+/// assert_eq!(unweighted.adjwgt(), None);
+/// # Ok(())
+/// # }
+/// ```
+impl<N, E, Ty, Ix> PartitionExt<E> for petgraph::Graph<N, E, Ty, Ix>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    fn setup_partition_with(&self, config: &PartitionConfig<'_, E>) -> Result<GraphOwned> {
+        let edges = self
+            .edge_references()
+            .map(|e| (e.source().index(), e.target().index(), e.weight()));
+        with_config(self.node_count(), edges, true, config)
+    }
+}
+
+/// Vertices are numbered densely, in the order of node indices, as with
+/// [`from_stable_graph`].  This is the order of
+/// [`StableGraph::node_indices`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::PartitionConfig;
+/// use metis::PartitionExt;
+/// use petgraph::stable_graph::StableUnGraph;
+///
+/// // 0 - 1 - 2 - 3 - 4, with a heavy edge in the middle, whose ends are
+/// // removed.
+/// let mut graph = StableUnGraph::<(), metis::Idx>::default();
+/// let nodes: Vec<_> = (0..5).map(|_| graph.add_node(())).collect();
+/// for (w, weight) in nodes.windows(2).zip([1, 2, 2, 1]) {
+///     graph.add_edge(w[0], w[1], weight);
+/// }
+/// graph.remove_node(nodes[0]);
+/// graph.remove_node(nodes[4]);
+///
+/// let config = PartitionConfig::new(2).set_adjwgt(|w| *w);
+/// let metis_graph = graph.setup_partition_with(&config)?;
+/// assert_eq!(metis_graph.xadj(), &[0, 1, 3, 4]);
+/// assert_eq!(metis_graph.adjncy(), &[1, 0, 2, 1]);
+/// assert_eq!(metis_graph.adjwgt(), Some(&[2, 2, 2, 2][..]));
+///
+/// let map: Vec<_> = graph.node_indices().collect();
+/// assert_eq!(map, &nodes[1..4]);
+/// # Ok(())
+/// # }
+/// ```
+impl<N, E, Ty, Ix> PartitionExt<E> for StableGraph<N, E, Ty, Ix>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    fn setup_partition_with(&self, config: &PartitionConfig<'_, E>) -> Result<GraphOwned> {
+        let dense = dense_indices(self);
+        let edges = self.edge_references().map(|e| {
+            let u = dense[e.source().index()];
+            let v = dense[e.target().index()];
+            (u, v, e.weight())
+        });
+        with_config(self.node_count(), edges, true, config)
+    }
+}
+
+impl<N, E, Ty, Ix> PartitionExt<E> for Csr<N, E, Ty, Ix>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    fn setup_partition_with(&self, config: &PartitionConfig<'_, E>) -> Result<GraphOwned> {
+        let edges = self
+            .edge_references()
+            .map(|e| (e.source().index(), e.target().index(), e.weight()));
+        with_config(self.node_count(), edges, Ty::is_directed(), config)
+    }
+}
+
+/// Builds a METIS graph out of a list of `(u, v, value)` edges according to
+/// `config`, adding the reverse of each edge when `reverse` is set.
+fn with_config<'e, E: 'e>(
+    nvtxs: usize,
+    edges: impl Iterator<Item = (usize, usize, &'e E)>,
+    reverse: bool,
+    config: &PartitionConfig<'_, E>,
+) -> Result<GraphOwned> {
+    let (xadj, adjncy, adjwgt) = match config.adjwgt() {
+        Some(weight) => {
+            let edges = edges.map(|(u, v, e)| (u, v, weight(e)));
+            let (xadj, adjncy, adjwgt) = arcs(nvtxs, edges, reverse, WeightMerge::Sum)?;
+            (xadj, adjncy, Some(adjwgt))
+        }
+        None => {
+            let edges = edges.map(|(u, v, _)| (u, v, 1));
+            let (xadj, adjncy, _) = arcs(nvtxs, edges, reverse, WeightMerge::Max)?;
+            (xadj, adjncy, None)
+        }
+    };
+    Ok(config.build(xadj, adjncy, adjwgt)?)
+}
+
+/// Builds a METIS graph out of a list of `(u, v, weight)` edges, adding the
+/// reverse of each edge when `reverse` is set.
+fn build(
+    nvtxs: usize,
+    edges: impl Iterator<Item = (usize, usize, Idx)>,
+    reverse: bool,
+    nparts: Idx,
+    merge: WeightMerge,
+) -> StdResult<GraphOwned, NewGraphError> {
+    let (xadj, adjncy, adjwgt) = arcs(nvtxs, edges, reverse, merge)?;
+    GraphOwned::new(1, nparts, xadj, adjncy)?.try_set_adjwgt(adjwgt)
+}
+
+/// Makes the CSR arrays of a list of `(u, v, weight)` edges, without
+/// self-loops.
+#[allow(clippy::type_complexity)]
+fn arcs(
+    nvtxs: usize,
+    edges: impl Iterator<Item = (usize, usize, Idx)>,
+    reverse: bool,
+    merge: WeightMerge,
+) -> StdResult<(Vec<Idx>, Vec<Idx>, Vec<Idx>), NewGraphError> {
+    let nvtxs = Idx::try_from(nvtxs).map_err(|_| NewGraphError::TooLarge)?;
+    let mut arcs = Vec::new();
+    for (u, v, w) in edges {
+        if u == v {
+            continue;
+        }
+        // Vertices are below nvtxs, so they fit in Idx.
+        let (u, v) = (u as Idx, v as Idx);
+        arcs.push((u, v, w));
+        if reverse {
+            arcs.push((v, u, w));
+        }
+    }
+    csr::from_arcs(nvtxs, arcs, |_, a, b| match merge {
+        WeightMerge::Sum => a.checked_add(b).ok_or(NewGraphError::TooLarge),
+        _ => Ok(merge.apply(a, b)),
+    })
+}
@@ -0,0 +1,2036 @@
+//! Quality metrics of partitions.
+//!
+//! These functions evaluate partitions computed elsewhere, or check the
+//! objective returned by METIS.  Graphs are given in the format of
+//! [`crate::Graph::new`].
+
+use crate::array_len;
+use crate::check_graph_structure;
+use crate::csr;
+use crate::find_negative;
+use crate::option::Numbering;
+use crate::option::ObjType;
+use crate::option::Opt as _;
+use crate::order::Permutation;
+use crate::zero_based;
+use crate::Graph;
+use crate::Idx;
+use crate::NewGraphError;
+use crate::Real;
+use crate::Result;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::mem;
+use std::result::Result as StdResult;
+
+/// Error raised when a partition does not match its graph or its number of
+/// parts.
+///
+/// Returned by [`validate_partition`].  The other functions of this module
+/// run the same checks, and return these errors within
+/// [`crate::Error::Input`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartitionError {
+    /// The number of parts is not strictly positive.
+    NoParts,
+
+    /// The partition does not have one label per vertex.
+    LengthMismatch {
+        /// The number of vertices.
+        expected: usize,
+
+        /// The length of the partition.
+        actual: usize,
+    },
+
+    /// A label is negative.
+    NegativeLabel {
+        /// The first offending vertex.
+        index: usize,
+
+        /// Its label.
+        value: Idx,
+    },
+
+    /// A label is not less than the number of parts.
+    LabelOutOfBounds {
+        /// The first offending vertex.
+        index: usize,
+
+        /// Its label.
+        value: Idx,
+
+        /// The number of parts.
+        nparts: Idx,
+    },
+}
+
+impl fmt::Display for PartitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoParts => write!(f, "there must be at least one part"),
+            Self::LengthMismatch { expected, actual } => {
+                write!(f, "partition has length {actual}, expected {expected}")
+            }
+            Self::NegativeLabel { index, value } => {
+                write!(f, "part[{index}] is negative ({value})")
+            }
+            Self::LabelOutOfBounds {
+                index,
+                value,
+                nparts,
+            } => write!(f, "part[{index}] is {value}, expected within 0..{nparts}"),
+        }
+    }
+}
+
+impl std::error::Error for PartitionError {}
+
+/// Summary of a valid partition.
+///
+/// Returned by [`validate_partition`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionStats {
+    counts: Vec<usize>,
+}
+
+impl PartitionStats {
+    /// The number of parts.
+    pub fn nparts(&self) -> Idx {
+        self.counts.len() as Idx
+    }
+
+    /// The number of vertices of each part.
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// The parts without vertices, in increasing order.
+    pub fn empty_parts(&self) -> Vec<Idx> {
+        (0..self.nparts())
+            .filter(|p| self.counts[*p as usize] == 0)
+            .collect()
+    }
+}
+
+/// Checks a partition of a graph of `nvtxs` vertices into `nparts` parts.
+///
+/// This is the counterpart of the checks of [`crate::Graph::new`] for the
+/// output of a partitioner, e.g. a partition read from a file.
+///
+/// # Errors
+///
+/// This function returns an error if `nparts` is not strictly positive, if the
+/// length of `part` is not `nvtxs`, or if some labels are not within zero and
+/// `nparts`.
+///
+/// # Example
+///
+/// ```rust
+/// use metis::quality::{validate_partition, PartitionError};
+///
+/// let stats = validate_partition(6, 4, &[0, 3, 3, 0, 1, 0]).unwrap();
+/// assert_eq!(stats.counts(), [3, 1, 0, 2]);
+/// assert_eq!(stats.empty_parts(), [2]);
+///
+/// assert_eq!(
+///     validate_partition(6, 3, &[0, 3, 3, 0, 1, 0]),
+///     Err(PartitionError::LabelOutOfBounds { index: 1, value: 3, nparts: 3 }),
+/// );
+/// assert_eq!(
+///     validate_partition(6, 4, &[0, 3, -1, 0, 1, 0]),
+///     Err(PartitionError::NegativeLabel { index: 2, value: -1 }),
+/// );
+/// assert_eq!(
+///     validate_partition(5, 4, &[0, 3, 3, 0, 1, 0]),
+///     Err(PartitionError::LengthMismatch { expected: 5, actual: 6 }),
+/// );
+/// assert_eq!(validate_partition(0, 0, &[]), Err(PartitionError::NoParts));
+/// ```
+pub fn validate_partition(
+    nvtxs: Idx,
+    nparts: Idx,
+    part: &[Idx],
+) -> StdResult<PartitionStats, PartitionError> {
+    let nvtxs = usize::try_from(nvtxs).map_err(|_| PartitionError::LengthMismatch {
+        expected: 0,
+        actual: part.len(),
+    })?;
+    check_partition(nvtxs, Some(nparts), part)?;
+    let mut counts = vec![0; nparts as usize];
+    for p in part {
+        counts[*p as usize] += 1;
+    }
+    Ok(PartitionStats { counts })
+}
+
+/// Checks that `part` has `nvtxs` non-negative labels, less than `nparts` if
+/// given.
+fn check_partition(
+    nvtxs: usize,
+    nparts: Option<Idx>,
+    part: &[Idx],
+) -> StdResult<(), PartitionError> {
+    if nvtxs != part.len() {
+        return Err(PartitionError::LengthMismatch {
+            expected: nvtxs,
+            actual: part.len(),
+        });
+    }
+    if let Some((index, value)) = find_negative(part) {
+        return Err(PartitionError::NegativeLabel { index, value });
+    }
+    if let Some(nparts) = nparts {
+        if nparts <= 0 {
+            return Err(PartitionError::NoParts);
+        }
+        if let Some(index) = part.iter().position(|p| nparts <= *p) {
+            return Err(PartitionError::LabelOutOfBounds {
+                index,
+                value: part[index],
+                nparts,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `part` has one non-negative label per vertex, and returns the
+/// number of vertices.
+pub(crate) fn check_part(xadj: &[Idx], adjncy: &[Idx], part: &[Idx]) -> Result<usize> {
+    let nvtxs = check_graph_structure(xadj, adjncy)? as usize;
+    check_partition(nvtxs, None, part)?;
+    Ok(nvtxs)
+}
+
+/// Checks that the labels of `part` are within zero and `nparts`.
+pub(crate) fn check_labels(part: &[Idx], nparts: Idx) -> Result<()> {
+    check_partition(part.len(), Some(nparts), part)?;
+    Ok(())
+}
+
+/// Checks that `vwgt`, if any, holds `ncon` non-negative weights for each of
+/// the `nvtxs` vertices.
+pub(crate) fn check_vwgt(ncon: Idx, vwgt: Option<&[Idx]>, nvtxs: usize) -> Result<()> {
+    if ncon <= 0 {
+        return Err(NewGraphError::NoConstraints.into());
+    }
+    let len = array_len(ncon, nvtxs)?;
+    if let Some(vwgt) = vwgt {
+        NewGraphError::check_len("vwgt", len, vwgt.len())?;
+        if let Some((index, value)) = find_negative(vwgt) {
+            return Err(NewGraphError::NegativeValue {
+                array: "vwgt",
+                index,
+                value,
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Sums the weights of the vertices of each part, for each constraint.
+///
+/// The weight of the `i`th part and `j`th constraint is at `i*ncon+j`.
+/// Arguments must have been checked by [`check_labels`] and [`check_vwgt`].
+pub(crate) fn sum_weights(
+    ncon: usize,
+    vwgt: Option<&[Idx]>,
+    part: &[Idx],
+    nparts: usize,
+) -> Vec<i64> {
+    let mut pwgts = vec![0; nparts * ncon];
+    for (v, p) in part.iter().enumerate() {
+        let pwgts = &mut pwgts[*p as usize * ncon..(*p as usize + 1) * ncon];
+        match vwgt {
+            Some(vwgt) => {
+                for (pwgt, w) in pwgts.iter_mut().zip(&vwgt[v * ncon..(v + 1) * ncon]) {
+                    *pwgt += i64::from(*w);
+                }
+            }
+            None => pwgts.iter_mut().for_each(|pwgt| *pwgt += 1),
+        }
+    }
+    pwgts
+}
+
+/// Computes the edge-cut of a partition: the total weight of the edges whose
+/// ends are in different parts.
+///
+/// Each undirected edge is counted once, with the weight of its arc from the
+/// lower to the higher vertex.  Edges have a weight of one when `adjwgt` is
+/// `None`.  This is the objective METIS minimizes by default.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `xadj` and `adjncy` are
+/// not a valid graph structure, if the length of `adjwgt` is not the one of
+/// `adjncy`, if the length of `part` is not the number of vertices, or if
+/// some labels are negative.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::edge_cut;
+///
+/// // 0 - 1 - 2 - 3
+/// //   5   1   5
+/// let xadj = &[0, 1, 3, 5, 6];
+/// let adjncy = &[1, 0, 2, 1, 3, 2];
+/// let adjwgt = &[5, 5, 1, 1, 5, 5];
+///
+/// assert_eq!(edge_cut(xadj, adjncy, None, &[0, 0, 1, 1])?, 1);
+/// assert_eq!(edge_cut(xadj, adjncy, Some(adjwgt), &[0, 0, 1, 1])?, 1);
+/// assert_eq!(edge_cut(xadj, adjncy, Some(adjwgt), &[0, 1, 0, 1])?, 11);
+/// assert!(edge_cut(xadj, adjncy, None, &[0, 0, 1]).is_err());
+///
+/// // Same as what METIS reports.
+/// let mut part = [0; 4];
+/// let objval = metis::Graph::new(1, 2, xadj, adjncy)?
+///     .set_adjwgt(adjwgt)
+///     .part_kway(&mut part)?;
+/// assert_eq!(edge_cut(xadj, adjncy, Some(adjwgt), &part)?, i64::from(objval));
+/// # Ok(())
+/// # }
+/// ```
+pub fn edge_cut(xadj: &[Idx], adjncy: &[Idx], adjwgt: Option<&[Idx]>, part: &[Idx]) -> Result<i64> {
+    check_part(xadj, adjncy, part)?;
+    if let Some(adjwgt) = adjwgt {
+        NewGraphError::check_len("adjwgt", adjncy.len(), adjwgt.len())?;
+    }
+    let mut cut = 0;
+    for (v, w) in xadj.windows(2).enumerate() {
+        for i in w[0] as usize..w[1] as usize {
+            let u = adjncy[i] as usize;
+            if v < u && part[v] != part[u] {
+                cut += adjwgt.map_or(1, |adjwgt| i64::from(adjwgt[i]));
+            }
+        }
+    }
+    Ok(cut)
+}
+
+/// Computes the total communication volume of a partition.
+///
+/// Each vertex `v` counts `vsize[v]` once for each part other than its own
+/// among the parts of its neighbors, that is, the amount of data sent to
+/// other parts if every vertex sends its data to each part that needs it.
+/// Vertices have a size of one when `vsize` is `None`.  This is the objective
+/// METIS minimizes with [`crate::option::ObjType::Vol`].
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `xadj` and `adjncy` are
+/// not a valid graph structure, if the length of `vsize` or `part` is not the
+/// number of vertices, or if some labels are negative.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::communication_volume;
+///
+/// // A star whose center 0 is adjacent to three parts.
+/// //     1
+/// //     |
+/// // 2 - 0 - 3
+/// //     |
+/// //     4
+/// let xadj = &[0, 4, 5, 6, 7, 8];
+/// let adjncy = &[1, 2, 3, 4, 0, 0, 0, 0];
+/// let part = &[0, 1, 2, 3, 3];
+///
+/// // The center sends to parts 1, 2 and 3, each leaf sends to part 0.
+/// assert_eq!(communication_volume(xadj, adjncy, None, part)?, 3 + 4);
+/// let vsize = &[10, 1, 1, 1, 1];
+/// assert_eq!(communication_volume(xadj, adjncy, Some(vsize), part)?, 30 + 4);
+/// assert!(communication_volume(xadj, adjncy, Some(&vsize[1..]), part).is_err());
+///
+/// // Same as what METIS reports.
+/// use metis::option::ObjType;
+/// let mut part = [0; 5];
+/// let objval = metis::Graph::new(1, 2, xadj, adjncy)?
+///     .set_option(ObjType::Vol)
+///     .part_kway(&mut part)?;
+/// assert_eq!(communication_volume(xadj, adjncy, None, &part)?, i64::from(objval));
+/// # Ok(())
+/// # }
+/// ```
+pub fn communication_volume(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    vsize: Option<&[Idx]>,
+    part: &[Idx],
+) -> Result<i64> {
+    let nvtxs = check_part(xadj, adjncy, part)?;
+    if let Some(vsize) = vsize {
+        NewGraphError::check_len("vsize", nvtxs, vsize.len())?;
+    }
+
+    // Labels are not bounded, so the foreign parts of each vertex are
+    // deduplicated by sorting them rather than with a marker per part.
+    let mut foreign = Vec::new();
+    let mut volume = 0;
+    for (v, w) in xadj.windows(2).enumerate() {
+        let size = vsize.map_or(1, |vsize| i64::from(vsize[v]));
+        foreign.clear();
+        foreign.extend(
+            adjncy[w[0] as usize..w[1] as usize]
+                .iter()
+                .map(|u| part[*u as usize])
+                .filter(|q| *q != part[v]),
+        );
+        foreign.sort_unstable();
+        foreign.dedup();
+        volume += size * foreign.len() as i64;
+    }
+    Ok(volume)
+}
+
+/// Computes the load imbalance of a partition, for each constraint.
+///
+/// The imbalance of the `j`th constraint is the largest ratio between the
+/// weight of a part and its target weight, `max_i w[i,j] / (t[i,j] * W[j])`,
+/// where `W[j]` is the total weight of the constraint, as in METIS' manual.
+/// A perfectly balanced partition has an imbalance of 1.0, and METIS aims for
+/// at most `1 + UFactor/1000` (see [`crate::option::UFactor`]).
+///
+/// Vertices have a weight of one when `vwgt` is `None`, and parts have a
+/// target weight of `1/nparts` when `tpwgts` is `None`.  Weights are laid out
+/// as in [`crate::Graph::set_vwgt`] and [`crate::Graph::set_tpwgts`].  An
+/// empty part with a target weight of zero does not count, while a non-empty
+/// one has an infinite imbalance.  A constraint whose weights are all zero has
+/// an imbalance of zero.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `ncon` or `nparts` is not
+/// strictly positive, if the length of `vwgt` is not `ncon` times the number
+/// of vertices, if the length of `tpwgts` is not `ncon` times `nparts`, if
+/// some weights are negative, or if some labels are not within zero and
+/// `nparts`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::imbalance;
+///
+/// // Six vertices with two constraints, in two parts.
+/// let vwgt = &[1, 0, 1, 0, 1, 0, 1, 2, 1, 2, 1, 2];
+/// let part = &[0, 0, 0, 1, 1, 1];
+///
+/// // Part 1 holds all the weight of the second constraint.
+/// assert_eq!(imbalance(2, Some(vwgt), None, part, 2)?, vec![1.0, 2.0]);
+/// let part = &[0, 0, 1, 1, 1, 1];
+/// assert_eq!(imbalance(2, Some(vwgt), None, part, 2)?, vec![4.0 / 3.0, 2.0]);
+///
+/// // Unweighted vertices, with a part twice as large as the other.
+/// let tpwgts = &[1.0 / 3.0, 2.0 / 3.0];
+/// assert_eq!(imbalance(1, None, Some(tpwgts), part, 2)?, vec![1.0]);
+/// assert_eq!(imbalance(1, None, None, part, 2)?, vec![4.0 / 3.0]);
+///
+/// // An empty part of target weight zero.
+/// let tpwgts = &[0.0, 1.0, 0.0];
+/// assert_eq!(imbalance(1, None, Some(tpwgts), &[1, 1, 1], 3)?, vec![1.0]);
+/// assert_eq!(imbalance(1, None, Some(tpwgts), &[0, 1, 1], 3)?, vec![f32::INFINITY]);
+///
+/// assert!(imbalance(1, None, None, &[0, 2], 2).is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub fn imbalance(
+    ncon: Idx,
+    vwgt: Option<&[Idx]>,
+    tpwgts: Option<&[Real]>,
+    part: &[Idx],
+    nparts: Idx,
+) -> Result<Vec<Real>> {
+    check_labels(part, nparts)?;
+    check_vwgt(ncon, vwgt, part.len())?;
+    if let Some(tpwgts) = tpwgts {
+        NewGraphError::check_len("tpwgts", array_len(ncon, nparts as usize)?, tpwgts.len())?;
+        if let Some(index) = tpwgts.iter().position(|t| !(t.is_finite() && *t >= 0.0)) {
+            return Err(NewGraphError::InvalidValue {
+                array: "tpwgts",
+                index,
+                value: tpwgts[index],
+            }
+            .into());
+        }
+    }
+    let (ncon, nparts) = (ncon as usize, nparts as usize);
+    let pwgts = sum_weights(ncon, vwgt, part, nparts);
+
+    let imbalance = (0..ncon)
+        .map(|j| {
+            let total: i64 = pwgts.iter().skip(j).step_by(ncon).sum();
+            if total == 0 {
+                return 0.0;
+            }
+            let mut max = 0.0_f64;
+            for (i, pwgt) in pwgts.iter().skip(j).step_by(ncon).enumerate() {
+                let target = match tpwgts {
+                    Some(tpwgts) => f64::from(tpwgts[i * ncon + j]),
+                    None => 1.0 / nparts as f64,
+                };
+                let ratio = match (*pwgt, target == 0.0) {
+                    (0, true) => 0.0,
+                    (_, true) => f64::INFINITY,
+                    (pwgt, false) => pwgt as f64 / (target * total as f64),
+                };
+                max = f64::max(max, ratio);
+            }
+            max as Real
+        })
+        .collect();
+    Ok(imbalance)
+}
+
+/// The number of vertices and the weights of each part of a partition.
+///
+/// Returned by [`part_weights`].  Its [`Display`](fmt::Display)
+/// implementation prints a table with one row per part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawPartWeights")
+)]
+pub struct PartWeights {
+    ncon: usize,
+    counts: Vec<usize>,
+    weights: Vec<i64>,
+}
+
+/// Unchecked form of [`PartWeights`], validated on deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawPartWeights {
+    ncon: usize,
+    counts: Vec<usize>,
+    weights: Vec<i64>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawPartWeights> for PartWeights {
+    type Error = &'static str;
+
+    fn try_from(raw: RawPartWeights) -> StdResult<Self, Self::Error> {
+        if raw.ncon.checked_mul(raw.counts.len()) != Some(raw.weights.len()) {
+            return Err("weights must have ncon values per part");
+        }
+        Ok(PartWeights {
+            ncon: raw.ncon,
+            counts: raw.counts,
+            weights: raw.weights,
+        })
+    }
+}
+
+impl PartWeights {
+    /// The number of parts.
+    pub fn nparts(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// The number of constraints.
+    pub fn ncon(&self) -> usize {
+        self.ncon
+    }
+
+    /// The number of vertices of each part.
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// The weights of the given part, one per constraint.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `part` is not within zero and the number of
+    /// parts.
+    pub fn weights(&self, part: usize) -> &[i64] {
+        &self.weights[part * self.ncon..(part + 1) * self.ncon]
+    }
+
+    /// The weight of the lightest part, for each constraint.
+    pub fn min(&self) -> Vec<i64> {
+        (0..self.ncon)
+            .map(|j| self.column(j).min().unwrap_or(0))
+            .collect()
+    }
+
+    /// The weight of the heaviest part, for each constraint.
+    pub fn max(&self) -> Vec<i64> {
+        (0..self.ncon)
+            .map(|j| self.column(j).max().unwrap_or(0))
+            .collect()
+    }
+
+    /// The mean weight of parts, for each constraint.
+    pub fn mean(&self) -> Vec<f64> {
+        let nparts = self.nparts() as f64;
+        (0..self.ncon)
+            .map(|j| self.column(j).sum::<i64>() as f64 / nparts)
+            .collect()
+    }
+
+    /// The weights of all parts for the `j`th constraint.
+    fn column(&self, j: usize) -> impl Iterator<Item = i64> + '_ {
+        self.weights.iter().skip(j).step_by(self.ncon).copied()
+    }
+}
+
+impl fmt::Display for PartWeights {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows = vec![vec!["part".to_string(), "vertices".to_string()]];
+        rows[0].extend((0..self.ncon).map(|j| format!("weight[{j}]")));
+        for (i, count) in self.counts.iter().enumerate() {
+            let mut row = vec![i.to_string(), count.to_string()];
+            row.extend(self.weights(i).iter().map(i64::to_string));
+            rows.push(row);
+        }
+        let widths: Vec<usize> = (0..rows[0].len())
+            .map(|c| rows.iter().map(|row| row[c].len()).max().unwrap_or(0))
+            .collect();
+        for row in rows {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:>width$}"))
+                .collect();
+            writeln!(f, "{}", cells.join("  "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Computes the number of vertices and the weights of each part of a
+/// partition.
+///
+/// Vertices have a weight of one when `vwgt` is `None`.  Weights are laid out
+/// as in [`crate::Graph::set_vwgt`]: the weight of the `i`th vertex and `j`th
+/// constraint is at `vwgt[i*ncon+j]`.  Sums are computed on 64 bits, so they
+/// do not overflow.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `ncon` or `nparts` is not
+/// strictly positive, if the length of `vwgt` is not `ncon` times the number
+/// of vertices, if some weights are negative, or if some labels are not
+/// within zero and `nparts`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::part_weights;
+///
+/// // Five vertices with two constraints, in three parts.
+/// let vwgt = &[1, 0, 2, 1, 3, 1, 1, 4, 5, 0];
+/// let part = &[0, 1, 1, 0, 1];
+/// let weights = part_weights(2, Some(vwgt), part, 3)?;
+///
+/// assert_eq!(weights.counts(), [2, 3, 0]);
+/// assert_eq!(weights.weights(0), [2, 4]);
+/// assert_eq!(weights.weights(1), [10, 2]);
+/// assert_eq!(weights.weights(2), [0, 0]);
+/// assert_eq!(weights.min(), [0, 0]);
+/// assert_eq!(weights.max(), [10, 4]);
+/// assert_eq!(weights.mean(), [4.0, 2.0]);
+/// assert_eq!(
+///     weights.to_string(),
+///     "\
+/// part  vertices  weight[0]  weight[1]
+///    0         2          2          4
+///    1         3         10          2
+///    2         0          0          0
+/// ",
+/// );
+///
+/// # #[cfg(feature = "serde")]
+/// # {
+/// let json = serde_json::to_string(&weights).unwrap();
+/// assert_eq!(json, r#"{"ncon":2,"counts":[2,3,0],"weights":[2,4,10,2,0,0]}"#);
+/// assert_eq!(serde_json::from_str::<metis::quality::PartWeights>(&json).unwrap(), weights);
+/// let json = r#"{"ncon":2,"counts":[2,3,0],"weights":[2,4,10,2]}"#;
+/// assert!(serde_json::from_str::<metis::quality::PartWeights>(json).is_err());
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub fn part_weights(
+    ncon: Idx,
+    vwgt: Option<&[Idx]>,
+    part: &[Idx],
+    nparts: Idx,
+) -> Result<PartWeights> {
+    check_labels(part, nparts)?;
+    check_vwgt(ncon, vwgt, part.len())?;
+    let (ncon, nparts) = (ncon as usize, nparts as usize);
+    let mut counts = vec![0; nparts];
+    for p in part {
+        counts[*p as usize] += 1;
+    }
+    Ok(PartWeights {
+        ncon,
+        counts,
+        weights: sum_weights(ncon, vwgt, part, nparts),
+    })
+}
+
+/// Lists the boundary vertices of each part of a partition, that is, the
+/// vertices that have at least one neighbor in another part.
+///
+/// The result is indexed by part and has `nparts` entries.  Vertices are
+/// sorted within each part.  This runs in a single pass over the graph, in
+/// `O(V + E)` time.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `xadj` and `adjncy` are
+/// not a valid graph structure, if the length of `part` is not the number of
+/// vertices, if `nparts` is not strictly positive, or if some labels are not
+/// within zero and `nparts`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::boundary_vertices;
+///
+/// // 0 - 1 - 2 | 3 - 4 | 5 - 6
+/// let xadj = &[0, 1, 3, 5, 7, 9, 11, 12];
+/// let adjncy = &[1, 0, 2, 1, 3, 2, 4, 3, 5, 4, 6, 5];
+/// let part = &[0, 0, 0, 1, 1, 2, 2];
+///
+/// // Vertices 0, 1 and 6 are inside their part.
+/// let boundary = boundary_vertices(xadj, adjncy, part, 3)?;
+/// assert_eq!(boundary, [vec![2], vec![3, 4], vec![5]]);
+///
+/// // Parts may be empty.
+/// let boundary = boundary_vertices(xadj, adjncy, part, 4)?;
+/// assert_eq!(boundary[3], []);
+/// # Ok(())
+/// # }
+/// ```
+pub fn boundary_vertices(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    part: &[Idx],
+    nparts: Idx,
+) -> Result<Vec<Vec<Idx>>> {
+    check_part(xadj, adjncy, part)?;
+    check_labels(part, nparts)?;
+    let mut boundary = vec![Vec::new(); nparts as usize];
+    for (v, w) in xadj.windows(2).enumerate() {
+        let p = part[v];
+        let neighbors = &adjncy[w[0] as usize..w[1] as usize];
+        if neighbors.iter().any(|u| part[*u as usize] != p) {
+            boundary[p as usize].push(v as Idx);
+        }
+    }
+    Ok(boundary)
+}
+
+/// A boundary vertex and the parts of its neighbors, other than its own.
+///
+/// Returned by [`boundary_parts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundaryVertex {
+    /// The vertex.
+    pub vertex: Idx,
+
+    /// The parts adjacent to the vertex, other than its own, sorted.
+    pub parts: Vec<Idx>,
+}
+
+/// Same as [`boundary_vertices`], but also lists the parts each boundary
+/// vertex is adjacent to, other than its own.
+///
+/// Each boundary vertex comes with its foreign parts.  The number of
+/// foreign parts of a vertex is the count it adds to [`communication_volume`]
+/// when vertices have a size of one.
+///
+/// # Errors
+///
+/// This function returns the same errors as [`boundary_vertices`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::boundary_parts;
+///
+/// // A star whose center 0 is adjacent to three parts.
+/// let xadj = &[0, 4, 5, 6, 7, 8];
+/// let adjncy = &[1, 2, 3, 4, 0, 0, 0, 0];
+/// let part = &[0, 3, 1, 3, 1];
+///
+/// let boundary = boundary_parts(xadj, adjncy, part, 4)?;
+/// assert_eq!(boundary[0].len(), 1);
+/// assert_eq!(boundary[0][0].vertex, 0);
+/// assert_eq!(boundary[0][0].parts, [1, 3]);
+/// let vertices = |p: usize| boundary[p].iter().map(|b| b.vertex).collect::<Vec<_>>();
+/// assert_eq!(vertices(1), [2, 4]);
+/// assert_eq!(vertices(2), []);
+/// assert_eq!(vertices(3), [1, 3]);
+/// assert!(boundary[3].iter().all(|b| b.parts == [0]));
+/// # Ok(())
+/// # }
+/// ```
+pub fn boundary_parts(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    part: &[Idx],
+    nparts: Idx,
+) -> Result<Vec<Vec<BoundaryVertex>>> {
+    check_part(xadj, adjncy, part)?;
+    check_labels(part, nparts)?;
+    let mut boundary = vec![Vec::new(); nparts as usize];
+
+    // marker[p] is the last vertex that found part p among its neighbors.
+    let mut marker = vec![usize::MAX; nparts as usize];
+    for (v, w) in xadj.windows(2).enumerate() {
+        let p = part[v];
+        marker[p as usize] = v;
+        let mut foreign = Vec::new();
+        for &u in &adjncy[w[0] as usize..w[1] as usize] {
+            let q = part[u as usize];
+            if marker[q as usize] != v {
+                marker[q as usize] = v;
+                foreign.push(q);
+            }
+        }
+        if !foreign.is_empty() {
+            foreign.sort_unstable();
+            boundary[p as usize].push(BoundaryVertex {
+                vertex: v as Idx,
+                parts: foreign,
+            });
+        }
+    }
+    Ok(boundary)
+}
+
+/// The vertices each part needs from the others as ghosts, for a distributed
+/// computation.
+///
+/// The list of the pair `(p, q)` holds the vertices owned by part `p` that
+/// have a neighbor in part `q`, sorted.  It is what `p` sends to `q`, and what
+/// `q` receives from `p`.  Returned by [`ghost_lists`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GhostLists {
+    nparts: Idx,
+    lists: BTreeMap<(Idx, Idx), Vec<Idx>>,
+}
+
+impl GhostLists {
+    /// The number of parts.
+    pub fn nparts(&self) -> Idx {
+        self.nparts
+    }
+
+    /// The vertices part `p` sends to part `q`.
+    pub fn list(&self, p: Idx, q: Idx) -> &[Idx] {
+        self.lists.get(&(p, q)).map_or(&[], Vec::as_slice)
+    }
+
+    /// The parts `p` sends vertices to, each with its non-empty list, in
+    /// increasing order of part.
+    pub fn sends(&self, p: Idx) -> impl Iterator<Item = (Idx, &[Idx])> + '_ {
+        self.lists
+            .range((p, 0)..=(p, Idx::MAX))
+            .map(|((_, q), list)| (*q, list.as_slice()))
+    }
+
+    /// The parts `q` receives vertices from, each with its non-empty list, in
+    /// increasing order of part.
+    pub fn receives(&self, q: Idx) -> impl Iterator<Item = (Idx, &[Idx])> + '_ {
+        self.lists
+            .iter()
+            .filter(move |((_, to), _)| *to == q)
+            .map(|((p, _), list)| (*p, list.as_slice()))
+    }
+
+    /// The total number of vertices sent, which is the
+    /// [`communication_volume`] of the partition when vertices have a size of
+    /// one.
+    pub fn total_volume(&self) -> usize {
+        self.lists.values().map(Vec::len).sum()
+    }
+}
+
+/// Computes the ghost lists of a partition, see [`GhostLists`].
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `xadj` and `adjncy` are
+/// not a valid graph structure, if the length of `part` is not the number of
+/// vertices, if `nparts` is not strictly positive, or if some labels are not
+/// within zero and `nparts`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::{communication_volume, ghost_lists};
+///
+/// // 0 - 1 - 2 - 3, in two parts.
+/// let xadj = &[0, 1, 3, 5, 6];
+/// let adjncy = &[1, 0, 2, 1, 3, 2];
+/// let ghosts = ghost_lists(xadj, adjncy, &[0, 0, 1, 1], 2)?;
+/// assert_eq!(ghosts.list(0, 1), [1]);
+/// assert_eq!(ghosts.list(1, 0), [2]);
+/// assert_eq!(ghosts.list(0, 0), []);
+/// assert_eq!(ghosts.total_volume(), 2);
+///
+/// // A 2x3 grid cut into its three columns:
+/// // 0 | 1 | 2
+/// // 3 | 4 | 5
+/// let xadj = &[0, 2, 5, 7, 9, 12, 14];
+/// let adjncy = &[1, 3, 0, 2, 4, 1, 5, 0, 4, 1, 3, 5, 2, 4];
+/// let part = &[0, 1, 2, 0, 1, 2];
+/// let ghosts = ghost_lists(xadj, adjncy, part, 3)?;
+///
+/// // The middle column sends to both sides, the outer ones do not see each
+/// // other.
+/// let sends: Vec<_> = ghosts.sends(1).collect();
+/// assert_eq!(sends, [(0, &[1, 4][..]), (2, &[1, 4][..])]);
+/// let receives: Vec<_> = ghosts.receives(1).collect();
+/// assert_eq!(receives, [(0, &[0, 3][..]), (2, &[2, 5][..])]);
+/// assert_eq!(ghosts.list(0, 2), []);
+///
+/// let volume = communication_volume(xadj, adjncy, None, part)?;
+/// assert_eq!(ghosts.total_volume() as i64, volume);
+/// # Ok(())
+/// # }
+/// ```
+pub fn ghost_lists(xadj: &[Idx], adjncy: &[Idx], part: &[Idx], nparts: Idx) -> Result<GhostLists> {
+    let mut lists = BTreeMap::new();
+    for boundary in boundary_parts(xadj, adjncy, part, nparts)? {
+        for BoundaryVertex { vertex, parts } in boundary {
+            let p = part[vertex as usize];
+            for q in parts {
+                lists.entry((p, q)).or_insert_with(Vec::new).push(vertex);
+            }
+        }
+    }
+    Ok(GhostLists { nparts, lists })
+}
+
+/// Builds the subdomain graph of a partition, also known as its quotient
+/// graph.
+///
+/// Vertices of the subdomain graph are the `nparts` parts, and two parts are
+/// adjacent when some of their vertices are.  The weight of an edge is the
+/// total weight of the edges cut between the two parts, so that the weights
+/// of the subdomain graph sum to twice the edge-cut.  Edges have a weight of
+/// one when `adjwgt` is `None`.  This is the graph whose degree
+/// [`crate::option::MinConn`] minimizes.
+///
+/// The result is returned as `(xadj, adjncy, adjwgt)`, with sorted adjacency
+/// lists.  It meets the requirements of [`crate::Graph::new`] and
+/// [`crate::Graph::set_adjwgt`], so it can be partitioned in turn, e.g. to map
+/// parts onto a hierarchical machine.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `xadj` and `adjncy` are
+/// not a valid graph structure, if the length of `adjwgt` is not the one of
+/// `adjncy`, if some edge weights are not strictly positive, if the length of
+/// `part` is not the number of vertices, if `nparts` is not strictly positive,
+/// if some labels are not within zero and `nparts`, or if some weights of the
+/// subdomain graph do not fit in an [`Idx`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::{edge_cut, subdomain_graph};
+///
+/// // A ring of eight vertices cut into four arcs, with heavier edges
+/// // between parts 0 and 3.
+/// let xadj = &[0, 2, 4, 6, 8, 10, 12, 14, 16];
+/// let adjncy = &[1, 7, 0, 2, 1, 3, 2, 4, 3, 5, 4, 6, 5, 7, 0, 6];
+/// let adjwgt = &[1, 3, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 3, 1];
+/// let part = &[0, 0, 1, 1, 2, 2, 3, 3];
+///
+/// // The parts form a ring as well.
+/// let (sxadj, sadjncy, sadjwgt) = subdomain_graph(xadj, adjncy, Some(adjwgt), part, 4)?;
+/// assert_eq!(sxadj, [0, 2, 4, 6, 8]);
+/// assert_eq!(sadjncy, [1, 3, 0, 2, 1, 3, 0, 2]);
+/// assert_eq!(sadjwgt, [1, 3, 1, 1, 1, 1, 3, 1]);
+///
+/// let cut = edge_cut(xadj, adjncy, Some(adjwgt), part)?;
+/// assert_eq!(sadjwgt.iter().map(|w| i64::from(*w)).sum::<i64>(), 2 * cut);
+///
+/// // The subdomain graph can be partitioned in turn.
+/// let mut parent = [0; 4];
+/// metis::Graph::new(1, 2, &sxadj, &sadjncy)?
+///     .set_adjwgt(&sadjwgt)
+///     .part_recursive(&mut parent)?;
+/// # Ok(())
+/// # }
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn subdomain_graph(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    adjwgt: Option<&[Idx]>,
+    part: &[Idx],
+    nparts: Idx,
+) -> Result<(Vec<Idx>, Vec<Idx>, Vec<Idx>)> {
+    check_part(xadj, adjncy, part)?;
+    check_labels(part, nparts)?;
+    if let Some(adjwgt) = adjwgt {
+        NewGraphError::check_len("adjwgt", adjncy.len(), adjwgt.len())?;
+        NewGraphError::check_positive("adjwgt", adjwgt)?;
+    }
+
+    let mut arcs = Vec::new();
+    for (v, w) in xadj.windows(2).enumerate() {
+        for i in w[0] as usize..w[1] as usize {
+            let (p, q) = (part[v], part[adjncy[i] as usize]);
+            if p != q {
+                arcs.push((p, q, adjwgt.map_or(1, |adjwgt| adjwgt[i])));
+            }
+        }
+    }
+    let graph = csr::from_arcs(nparts, arcs, |_, a, b| {
+        a.checked_add(b).ok_or(NewGraphError::TooLarge)
+    })?;
+    Ok(graph)
+}
+
+/// Computes the degree of each part in the subdomain graph of a partition,
+/// that is, the number of other parts it is adjacent to.
+///
+/// This is the degree of each vertex of [`subdomain_graph`], computed without
+/// building it: vertices are bucketed by part with [`invert_partition`], then
+/// the neighbors of each part are counted with a marker array.  This runs in
+/// `O(V + E)` time and allocates `O(V + nparts)` memory.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `xadj` and `adjncy` are
+/// not a valid graph structure, if the length of `part` is not the number of
+/// vertices, if `nparts` is not strictly positive, or if some labels are not
+/// within zero and `nparts`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::{subdomain_degrees, subdomain_graph};
+///
+/// // A star whose center 0 is adjacent to three parts.
+/// let xadj = &[0, 4, 5, 6, 7, 8];
+/// let adjncy = &[1, 2, 3, 4, 0, 0, 0, 0];
+/// let part = &[0, 1, 2, 3, 3];
+/// assert_eq!(subdomain_degrees(xadj, adjncy, part, 5)?, [3, 1, 1, 1, 0]);
+///
+/// let (sxadj, _, _) = subdomain_graph(xadj, adjncy, None, part, 5)?;
+/// let degrees: Vec<_> = sxadj.windows(2).map(|w| w[1] - w[0]).collect();
+/// assert_eq!(degrees, [3, 1, 1, 1, 0]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn subdomain_degrees(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    part: &[Idx],
+    nparts: Idx,
+) -> Result<Vec<Idx>> {
+    check_part(xadj, adjncy, part)?;
+    let index = invert_partition(part, nparts)?;
+    let nparts = nparts as usize;
+
+    // marker[q] is the last part that found part q among its neighbors.
+    let mut marker = vec![usize::MAX; nparts];
+    let mut degrees = vec![0; nparts];
+    for p in 0..nparts {
+        marker[p] = p;
+        for v in index.vertices_of(p as Idx) {
+            let v = *v as usize;
+            for u in &adjncy[xadj[v] as usize..xadj[v + 1] as usize] {
+                let q = part[*u as usize] as usize;
+                if marker[q] != p {
+                    marker[q] = p;
+                    degrees[p] += 1;
+                }
+            }
+        }
+    }
+    Ok(degrees)
+}
+
+/// Computes the maximum degree of the subdomain graph of a partition, that is,
+/// the largest number of parts a part is adjacent to.
+///
+/// This is the objective of [`crate::option::MinConn`].  See
+/// [`subdomain_degrees`] for the degree of each part.
+///
+/// # Errors
+///
+/// This function returns the same errors as [`subdomain_degrees`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::option::MinConn;
+/// use metis::quality::max_subdomain_degree;
+/// use metis::GraphBuilder;
+///
+/// // A triangulated 24x24 grid, where parts tend to have many neighbors.
+/// let n = 24;
+/// let mut builder = GraphBuilder::new(n * n)?;
+/// for i in 0..n {
+///     for j in 0..n - 1 {
+///         builder.add_edge(n * i + j, n * i + j + 1)?;
+///         builder.add_edge(n * j + i, n * j + i + n)?;
+///         if i < n - 1 {
+///             builder.add_edge(n * i + j, n * i + j + n + 1)?;
+///         }
+///     }
+/// }
+/// let graph = builder.build(1, 16)?;
+///
+/// let mut part = vec![0; (n * n) as usize];
+/// graph.as_graph().part_kway(&mut part)?;
+/// let default = max_subdomain_degree(graph.xadj(), graph.adjncy(), &part, 16)?;
+///
+/// graph.as_graph().set_option(MinConn(true)).part_kway(&mut part)?;
+/// let min_conn = max_subdomain_degree(graph.xadj(), graph.adjncy(), &part, 16)?;
+/// assert!(min_conn < default);
+/// # Ok(())
+/// # }
+/// ```
+pub fn max_subdomain_degree(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    part: &[Idx],
+    nparts: Idx,
+) -> Result<Idx> {
+    let degrees = subdomain_degrees(xadj, adjncy, part, nparts)?;
+    Ok(degrees.into_iter().max().unwrap_or(0))
+}
+
+/// Counts the connected components of each part of a partition.
+///
+/// A part is contiguous when it has exactly one component.  Empty parts have
+/// none.  METIS only tries to make parts contiguous with
+/// [`crate::option::Contig`], and not on disconnected graphs.
+///
+/// Components are found with a depth-first search that only follows edges
+/// within a part, using an explicit stack instead of recursion.  This runs in
+/// `O(V + E)` time.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `xadj` and `adjncy` are
+/// not a valid graph structure, if the length of `part` is not the number of
+/// vertices, if `nparts` is not strictly positive, or if some labels are not
+/// within zero and `nparts`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::option::Contig;
+/// use metis::quality::contiguity;
+/// use metis::GraphBuilder;
+///
+/// // 0 - 1 - 2 - 3 - 4 - 5, with parts interleaved.
+/// let xadj = &[0, 1, 3, 5, 7, 9, 10];
+/// let adjncy = &[1, 0, 2, 1, 3, 2, 4, 3, 5, 4];
+/// assert_eq!(contiguity(xadj, adjncy, &[0, 0, 1, 1, 0, 0], 3)?, [2, 1, 0]);
+/// assert_eq!(contiguity(xadj, adjncy, &[0, 1, 0, 1, 0, 1], 2)?, [3, 3]);
+///
+/// // A 10x10 grid, partitioned with contiguous parts.
+/// let mut builder = GraphBuilder::new(100)?;
+/// for i in 0..10 {
+///     for j in 0..9 {
+///         builder.add_edge(10 * i + j, 10 * i + j + 1)?;
+///         builder.add_edge(10 * j + i, 10 * j + i + 10)?;
+///     }
+/// }
+/// let graph = builder.build(1, 6)?;
+/// let mut part = [0; 100];
+/// graph.as_graph().set_option(Contig(true)).part_kway(&mut part)?;
+/// assert_eq!(contiguity(graph.xadj(), graph.adjncy(), &part, 6)?, [1; 6]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn contiguity(xadj: &[Idx], adjncy: &[Idx], part: &[Idx], nparts: Idx) -> Result<Vec<usize>> {
+    check_part(xadj, adjncy, part)?;
+    check_labels(part, nparts)?;
+    let mut components = vec![0; nparts as usize];
+    for root in label_components(xadj, adjncy, part).1 {
+        components[part[root] as usize] += 1;
+    }
+    Ok(components)
+}
+
+/// Finds the connected components of each part of a partition.
+///
+/// Returns the component of each vertex, and the first vertex of each
+/// component.  Components are numbered in the order of their first vertex.
+/// Arguments must have been checked by [`check_part`].
+pub(crate) fn label_components(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    part: &[Idx],
+) -> (Vec<usize>, Vec<usize>) {
+    let nvtxs = part.len();
+    let mut component = vec![usize::MAX; nvtxs];
+    let mut roots = Vec::new();
+    let mut stack = Vec::new();
+    for root in 0..nvtxs {
+        if component[root] != usize::MAX {
+            continue;
+        }
+        let c = roots.len();
+        roots.push(root);
+        component[root] = c;
+        stack.push(root);
+        while let Some(v) = stack.pop() {
+            for u in &adjncy[xadj[v] as usize..xadj[v + 1] as usize] {
+                let u = *u as usize;
+                if component[u] == usize::MAX && part[u] == part[root] {
+                    component[u] = c;
+                    stack.push(u);
+                }
+            }
+        }
+    }
+    (component, roots)
+}
+
+/// A summary of the quality of a partition.
+///
+/// Returned by [`report`].  Its [`Display`](fmt::Display) implementation
+/// prints one metric per line.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartitionQuality {
+    /// The number of parts.
+    pub nparts: Idx,
+
+    /// The edge-cut, see [`edge_cut`].
+    pub edge_cut: i64,
+
+    /// The total communication volume, see [`communication_volume`].
+    pub communication_volume: i64,
+
+    /// The load imbalance of each constraint, see [`imbalance`].
+    #[cfg_attr(feature = "serde", serde(with = "reals_as_f64"))]
+    pub imbalance: Vec<Real>,
+
+    /// The weight of the lightest part, for each constraint.
+    pub min_part_weight: Vec<i64>,
+
+    /// The weight of the heaviest part, for each constraint.
+    pub max_part_weight: Vec<i64>,
+
+    /// The maximum degree of the subdomain graph, see
+    /// [`max_subdomain_degree`].
+    pub max_subdomain_degree: Idx,
+
+    /// The number of parts with more than one connected component, see
+    /// [`contiguity`].
+    pub non_contiguous_parts: usize,
+
+    /// The number of parts without vertices.
+    pub empty_parts: usize,
+
+    /// The objective METIS minimized and the value it returned, when the
+    /// partition comes from [`Graph::part_kway_with_stats`] or
+    /// [`Graph::part_recursive_with_stats`].
+    pub objective: Option<(ObjType, Idx)>,
+}
+
+/// (De)serializes reals as `f64`, so that the serialized form does not depend
+/// on the size of [`Real`].
+#[cfg(feature = "serde")]
+mod reals_as_f64 {
+    use crate::Real;
+    use serde::Deserialize as _;
+
+    pub fn serialize<S: serde::Serializer>(values: &[Real], s: S) -> Result<S::Ok, S::Error> {
+        s.collect_seq(values.iter().map(|x| f64::from(*x)))
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Vec<Real>, D::Error> {
+        let values = Vec::<f64>::deserialize(d)?;
+        Ok(values.into_iter().map(|x| x as Real).collect())
+    }
+}
+
+impl fmt::Display for PartitionQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |values: Vec<String>| values.join(" ");
+        writeln!(f, "Partition into {} parts:", self.nparts)?;
+        writeln!(f, " - edge-cut: {}", self.edge_cut)?;
+        writeln!(f, " - communication volume: {}", self.communication_volume)?;
+        let imbalance = self.imbalance.iter().map(|x| format!("{x:.3}"));
+        writeln!(f, " - imbalance: {}", join(imbalance.collect()))?;
+        let weights = self.min_part_weight.iter().zip(&self.max_part_weight);
+        let weights = weights.map(|(min, max)| format!("{min}..={max}"));
+        writeln!(f, " - part weights: {}", join(weights.collect()))?;
+        writeln!(f, " - max subdomain degree: {}", self.max_subdomain_degree)?;
+        writeln!(f, " - non-contiguous parts: {}", self.non_contiguous_parts)?;
+        writeln!(f, " - empty parts: {}", self.empty_parts)?;
+        match self.objective {
+            Some((ObjType::Cut, objval)) => writeln!(f, " - objective (cut): {objval}"),
+            Some((ObjType::Vol, objval)) => writeln!(f, " - objective (volume): {objval}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Computes the quality metrics of a partition of `graph`.
+///
+/// The metrics use the number of parts, the weights and the target partition
+/// weights of `graph`, as METIS would.  The options of `graph` are ignored.
+/// With [`Graph::new_fortran`], part labels start at one.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if the length of `part` is
+/// not the number of vertices, or if some labels are not within zero and the
+/// number of parts.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::report;
+/// use metis::Graph;
+///
+/// // 0 - 1 | 2 - 3 | 4 - 5
+/// let xadj = &[0, 1, 3, 5, 7, 9, 10];
+/// let adjncy = &[1, 0, 2, 1, 3, 2, 4, 3, 5, 4];
+/// let graph = Graph::new(1, 3, xadj, adjncy)?;
+/// let part = [0, 0, 1, 1, 2, 2];
+///
+/// let quality = report(&graph, &part)?;
+/// assert_eq!(quality.edge_cut, 2);
+/// assert_eq!(quality.max_subdomain_degree, 2);
+/// assert_eq!(
+///     quality.to_string(),
+///     "\
+/// Partition into 3 parts:
+///  - edge-cut: 2
+///  - communication volume: 4
+///  - imbalance: 1.000
+///  - part weights: 2..=2
+///  - max subdomain degree: 2
+///  - non-contiguous parts: 0
+///  - empty parts: 0
+/// ",
+/// );
+///
+/// # #[cfg(feature = "serde")]
+/// # {
+/// let json = serde_json::to_string(&quality).unwrap();
+/// assert_eq!(
+///     json,
+///     r#"{"nparts":3,"edge_cut":2,"communication_volume":4,"imbalance":[1.0],"#.to_owned()
+///         + r#""min_part_weight":[2],"max_part_weight":[2],"max_subdomain_degree":2,"#
+///         + r#""non_contiguous_parts":0,"empty_parts":0,"objective":null}"#,
+/// );
+/// let parsed: metis::quality::PartitionQuality = serde_json::from_str(&json).unwrap();
+/// assert_eq!(parsed, quality);
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub fn report(graph: &Graph<'_>, part: &[Idx]) -> Result<PartitionQuality> {
+    if graph.numbering == Numbering::Fortran {
+        let xadj = zero_based(graph.xadj);
+        let adjncy = zero_based(graph.adjncy);
+        let part = zero_based(part);
+        let graph = Graph {
+            xadj: &xadj,
+            adjncy: &adjncy,
+            numbering: Numbering::C,
+            ..*graph
+        };
+        return report(&graph, &part);
+    }
+
+    let (xadj, adjncy, nparts) = (graph.xadj, graph.adjncy, graph.nparts);
+    let weights = part_weights(graph.ncon, graph.vwgt, part, nparts)?;
+    let components = contiguity(xadj, adjncy, part, nparts)?;
+    Ok(PartitionQuality {
+        nparts,
+        edge_cut: edge_cut(xadj, adjncy, graph.adjwgt, part)?,
+        communication_volume: communication_volume(xadj, adjncy, graph.vsize, part)?,
+        imbalance: imbalance(graph.ncon, graph.vwgt, graph.tpwgts, part, nparts)?,
+        min_part_weight: weights.min(),
+        max_part_weight: weights.max(),
+        max_subdomain_degree: max_subdomain_degree(xadj, adjncy, part, nparts)?,
+        non_contiguous_parts: components.iter().filter(|c| **c > 1).count(),
+        empty_parts: components.iter().filter(|c| **c == 0).count(),
+        objective: None,
+    })
+}
+
+impl Graph<'_> {
+    /// Partition the graph using multilevel recursive bisection, and compute
+    /// the quality of the partition.
+    ///
+    /// See [`Graph::part_kway_with_stats`].  Recursive bisection always
+    /// minimizes the edge-cut.
+    pub fn part_recursive_with_stats(&mut self, part: &mut [Idx]) -> Result<PartitionQuality> {
+        let objval = self.try_part_recursive(part)?;
+        let mut quality = report(self, part)?;
+        quality.objective = Some((ObjType::Cut, objval));
+        Ok(quality)
+    }
+
+    /// Partition the graph using multilevel k-way partitioning, and compute
+    /// the quality of the partition.
+    ///
+    /// This is [`Graph::try_part_kway`] followed by [`report`], with the
+    /// objective set to the one METIS minimized and the value it returned.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [`Graph::try_part_kway`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::option::ObjType;
+    /// use metis::Graph;
+    ///
+    /// // A ring of six vertices.
+    /// let xadj = &[0, 2, 4, 6, 8, 10, 12];
+    /// let adjncy = &[1, 5, 0, 2, 1, 3, 2, 4, 3, 5, 0, 4];
+    /// let mut part = [0; 6];
+    ///
+    /// let mut graph = Graph::new(1, 3, xadj, adjncy)?;
+    /// let stats = graph.part_kway_with_stats(&mut part)?;
+    /// let (objtype, objval) = stats.objective.unwrap();
+    /// assert_eq!(objtype, ObjType::Cut);
+    /// assert_eq!(stats.edge_cut, i64::from(objval));
+    ///
+    /// let stats = graph.set_option(ObjType::Vol).part_kway_with_stats(&mut part)?;
+    /// let (objtype, objval) = stats.objective.unwrap();
+    /// assert_eq!(objtype, ObjType::Vol);
+    /// assert_eq!(stats.communication_volume, i64::from(objval));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn part_kway_with_stats(&mut self, part: &mut [Idx]) -> Result<PartitionQuality> {
+        let objval = self.try_part_kway(part)?;
+        let objtype = if self.options[ObjType::INDEX] == ObjType::Vol.value() {
+            ObjType::Vol
+        } else {
+            ObjType::Cut
+        };
+        let mut quality = report(self, part)?;
+        quality.objective = Some((objtype, objval));
+        Ok(quality)
+    }
+}
+
+/// How vertices moved from one partition to another.
+///
+/// Returned by [`compare`].  Parts are identified by their labels, so two
+/// partitions that only differ by the naming of their parts look completely
+/// different.  [`PartitionDiff::best_relabeling`] finds the naming that
+/// hides this.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionDiff {
+    old_nparts: usize,
+    new_nparts: usize,
+    counts: Vec<usize>,
+    weights: Vec<i64>,
+}
+
+impl PartitionDiff {
+    /// The number of parts of the old partition.
+    pub fn old_nparts(&self) -> usize {
+        self.old_nparts
+    }
+
+    /// The number of parts of the new partition.
+    pub fn new_nparts(&self) -> usize {
+        self.new_nparts
+    }
+
+    /// The number of vertices that moved from part `from` in the old
+    /// partition to part `to` in the new one.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `from` or `to` is out of bounds.
+    pub fn migration_count(&self, from: usize, to: usize) -> usize {
+        assert!(to < self.new_nparts);
+        self.counts[from * self.new_nparts + to]
+    }
+
+    /// The weight of the vertices that moved from part `from` in the old
+    /// partition to part `to` in the new one.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `from` or `to` is out of bounds.
+    pub fn migration_weight(&self, from: usize, to: usize) -> i64 {
+        assert!(to < self.new_nparts);
+        self.weights[from * self.new_nparts + to]
+    }
+
+    /// The number of vertices whose label changed.
+    pub fn moved(&self) -> usize {
+        let total: usize = self.counts.iter().sum();
+        total - self.kept(&self.counts)
+    }
+
+    /// The weight of the vertices whose label changed.
+    pub fn moved_weight(&self) -> i64 {
+        let total: i64 = self.weights.iter().sum();
+        total - self.kept(&self.weights)
+    }
+
+    /// Sums the diagonal of a migration matrix.
+    fn kept<T: Copy + std::iter::Sum>(&self, matrix: &[T]) -> T {
+        (0..usize::min(self.old_nparts, self.new_nparts))
+            .map(|p| matrix[p * self.new_nparts + p])
+            .sum()
+    }
+
+    /// Finds the relabeling of the new partition that moves the least weight.
+    ///
+    /// Returns the new label of each part of the new partition.  Labels are
+    /// a permutation of `0..max(old_nparts, new_nparts)`, chosen so that the
+    /// weight that stays in place is maximal.  This is an assignment problem,
+    /// solved with the Hungarian algorithm in `O(n^3)` time, where `n` is the
+    /// largest number of parts.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::quality::compare;
+    ///
+    /// let old = &[0, 0, 1, 1, 2, 2];
+    /// let new = &[2, 2, 0, 0, 1, 1];
+    /// let diff = compare(old, 3, new, 3, None)?;
+    /// assert_eq!(diff.moved(), 6);
+    /// assert_eq!(diff.migration_count(0, 2), 2);
+    ///
+    /// // The new partition is the old one with parts renamed.
+    /// let relabeling = diff.best_relabeling();
+    /// assert_eq!(relabeling, [1, 2, 0]);
+    /// let relabeled: Vec<_> = new.iter().map(|p| relabeling[*p as usize]).collect();
+    /// assert_eq!(relabeled, old);
+    /// assert_eq!(compare(old, 3, &relabeled, 3, None)?.moved(), 0);
+    ///
+    /// // Part 3 gets a label of its own.
+    /// let new = &[1, 1, 3, 0, 0, 0];
+    /// let relabeling = compare(old, 3, new, 4, None)?.best_relabeling();
+    /// assert_eq!(relabeling, [2, 0, 3, 1]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn best_relabeling(&self) -> Vec<Idx> {
+        let n = usize::max(self.old_nparts, self.new_nparts);
+        let max = self.weights.iter().copied().max().unwrap_or(0);
+        let cost = |old: usize, new: usize| {
+            if old < self.old_nparts && new < self.new_nparts {
+                max - self.weights[old * self.new_nparts + new]
+            } else {
+                max
+            }
+        };
+
+        // Hungarian algorithm, with potentials.  Rows are old parts, columns
+        // are new parts, and both are numbered from one, zero being a
+        // sentinel column.  row[j] is the row assigned to column j.
+        let mut u = vec![0; n + 1];
+        let mut v = vec![0; n + 1];
+        let mut row = vec![0; n + 1];
+        let mut way = vec![0; n + 1];
+        for i in 1..=n {
+            row[0] = i;
+            let mut j0 = 0;
+            let mut minv = vec![i64::MAX; n + 1];
+            let mut used = vec![false; n + 1];
+            loop {
+                used[j0] = true;
+                let i0 = row[j0];
+                let mut delta = i64::MAX;
+                let mut j1 = 0;
+                for j in 1..=n {
+                    if used[j] {
+                        continue;
+                    }
+                    let reduced = cost(i0 - 1, j - 1) - u[i0] - v[j];
+                    if reduced < minv[j] {
+                        minv[j] = reduced;
+                        way[j] = j0;
+                    }
+                    if minv[j] < delta {
+                        delta = minv[j];
+                        j1 = j;
+                    }
+                }
+                for j in 0..=n {
+                    if used[j] {
+                        u[row[j]] += delta;
+                        v[j] -= delta;
+                    } else {
+                        minv[j] -= delta;
+                    }
+                }
+                j0 = j1;
+                if row[j0] == 0 {
+                    break;
+                }
+            }
+            while j0 != 0 {
+                let j1 = way[j0];
+                row[j0] = row[j1];
+                j0 = j1;
+            }
+        }
+        (1..=self.new_nparts).map(|j| row[j] as Idx - 1).collect()
+    }
+}
+
+/// Compares two partitions of the same graph, for example before and after a
+/// repartitioning.
+///
+/// The old partition has `old_nparts` parts and the new one `new_nparts`, so
+/// that the migration matrix has `old_nparts * new_nparts` entries.
+///
+/// Vertices have a weight of one when `vwgt` is `None`, otherwise `vwgt` has
+/// one weight per vertex, e.g. the size of the data to migrate.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `old`, `new` and `vwgt`
+/// do not have the same length, if `old_nparts` or `new_nparts` is not
+/// strictly positive, if some labels are not within zero and their number of
+/// parts, or if some weights are negative.  The source of the error is
+/// [`crate::NewGraphError::TooLarge`] if the migration matrix does not fit in
+/// memory.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::compare;
+///
+/// let old = &[0, 0, 0, 1, 1, 1];
+/// let new = &[0, 0, 1, 1, 1, 2];
+/// let vwgt = &[1, 1, 5, 1, 1, 3];
+/// let diff = compare(old, 2, new, 3, Some(vwgt))?;
+/// assert_eq!((diff.old_nparts(), diff.new_nparts()), (2, 3));
+/// assert_eq!(diff.moved(), 2);
+/// assert_eq!(diff.moved_weight(), 8);
+/// assert_eq!(diff.migration_count(0, 1), 1);
+/// assert_eq!(diff.migration_weight(1, 2), 3);
+///
+/// // Labels must be within their number of parts.
+/// assert!(compare(old, 2, new, 2, None).is_err());
+///
+/// // The migration matrix is too large.
+/// use metis::{Idx, NewGraphError};
+/// use std::error::Error as _;
+/// let err = compare(old, Idx::MAX, new, Idx::MAX, None).unwrap_err();
+/// assert!(matches!(
+///     err.source().unwrap().downcast_ref::<NewGraphError>(),
+///     Some(NewGraphError::TooLarge),
+/// ));
+/// # Ok(())
+/// # }
+/// ```
+pub fn compare(
+    old: &[Idx],
+    old_nparts: Idx,
+    new: &[Idx],
+    new_nparts: Idx,
+    vwgt: Option<&[Idx]>,
+) -> Result<PartitionDiff> {
+    check_labels(old, old_nparts)?;
+    check_partition(old.len(), Some(new_nparts), new)?;
+    check_vwgt(1, vwgt, old.len())?;
+    let (old_nparts, new_nparts) = (old_nparts as usize, new_nparts as usize);
+    let len = old_nparts
+        .checked_mul(new_nparts)
+        .filter(|len| *len <= isize::MAX as usize / mem::size_of::<i64>())
+        .ok_or(NewGraphError::TooLarge)?;
+
+    let mut counts = vec![0; len];
+    let mut weights = vec![0; len];
+    for (v, (p, q)) in old.iter().zip(new).enumerate() {
+        let i = *p as usize * new_nparts + *q as usize;
+        counts[i] += 1;
+        weights[i] += vwgt.map_or(1, |vwgt| i64::from(vwgt[v]));
+    }
+    Ok(PartitionDiff {
+        old_nparts,
+        new_nparts,
+        counts,
+        weights,
+    })
+}
+
+/// How [`relabel`] orders parts.
+///
+/// In all cases, empty parts are dropped and the remaining ones are numbered
+/// from zero without gaps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelabelOrder {
+    /// Keep the order of the original labels.
+    Dense,
+
+    /// Number parts from the largest to the smallest, by number of vertices.
+    /// Parts of the same size keep the order of their original labels.
+    BySizeDesc,
+
+    /// Number parts in the order in which they first appear in the partition.
+    ByFirstOccurrence,
+}
+
+/// Renames the parts of a partition, in place.
+///
+/// Returns the new label of each of the `nparts` original parts, or `-1` for
+/// parts that are empty.  This takes a single pass over `part` to count
+/// vertices, a sort of the parts, and another pass to rename them.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`], and leaves `part` as is, if
+/// `nparts` is not strictly positive, or if some labels are not within zero
+/// and `nparts`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::{relabel, RelabelOrder};
+///
+/// // Parts 0 and 3 are empty.
+/// let part = [4, 2, 2, 5, 1, 5, 5];
+///
+/// let mut dense = part;
+/// let map = relabel(&mut dense, 6, RelabelOrder::Dense)?;
+/// assert_eq!(map, [-1, 0, 1, -1, 2, 3]);
+/// assert_eq!(dense, [2, 1, 1, 3, 0, 3, 3]);
+///
+/// let mut by_size = part;
+/// let map = relabel(&mut by_size, 6, RelabelOrder::BySizeDesc)?;
+/// assert_eq!(map, [-1, 2, 1, -1, 3, 0]);
+/// assert_eq!(by_size, [3, 1, 1, 0, 2, 0, 0]);
+///
+/// let mut by_occurrence = part;
+/// let map = relabel(&mut by_occurrence, 6, RelabelOrder::ByFirstOccurrence)?;
+/// assert_eq!(map, [-1, 3, 1, -1, 0, 2]);
+/// assert_eq!(by_occurrence, [0, 1, 1, 2, 3, 2, 2]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn relabel(part: &mut [Idx], nparts: Idx, order: RelabelOrder) -> Result<Vec<Idx>> {
+    check_labels(part, nparts)?;
+    let nparts = nparts as usize;
+
+    let mut sizes = vec![0_usize; nparts];
+    let mut first = vec![usize::MAX; nparts];
+    for (v, p) in part.iter().enumerate() {
+        let p = *p as usize;
+        sizes[p] += 1;
+        first[p] = usize::min(first[p], v);
+    }
+
+    let mut parts: Vec<usize> = (0..nparts).filter(|p| sizes[*p] != 0).collect();
+    match order {
+        RelabelOrder::Dense => {}
+        RelabelOrder::BySizeDesc => parts.sort_by_key(|p| std::cmp::Reverse(sizes[*p])),
+        RelabelOrder::ByFirstOccurrence => parts.sort_unstable_by_key(|p| first[*p]),
+    }
+
+    let mut map = vec![-1; nparts];
+    for (new, old) in parts.into_iter().enumerate() {
+        map[old] = new as Idx;
+    }
+    for p in part {
+        *p = map[*p as usize];
+    }
+    Ok(map)
+}
+
+/// The vertices of each part of a partition, in CSR format.
+///
+/// The vertices of part `p` are `pind[pptr[p]..pptr[p+1]]`, in increasing
+/// order.  Returned by [`invert_partition`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartIndex {
+    pptr: Vec<Idx>,
+    pind: Vec<Idx>,
+}
+
+impl PartIndex {
+    /// The number of parts.
+    pub fn nparts(&self) -> Idx {
+        self.pptr.len() as Idx - 1
+    }
+
+    /// The offsets of each part in [`PartIndex::pind`], plus its length at
+    /// the end.
+    pub fn pptr(&self) -> &[Idx] {
+        &self.pptr
+    }
+
+    /// The vertices, grouped by part.
+    pub fn pind(&self) -> &[Idx] {
+        &self.pind
+    }
+
+    /// The vertices of part `p`, in increasing order.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `p` is not within zero and the number of parts.
+    pub fn vertices_of(&self, p: Idx) -> &[Idx] {
+        let p = p as usize;
+        &self.pind[self.pptr[p] as usize..self.pptr[p + 1] as usize]
+    }
+
+    /// The number of vertices of part `p`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `p` is not within zero and the number of parts.
+    pub fn len_of(&self, p: Idx) -> usize {
+        self.vertices_of(p).len()
+    }
+
+    /// The parts that have vertices, with their vertices, in increasing order
+    /// of part.
+    pub fn non_empty(&self) -> impl Iterator<Item = (Idx, &[Idx])> + '_ {
+        (0..self.nparts())
+            .map(|p| (p, self.vertices_of(p)))
+            .filter(|(_, vertices)| !vertices.is_empty())
+    }
+}
+
+/// Groups the vertices of a partition by part, see [`PartIndex`].
+///
+/// This is a counting sort, which runs in `O(V + nparts)` time.  It is stable:
+/// vertices of the same part keep their relative order.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `nparts` is not strictly
+/// positive, or if some labels are not within zero and `nparts`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::invert_partition;
+///
+/// let index = invert_partition(&[2, 0, 2, 2, 0, 3], 4)?;
+/// assert_eq!(index.pptr(), [0, 2, 2, 5, 6]);
+/// assert_eq!(index.pind(), [1, 4, 0, 2, 3, 5]);
+/// assert_eq!(index.vertices_of(2), [0, 2, 3]);
+/// assert_eq!(index.len_of(1), 0);
+///
+/// let parts: Vec<_> = index.non_empty().map(|(p, _)| p).collect();
+/// assert_eq!(parts, [0, 2, 3]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn invert_partition(part: &[Idx], nparts: Idx) -> Result<PartIndex> {
+    check_labels(part, nparts)?;
+    Idx::try_from(part.len()).map_err(|_| NewGraphError::TooLarge)?;
+    let mut pptr = vec![0; nparts as usize + 1];
+    for p in part {
+        pptr[*p as usize + 1] += 1;
+    }
+    for p in 1..pptr.len() {
+        pptr[p] += pptr[p - 1];
+    }
+    let mut next = pptr.clone();
+    let mut pind = vec![0; part.len()];
+    for (v, p) in part.iter().enumerate() {
+        let next = &mut next[*p as usize];
+        pind[*next as usize] = v as Idx;
+        *next += 1;
+    }
+    Ok(PartIndex { pptr, pind })
+}
+
+/// Computes the permutation that groups vertices by part, for example to
+/// reorder a matrix so that the rows of each part are contiguous.
+///
+/// Vertices are sorted by part, and keep their original order within a part.
+/// Returns the permutation, with [`Permutation::perm`] mapping new indices to
+/// old ones as [`crate::csr::permute`] expects, and the offsets of each part
+/// in the new numbering: part `p` spans the rows `ranges[p]..ranges[p+1]`.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `nparts` is not strictly
+/// positive, or if some labels are not within zero and `nparts`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::partition_permutation;
+///
+/// // 0 - 1 - 2 - 3 - 4, with parts interleaved.
+/// let xadj = &[0, 1, 3, 5, 7, 8];
+/// let adjncy = &[1, 0, 2, 1, 3, 2, 4, 3];
+/// let part = [1, 0, 1, 2, 0];
+///
+/// let (permutation, ranges) = partition_permutation(&part, 3)?;
+/// assert_eq!(permutation.perm(), [1, 4, 0, 2, 3]);
+/// assert_eq!(ranges, [0, 2, 4, 5]);
+/// let (new_xadj, new_adjncy, _) =
+///     metis::csr::permute(xadj, adjncy, None, permutation.perm())?;
+///
+/// // Parts are contiguous in the permuted graph.
+/// let new_part = permutation.apply_to(&part);
+/// assert!(new_part.windows(2).all(|w| w[0] <= w[1]));
+/// for p in 0..3 {
+///     let rows = ranges[p] as usize..ranges[p + 1] as usize;
+///     assert!(new_part[rows].iter().all(|q| *q == p as metis::Idx));
+/// }
+///
+/// // The permuted graph has the same edge-cut.
+/// let cut = |xadj, adjncy, part| metis::quality::edge_cut(xadj, adjncy, None, part);
+/// assert_eq!(cut(&new_xadj, &new_adjncy, &new_part)?, cut(xadj, adjncy, &part)?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn partition_permutation(part: &[Idx], nparts: Idx) -> Result<(Permutation, Vec<Idx>)> {
+    let PartIndex { pptr, pind } = invert_partition(part, nparts)?;
+    let permutation =
+        Permutation::from_perm(pind).expect("vertices grouped by part are a permutation");
+    Ok((permutation, pptr))
+}
+
+/// The quality of a vertex separator.
+///
+/// Returned by [`separator_stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeparatorStats {
+    /// The number of vertices of each side, then of the separator.
+    pub sizes: [usize; 3],
+
+    /// The weight of each side, then of the separator.
+    pub weights: [i64; 3],
+
+    /// The weight of the heaviest side over the mean weight of the two sides,
+    /// 1.0 for a perfect balance.  Zero when both sides are empty.
+    pub balance: Real,
+
+    /// An edge between both sides, if any.
+    pub crossing_edge: Option<(Idx, Idx)>,
+}
+
+impl SeparatorStats {
+    /// Whether the separator separates the two sides, that is, whether no
+    /// edge goes from one side to the other.
+    pub fn is_separator(&self) -> bool {
+        self.crossing_edge.is_none()
+    }
+}
+
+/// Computes the quality of a vertex separator.
+///
+/// `sep_part` labels the vertices of both sides with `0` and `1`, and those of
+/// the separator with `2`, as `METIS_ComputeVertexSeparator` does.  Vertices
+/// have a weight of one when `vwgt` is `None`.
+///
+/// The most important check is whether the separator is valid: removing it
+/// must disconnect the two sides, see [`SeparatorStats::is_separator`].
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`] if `xadj` and `adjncy` are
+/// not a valid graph structure, if the length of `vwgt` or `sep_part` is not
+/// the number of vertices, if some weights are negative, or if some labels are
+/// not within zero and two.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::quality::separator_stats;
+///
+/// // 0 - 1 - 2 - 3 - 4
+/// let xadj = &[0, 1, 3, 5, 7, 8];
+/// let adjncy = &[1, 0, 2, 1, 3, 2, 4, 3];
+///
+/// let stats = separator_stats(xadj, adjncy, None, &[0, 0, 2, 1, 1])?;
+/// assert!(stats.is_separator());
+/// assert_eq!(stats.sizes, [2, 2, 1]);
+/// assert_eq!(stats.balance, 1.0);
+///
+/// let vwgt = &[1, 1, 1, 1, 3];
+/// let stats = separator_stats(xadj, adjncy, Some(vwgt), &[0, 0, 2, 1, 1])?;
+/// assert_eq!(stats.weights, [2, 4, 1]);
+/// assert_eq!(stats.balance, 4.0 / 3.0);
+///
+/// // Vertices 2 and 3 are adjacent but on different sides.
+/// let stats = separator_stats(xadj, adjncy, None, &[0, 2, 0, 1, 1])?;
+/// assert!(!stats.is_separator());
+/// assert_eq!(stats.crossing_edge, Some((2, 3)));
+/// # Ok(())
+/// # }
+/// ```
+pub fn separator_stats(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    vwgt: Option<&[Idx]>,
+    sep_part: &[Idx],
+) -> Result<SeparatorStats> {
+    let nvtxs = check_part(xadj, adjncy, sep_part)?;
+    check_labels(sep_part, 3)?;
+    check_vwgt(1, vwgt, nvtxs)?;
+
+    let mut sizes = [0; 3];
+    let mut weights = [0; 3];
+    let mut crossing_edge = None;
+    for (v, w) in xadj.windows(2).enumerate() {
+        let p = sep_part[v] as usize;
+        sizes[p] += 1;
+        weights[p] += vwgt.map_or(1, |vwgt| i64::from(vwgt[v]));
+        if p == 2 || crossing_edge.is_some() {
+            continue;
+        }
+        let crossing = adjncy[w[0] as usize..w[1] as usize]
+            .iter()
+            .find(|u| sep_part[**u as usize] as usize == 1 - p);
+        if let Some(u) = crossing {
+            crossing_edge = Some((v as Idx, *u));
+        }
+    }
+    let sides = weights[0] + weights[1];
+    let balance = if sides == 0 {
+        0.0
+    } else {
+        (2 * i64::max(weights[0], weights[1])) as f64 / sides as f64
+    };
+    Ok(SeparatorStats {
+        sizes,
+        weights,
+        balance: balance as Real,
+        crossing_edge,
+    })
+}
@@ -0,0 +1,410 @@
+//! Post-passes that improve existing partitions, written in Rust.
+//!
+//! These functions do not call METIS.  They take a partition, e.g. one
+//! computed by METIS, and move a few vertices to fix a specific defect.
+//! Graphs are given in the format of [`crate::Graph::new`].
+
+use crate::quality;
+use crate::quality::check_labels;
+use crate::quality::check_part;
+use crate::quality::check_vwgt;
+use crate::quality::label_components;
+use crate::quality::sum_weights;
+use crate::Idx;
+use crate::NewGraphError;
+use crate::Real;
+use crate::Result;
+use std::cmp;
+
+/// What [`rebalance`] did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebalanceReport {
+    /// The number of vertices moved.
+    pub moves: usize,
+
+    /// The edge-cut before rebalancing.
+    pub edge_cut_before: i64,
+
+    /// The edge-cut after rebalancing.
+    pub edge_cut_after: i64,
+
+    /// The load imbalance of each constraint before rebalancing, see
+    /// [`quality::imbalance`].
+    pub imbalance_before: Vec<Real>,
+
+    /// The load imbalance of each constraint after rebalancing.
+    pub imbalance_after: Vec<Real>,
+}
+
+/// Moves boundary vertices out of overweight parts, until the partition is
+/// balanced.
+///
+/// A part is overweight when, for some constraint `j`, its weight exceeds
+/// `ubvec[j]` times the mean part weight.  The number of constraints is the
+/// length of `ubvec`, and `vwgt` is laid out as in
+/// [`crate::Graph::set_vwgt`].  Vertices and edges have a weight of one when
+/// `vwgt` and `adjwgt` are `None`.
+///
+/// This is a greedy pass: vertices of overweight parts are moved to an
+/// adjacent part that stays within bounds, the moves that increase the
+/// edge-cut the least first.  It stops when no part is overweight, or when no
+/// such move remains, so the result might still be imbalanced.  Each round
+/// runs in `O(E + C log C)` time, where `C` is the number of candidate moves.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`], and leaves `part` as is, if
+/// `xadj` and `adjncy` are not a valid graph structure, if `ubvec` is empty
+/// or has values that are not finite and greater than or equal to 1.0, if the
+/// lengths of the weight arrays do not match the graph, if some vertex
+/// weights are negative or some edge weights are not strictly positive, or if
+/// `part` is not a partition of the graph into `nparts` parts.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::refine::rebalance;
+///
+/// // 0 - 1 - 2 - 3 - 4 - 5 - 6 - 7 - 8 - 9
+/// let xadj = &[0, 1, 3, 5, 7, 9, 11, 13, 15, 17, 18];
+/// let adjncy = &[1, 0, 2, 1, 3, 2, 4, 3, 5, 4, 6, 5, 7, 6, 8, 7, 9, 8];
+/// let mut part = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1];
+///
+/// let report = rebalance(xadj, adjncy, None, None, &mut part, 2, &[1.1])?;
+/// assert_eq!(report.imbalance_before, [1.6]);
+/// assert_eq!(report.imbalance_after, [1.0]);
+/// assert_eq!(report.moves, 3);
+/// assert_eq!((report.edge_cut_before, report.edge_cut_after), (1, 1));
+/// assert_eq!(part, [0, 0, 0, 0, 0, 1, 1, 1, 1, 1]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn rebalance(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    adjwgt: Option<&[Idx]>,
+    vwgt: Option<&[Idx]>,
+    part: &mut [Idx],
+    nparts: Idx,
+    ubvec: &[Real],
+) -> Result<RebalanceReport> {
+    let nvtxs = check_part(xadj, adjncy, part)?;
+    check_labels(part, nparts)?;
+    if ubvec.is_empty() {
+        return Err(NewGraphError::NoConstraints.into());
+    }
+    NewGraphError::check_ubvec(ubvec)?;
+    let ncon = Idx::try_from(ubvec.len()).map_err(|_| NewGraphError::TooLarge)?;
+    check_vwgt(ncon, vwgt, nvtxs)?;
+    if let Some(adjwgt) = adjwgt {
+        NewGraphError::check_len("adjwgt", adjncy.len(), adjwgt.len())?;
+        NewGraphError::check_positive("adjwgt", adjwgt)?;
+    }
+    let edge_cut_before = quality::edge_cut(xadj, adjncy, adjwgt, part)?;
+    let imbalance_before = quality::imbalance(ncon, vwgt, None, part, nparts)?;
+
+    let (ncon, nparts) = (ncon as usize, nparts as usize);
+    let pwgts = sum_weights(ncon, vwgt, part, nparts);
+    let max = (0..ncon)
+        .map(|j| {
+            let total: i64 = pwgts.iter().skip(j).step_by(ncon).sum();
+            f64::from(ubvec[j]) * total as f64 / nparts as f64
+        })
+        .collect();
+    let mut state = Rebalancer {
+        xadj,
+        adjncy,
+        adjwgt,
+        vwgt,
+        ncon,
+        max,
+        pwgts,
+        conn: vec![0; nparts],
+    };
+
+    let mut moves = 0;
+    loop {
+        let mut candidates: Vec<(i64, usize)> = (0..nvtxs)
+            .filter_map(|v| Some((state.best_move(part, v)?.0, v)))
+            .collect();
+        candidates.sort_by_key(|(gain, v)| (cmp::Reverse(*gain), *v));
+        let before = moves;
+        for (_, v) in candidates {
+            // Earlier moves may have changed the best move of v, or fixed the
+            // balance of its part.
+            if let Some((_, q)) = state.best_move(part, v) {
+                state.apply(part, v, q);
+                moves += 1;
+            }
+        }
+        if moves == before {
+            break;
+        }
+    }
+
+    Ok(RebalanceReport {
+        moves,
+        edge_cut_before,
+        edge_cut_after: quality::edge_cut(xadj, adjncy, adjwgt, part)?,
+        imbalance_before,
+        imbalance_after: quality::imbalance(ncon as Idx, vwgt, None, part, nparts as Idx)?,
+    })
+}
+
+/// State of [`rebalance`].
+struct Rebalancer<'a> {
+    xadj: &'a [Idx],
+    adjncy: &'a [Idx],
+    adjwgt: Option<&'a [Idx]>,
+    vwgt: Option<&'a [Idx]>,
+    ncon: usize,
+
+    /// The maximum weight of a part, for each constraint.
+    max: Vec<f64>,
+
+    /// The weight of each part and constraint, at `p*ncon+j`.
+    pwgts: Vec<i64>,
+
+    /// Scratch space: the weight of the edges from a vertex to each part.
+    conn: Vec<i64>,
+}
+
+impl Rebalancer<'_> {
+    fn vwgt(&self, v: usize, j: usize) -> i64 {
+        self.vwgt
+            .map_or(1, |vwgt| i64::from(vwgt[v * self.ncon + j]))
+    }
+
+    /// Returns the best part to move `v` to, with the change of edge-cut
+    /// (negated), if moving it would lighten an overweight constraint of its
+    /// part without making another part overweight.
+    fn best_move(&mut self, part: &[Idx], v: usize) -> Option<(i64, usize)> {
+        let p = part[v] as usize;
+        let helps = (0..self.ncon)
+            .any(|j| self.vwgt(v, j) > 0 && self.pwgts[p * self.ncon + j] as f64 > self.max[j]);
+        if !helps {
+            return None;
+        }
+
+        let mut internal = 0;
+        let mut neighbors = Vec::new();
+        for i in self.xadj[v] as usize..self.xadj[v + 1] as usize {
+            let q = part[self.adjncy[i] as usize] as usize;
+            let w = self.adjwgt.map_or(1, |adjwgt| i64::from(adjwgt[i]));
+            if q == p {
+                internal += w;
+            } else {
+                if self.conn[q] == 0 {
+                    neighbors.push(q);
+                }
+                self.conn[q] += w;
+            }
+        }
+
+        let mut best: Option<(i64, usize)> = None;
+        for q in neighbors {
+            let gain = self.conn[q] - internal;
+            self.conn[q] = 0;
+            let fits = (0..self.ncon)
+                .all(|j| (self.pwgts[q * self.ncon + j] + self.vwgt(v, j)) as f64 <= self.max[j]);
+            let better = match best {
+                Some((best_gain, best_q)) => {
+                    (gain, cmp::Reverse(q)) > (best_gain, cmp::Reverse(best_q))
+                }
+                None => true,
+            };
+            if fits && better {
+                best = Some((gain, q));
+            }
+        }
+        best
+    }
+
+    /// Moves `v` to part `q`.
+    fn apply(&mut self, part: &mut [Idx], v: usize, q: usize) {
+        let p = part[v] as usize;
+        for j in 0..self.ncon {
+            let w = self.vwgt(v, j);
+            self.pwgts[p * self.ncon + j] -= w;
+            self.pwgts[q * self.ncon + j] += w;
+        }
+        part[v] = q as Idx;
+    }
+}
+
+/// What [`make_contiguous`] did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContigReport {
+    /// The number of fragments moved to another part.
+    pub fragments_moved: usize,
+
+    /// The number of vertices moved to another part.
+    pub vertices_moved: usize,
+
+    /// The number of parts that are still not contiguous, because their
+    /// fragments have no neighbor in another part.
+    pub non_contiguous_parts: usize,
+
+    /// The edge-cut before the pass.
+    pub edge_cut_before: i64,
+
+    /// The edge-cut after the pass.
+    pub edge_cut_after: i64,
+
+    /// The load imbalance before the pass, see [`quality::imbalance`].
+    pub imbalance_before: Vec<Real>,
+
+    /// The load imbalance after the pass.
+    pub imbalance_after: Vec<Real>,
+}
+
+/// Moves the fragments of non-contiguous parts to neighboring parts, until
+/// all parts are contiguous.
+///
+/// The fragments of a part are its connected components (see
+/// [`quality::contiguity`]), except the heaviest one.  Fragments are moved,
+/// the lightest first, to the adjacent part they share the most edges with.
+/// Each move merges the fragment with a component of the other part, so the
+/// pass ends.  Fragments without a neighbor in another part, which only
+/// exist in disconnected graphs, stay where they are.
+///
+/// Vertices have a weight of one when `vwgt` is `None`, otherwise `vwgt` has
+/// one weight per vertex.  Components are recomputed after each move, so
+/// this runs in `O(F (V + E))` time, where `F` is the number of fragments.
+///
+/// # Errors
+///
+/// This function returns [`crate::Error::Input`], and leaves `part` as is, if
+/// `xadj` and `adjncy` are not a valid graph structure, if the length of
+/// `vwgt` is not the number of vertices, if some weights are negative, or if
+/// `part` is not a partition of the graph into `nparts` parts.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::option::{Contig, UFactor};
+/// use metis::quality::contiguity;
+/// use metis::refine::make_contiguous;
+/// use metis::{Error, GraphBuilder};
+///
+/// // Two 10x10 grids, the first one with a long-range edge.
+/// let mut builder = GraphBuilder::new(200)?;
+/// for grid in [0, 100] {
+///     for i in 0..10 {
+///         for j in 0..9 {
+///             builder.add_edge(grid + 10 * i + j, grid + 10 * i + j + 1)?;
+///             builder.add_edge(grid + 10 * j + i, grid + 10 * j + i + 10)?;
+///         }
+///     }
+/// }
+/// builder.add_edge(69, 12)?;
+/// let graph = builder.build(1, 6)?;
+/// let (xadj, adjncy) = (graph.xadj(), graph.adjncy());
+///
+/// // METIS refuses to compute contiguous partitions of disconnected graphs.
+/// let mut part = [0; 200];
+/// let err = graph.as_graph().set_option(Contig(true)).part_kway(&mut part);
+/// assert_eq!(err.unwrap_err(), Error::Other(None));
+///
+/// // Without Contig, k-way partitioning with a tight balance leaves a part in
+/// // two pieces.
+/// graph.as_graph().set_option(UFactor(1)).part_kway(&mut part)?;
+/// assert!(contiguity(xadj, adjncy, &part, 6)?.contains(&2));
+///
+/// // Each grid holds whole parts, so that all of them can be repaired.
+/// let report = make_contiguous(xadj, adjncy, None, &mut part, 6)?;
+/// assert_eq!(report.fragments_moved, 1);
+/// assert_eq!(report.non_contiguous_parts, 0);
+/// assert_eq!(contiguity(xadj, adjncy, &part, 6)?, [1; 6]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn make_contiguous(
+    xadj: &[Idx],
+    adjncy: &[Idx],
+    vwgt: Option<&[Idx]>,
+    part: &mut [Idx],
+    nparts: Idx,
+) -> Result<ContigReport> {
+    let nvtxs = check_part(xadj, adjncy, part)?;
+    check_labels(part, nparts)?;
+    check_vwgt(1, vwgt, nvtxs)?;
+    let edge_cut_before = quality::edge_cut(xadj, adjncy, None, part)?;
+    let imbalance_before = quality::imbalance(1, vwgt, None, part, nparts)?;
+
+    let mut fragments_moved = 0;
+    let mut vertices_moved = 0;
+    let mut conn = vec![0; nparts as usize];
+    let non_contiguous_parts = loop {
+        let (component, roots) = label_components(xadj, adjncy, part);
+        let mut weights = vec![0; roots.len()];
+        for (v, c) in component.iter().enumerate() {
+            weights[*c] += vwgt.map_or(1, |vwgt| i64::from(vwgt[v]));
+        }
+
+        // The heaviest component of each part stays, the others are
+        // fragments.
+        let mut main = vec![usize::MAX; nparts as usize];
+        for (c, root) in roots.iter().enumerate() {
+            let main = &mut main[part[*root] as usize];
+            if *main == usize::MAX || weights[*main] < weights[c] {
+                *main = c;
+            }
+        }
+        let mut fragments: Vec<usize> = (0..roots.len())
+            .filter(|c| main[part[roots[*c]] as usize] != *c)
+            .collect();
+        fragments.sort_by_key(|c| (weights[*c], *c));
+
+        let component: Vec<Idx> = component.into_iter().map(|c| c as Idx).collect();
+        let index = quality::invert_partition(&component, roots.len() as Idx)?;
+        let mut moved = false;
+        for c in &fragments {
+            let vertices = index.vertices_of(*c as Idx);
+            let p = part[vertices[0] as usize];
+            let mut neighbors = Vec::new();
+            for v in vertices {
+                let v = *v as usize;
+                for u in &adjncy[xadj[v] as usize..xadj[v + 1] as usize] {
+                    let q = part[*u as usize];
+                    if q != p {
+                        if conn[q as usize] == 0 {
+                            neighbors.push(q);
+                        }
+                        conn[q as usize] += 1;
+                    }
+                }
+            }
+            let target = neighbors
+                .iter()
+                .max_by_key(|q| (conn[**q as usize], cmp::Reverse(**q)))
+                .copied();
+            neighbors.iter().for_each(|q| conn[*q as usize] = 0);
+            if let Some(q) = target {
+                vertices.iter().for_each(|v| part[*v as usize] = q);
+                fragments_moved += 1;
+                vertices_moved += vertices.len();
+                moved = true;
+                break;
+            }
+        }
+        if !moved {
+            let mut fragmented: Vec<Idx> = fragments.iter().map(|c| part[roots[*c]]).collect();
+            fragmented.sort_unstable();
+            fragmented.dedup();
+            break fragmented.len();
+        }
+    };
+
+    Ok(ContigReport {
+        fragments_moved,
+        vertices_moved,
+        non_contiguous_parts,
+        edge_cut_before,
+        edge_cut_after: quality::edge_cut(xadj, adjncy, None, part)?,
+        imbalance_before,
+        imbalance_after: quality::imbalance(1, vwgt, None, part, nparts)?,
+    })
+}
@@ -0,0 +1,102 @@
+//! Helpers shared by the integrations of sparse matrix crates.
+
+use crate::convert;
+use crate::GraphOwned;
+use crate::Idx;
+use crate::NewGraphError;
+use crate::Result;
+use crate::StructureErrorKind;
+use std::convert::TryInto;
+use std::fmt;
+use std::result::Result as StdResult;
+
+/// Converts the sparsity pattern of a square compressed matrix into the
+/// `xadj` and `adjncy` arrays of a graph.
+///
+/// Diagonal entries are dropped, or rejected with [`NewGraphError::SelfLoop`]
+/// when `drop_diagonal` is false.  Also returns the position in `indices` of
+/// each element of `adjncy`, to pick the matching values.  `offsets` and
+/// `indices` must come from a valid matrix of the given shape.
+#[allow(clippy::type_complexity)]
+pub(crate) fn pattern<Iptr, I>(
+    shape: (usize, usize),
+    offsets: &[Iptr],
+    indices: &[I],
+    drop_diagonal: bool,
+) -> Result<(Vec<Idx>, Vec<Idx>, Vec<usize>)>
+where
+    Iptr: Copy + TryInto<Idx> + fmt::Display,
+    I: Copy + TryInto<Idx> + fmt::Display,
+{
+    check_square(shape)?;
+    let mut xadj = convert::to_idx_vec(offsets)?;
+    let mut adjncy = convert::to_idx_vec(indices)?;
+    let kept = strip_diagonal(&mut xadj, &mut adjncy, drop_diagonal)?;
+    Ok((xadj, adjncy, kept))
+}
+
+/// Makes an unweighted graph of `nparts` parts out of the sparsity pattern of
+/// a square compressed matrix, without its diagonal.
+///
+/// Indices that do not fit in [`Idx`] are reported as
+/// [`NewGraphError::TooLarge`].  Also returns the position in `indices` of
+/// each element of `adjncy`, as [`pattern`] does.
+pub(crate) fn pattern_graph<Iptr, I>(
+    shape: (usize, usize),
+    offsets: &[Iptr],
+    indices: &[I],
+    nparts: Idx,
+) -> StdResult<(GraphOwned, Vec<usize>), NewGraphError>
+where
+    Iptr: Copy + TryInto<Idx> + fmt::Display,
+    I: Copy + TryInto<Idx> + fmt::Display,
+{
+    check_square(shape)?;
+    let mut xadj = convert::to_idx_vec(offsets).map_err(|_| NewGraphError::TooLarge)?;
+    let mut adjncy = convert::to_idx_vec(indices).map_err(|_| NewGraphError::TooLarge)?;
+    let kept = strip_diagonal(&mut xadj, &mut adjncy, true)?;
+    Ok((GraphOwned::new(1, nparts, xadj, adjncy)?, kept))
+}
+
+/// Removes the diagonal entries from the arrays of a graph, or rejects them
+/// with [`NewGraphError::SelfLoop`] when `drop_diagonal` is false.
+///
+/// Returns the former position in `adjncy` of each remaining element.
+/// `xadj` must start at zero and be non-decreasing, and end at the length
+/// of `adjncy`.
+fn strip_diagonal(
+    xadj: &mut [Idx],
+    adjncy: &mut Vec<Idx>,
+    drop_diagonal: bool,
+) -> StdResult<Vec<usize>, NewGraphError> {
+    let mut kept = Vec::with_capacity(adjncy.len());
+    let mut start = 0;
+    for u in 0..xadj.len() - 1 {
+        let end = xadj[u + 1] as usize;
+        for e in start..end {
+            if adjncy[e] as usize == u {
+                if drop_diagonal {
+                    continue;
+                }
+                return Err(NewGraphError::SelfLoop { vertex: u as Idx });
+            }
+            adjncy[kept.len()] = adjncy[e];
+            kept.push(e);
+        }
+        start = end;
+        xadj[u + 1] = kept.len() as Idx;
+    }
+    adjncy.truncate(kept.len());
+    Ok(kept)
+}
+
+/// Checks that a matrix of the given shape is square.
+pub(crate) fn check_square((rows, cols): (usize, usize)) -> StdResult<(), NewGraphError> {
+    if rows != cols {
+        return Err(NewGraphError::msg(
+            StructureErrorKind::LengthMismatch,
+            "the matrix is not square",
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,523 @@
+//! Conversion of [sprs](https://docs.rs/sprs) sparse matrices.
+//!
+//! The sparsity pattern of a square matrix is a graph: the non-zero entry at
+//! row `i` and column `j` is an edge between vertices `i` and `j`, whose weight
+//! is the value of the entry.  METIS expects this pattern to be symmetric,
+//! in which case CSR and CSC matrices give the same graph.
+//!
+//! Matrices whose indices and values are [`Idx`] implement [`Partition`],
+//! re-exported here from the crate root.  Other matrices, like the usual
+//! `CsMat<f64>` with `usize` indices, go through [`PartitionConverted`].  In
+//! both cases, the arrays are copied into the returned [`GraphOwned`].
+//!
+//! Values are only used as edge weights by [`Partition::setup_partition`],
+//! [`PartitionConverted::setup_partition_weighted`] and its variants, which
+//! take a closure to turn values into weights.
+//! [`PartitionPattern::setup_partition_unweighted`] and
+//! [`PartitionConverted::setup_partition_converted`] ignore them, so that
+//! matrices with the same sparsity pattern give the same graph.
+//!
+//! Diagonal entries would be self-loops, which METIS does not accept.  They
+//! are dropped along with their values, unless
+//! [`PartitionConverted::setup_partition_diagonal`] is given
+//! [`Diagonal::Error`].
+//!
+//! Matrices also implement [`crate::PartitionExt`], which drops the diagonal
+//! as well, and sets the number of constraints, the options and the weights
+//! of the graph from a [`crate::PartitionConfig`].
+//!
+//! Views made by slicing the outer dimension of a matrix are not square, and
+//! are rejected.
+
+use crate::csr;
+use crate::csr::WeightMerge;
+use crate::sparse;
+use crate::GraphOwned;
+use crate::Idx;
+use crate::NewGraphError;
+use crate::PartitionConfig;
+use crate::PartitionExt;
+use crate::Result;
+use sprs::CsMatBase;
+use sprs::CsMatI;
+use sprs::SpIndex;
+use std::convert::TryInto;
+use std::fmt;
+use std::ops::Deref;
+use std::result::Result as StdResult;
+
+pub use crate::Partition;
+
+/// The rows of the matrix are the vertices of the graph, and its values are
+/// the edge weights.  Diagonal entries are dropped, along with their values.
+/// See [`PartitionPattern::setup_partition_unweighted`] to ignore the values.
+///
+/// # Errors
+///
+/// [`Partition::setup_partition`] returns an error if the matrix is not
+/// square, if its arrays are not a valid graph (see [`crate::Graph::new`]), or
+/// if some values of off-diagonal entries are not strictly positive.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::sprs::Partition;
+/// use metis::Idx;
+/// use sprs::CsMatI;
+///
+/// // 0 - 1 - 2 - 3, with heavy ends.
+/// let matrix = CsMatI::<Idx, Idx>::new(
+///     (4, 4),
+///     vec![0, 1, 3, 5, 6],
+///     vec![1, 0, 2, 1, 3, 2],
+///     vec![4, 4, 1, 1, 4, 4],
+/// );
+/// let mut part = [0; 4];
+/// let edgecut = matrix.setup_partition(2)?.part_recursive(&mut part)?;
+/// assert_eq!(edgecut, 1);
+/// assert_eq!(part[0], part[1]);
+///
+/// // The same matrix with a full diagonal, whose values are not weights.
+/// let matrix = CsMatI::<Idx, Idx>::new(
+///     (4, 4),
+///     vec![0, 2, 5, 8, 10],
+///     vec![0, 1, 0, 1, 2, 1, 2, 3, 2, 3],
+///     vec![0, 4, 4, 0, 1, 1, 0, 4, 4, 0],
+/// );
+/// let graph = matrix.setup_partition(2)?;
+/// assert_eq!(graph.xadj(), &[0, 1, 3, 5, 6]);
+/// assert_eq!(graph.adjncy(), &[1, 0, 2, 1, 3, 2]);
+/// assert_eq!(graph.adjwgt(), Some(&[4, 4, 1, 1, 4, 4][..]));
+///
+/// // The identity has no edges.
+/// let eye = CsMatI::<Idx, Idx>::eye(4).setup_partition(2)?;
+/// assert_eq!(eye.xadj(), &[0; 5]);
+/// # Ok(())
+/// # }
+/// ```
+impl<IptrStorage, IndStorage, DataStorage> Partition
+    for CsMatBase<Idx, Idx, IptrStorage, IndStorage, DataStorage, Idx>
+where
+    IptrStorage: Deref<Target = [Idx]>,
+    IndStorage: Deref<Target = [Idx]>,
+    DataStorage: Deref<Target = [Idx]>,
+{
+    fn setup_partition(&self, nparts: Idx) -> StdResult<GraphOwned, NewGraphError> {
+        let indptr = self.proper_indptr();
+        let (graph, kept) = sparse::pattern_graph(self.shape(), &indptr, self.indices(), nparts)?;
+        let data = self.data();
+        graph.try_set_adjwgt(kept.into_iter().map(|e| data[e]).collect())
+    }
+}
+
+/// Partitioning of the rows of sparse matrices, ignoring their values.
+pub trait PartitionPattern {
+    /// Makes a [`GraphOwned`] of `nparts` parts out of the sparsity pattern
+    /// of the matrix, without edge weights.
+    ///
+    /// The index arrays of the matrix are copied, without its diagonal
+    /// entries.  Its values can be of any type.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [`Partition::setup_partition`],
+    /// except for weights.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::sprs::PartitionPattern;
+    /// use metis::Idx;
+    /// use sprs::CsMatI;
+    ///
+    /// // Two matrices with the pattern of a 4x4 grid, and different values.
+    /// let n = 4;
+    /// let mut indptr = vec![0];
+    /// let mut indices = Vec::new();
+    /// for i in 0..n {
+    ///     for j in 0..n {
+    ///         let v = n * i + j;
+    ///         let neighbors = [
+    ///             (i > 0, v - n),
+    ///             (j > 0, v - 1),
+    ///             (j < n - 1, v + 1),
+    ///             (i < n - 1, v + n),
+    ///         ];
+    ///         indices.extend(neighbors.iter().filter(|(ok, _)| *ok).map(|(_, v)| *v as Idx));
+    ///         indptr.push(indices.len() as Idx);
+    ///     }
+    /// }
+    /// let values1: Vec<f64> = (0..indices.len()).map(|e| e as f64).collect();
+    /// let values2 = vec![-1.0; indices.len()];
+    /// let shape = (16, 16);
+    /// let matrix1 = CsMatI::new(shape, indptr.clone(), indices.clone(), values1);
+    /// let matrix2 = CsMatI::new(shape, indptr, indices, values2);
+    ///
+    /// let mut part1 = [0; 16];
+    /// let mut part2 = [0; 16];
+    /// matrix1.setup_partition_unweighted(4)?.part_kway(&mut part1)?;
+    /// matrix2.setup_partition_unweighted(4)?.part_kway(&mut part2)?;
+    /// assert_eq!(part1, part2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn setup_partition_unweighted(&self, nparts: Idx) -> StdResult<GraphOwned, NewGraphError>;
+}
+
+impl<N, IptrStorage, IndStorage, DataStorage> PartitionPattern
+    for CsMatBase<N, Idx, IptrStorage, IndStorage, DataStorage, Idx>
+where
+    IptrStorage: Deref<Target = [Idx]>,
+    IndStorage: Deref<Target = [Idx]>,
+    DataStorage: Deref<Target = [N]>,
+{
+    fn setup_partition_unweighted(&self, nparts: Idx) -> StdResult<GraphOwned, NewGraphError> {
+        let indptr = self.proper_indptr();
+        Ok(sparse::pattern_graph(self.shape(), &indptr, self.indices(), nparts)?.0)
+    }
+}
+
+/// What [`PartitionConverted`] does with the diagonal entries of a matrix.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::sprs::{Diagonal, PartitionConverted};
+/// use metis::NewGraphError;
+/// use sprs::CsMat;
+///
+/// // Tridiagonal matrix.
+/// //  2 -1  .  .
+/// // -1  2 -1  .
+/// //  . -1  2 -1
+/// //  .  . -1  2
+/// let matrix: CsMat<f64> = CsMat::new(
+///     (4, 4),
+///     vec![0, 2, 5, 8, 10],
+///     vec![0, 1, 0, 1, 2, 1, 2, 3, 2, 3],
+///     vec![2.0, -1.0, -1.0, 2.0, -1.0, -1.0, 2.0, -1.0, -1.0, 2.0],
+/// );
+///
+/// // The diagonal is dropped by default, along with its values.
+/// let graph = matrix.setup_partition_converted(2)?;
+/// assert_eq!(graph.xadj(), &[0, 1, 3, 5, 6]);
+/// assert_eq!(graph.adjncy(), &[1, 0, 2, 1, 3, 2]);
+/// let graph = matrix.setup_partition_diagonal(2, Diagonal::Drop, |v| (-v) as metis::Idx)?;
+/// assert_eq!(graph.adjwgt(), Some(&[1, 1, 1, 1, 1, 1][..]));
+///
+/// let err = matrix.setup_partition_diagonal(2, Diagonal::Error, |_| 1).unwrap_err();
+/// let source = std::error::Error::source(&err).unwrap();
+/// assert!(matches!(
+///     source.downcast_ref::<NewGraphError>(),
+///     Some(NewGraphError::SelfLoop { vertex: 0 }),
+/// ));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Diagonal {
+    /// Drop diagonal entries, along with their values.
+    #[default]
+    Drop,
+
+    /// Fail with [`NewGraphError::SelfLoop`].
+    Error,
+}
+
+/// Partitioning of the rows of sparse matrices of any index and value types.
+///
+/// Indices are converted to [`Idx`] into arrays owned by the returned graph.
+pub trait PartitionConverted<N> {
+    /// Makes a [`GraphOwned`] of `nparts` parts out of the sparsity pattern
+    /// of the matrix, without edge weights.
+    ///
+    /// Diagonal entries are dropped.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the matrix is not square, if some
+    /// indices do not fit in [`Idx`] (see [`crate::convert::ConvertError`]),
+    /// or if its arrays are not a valid graph (see [`crate::Graph::new`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::sprs::PartitionConverted;
+    /// use sprs::CsMat;
+    ///
+    /// // 0 - 1 - 2 - 3
+    /// let matrix: CsMat<f64> = CsMat::new(
+    ///     (4, 4),
+    ///     vec![0, 1, 3, 5, 6],
+    ///     vec![1, 0, 2, 1, 3, 2],
+    ///     vec![-1.0, -1.0, -0.1, -0.1, -1.0, -1.0],
+    /// );
+    /// let graph = matrix.setup_partition_converted(2)?;
+    /// assert_eq!(graph.xadj(), &[0, 1, 3, 5, 6]);
+    /// assert_eq!(graph.adjwgt(), None);
+    ///
+    /// // Values are mapped to edge weights by a closure.
+    /// let graph = matrix.setup_partition_weighted(2, |v| (10.0 * v.abs()) as metis::Idx)?;
+    /// assert_eq!(graph.adjwgt(), Some(&[10, 10, 1, 1, 10, 10][..]));
+    /// let mut part = [0; 4];
+    /// assert_eq!(graph.part_recursive(&mut part)?, 1);
+    ///
+    /// // Weights must be strictly positive.
+    /// assert!(matrix.setup_partition_weighted(2, |_| 0).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn setup_partition_converted(&self, nparts: Idx) -> Result<GraphOwned>;
+
+    /// Same as [`PartitionConverted::setup_partition_converted`], with the
+    /// edge weights given by applying `weight` to the values of the matrix.
+    ///
+    /// Diagonal entries are dropped, and `weight` is not called on their
+    /// values.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as
+    /// [`PartitionConverted::setup_partition_converted`], and also fails if
+    /// some weights are not strictly positive.
+    fn setup_partition_weighted<F>(&self, nparts: Idx, weight: F) -> Result<GraphOwned>
+    where
+        F: FnMut(&N) -> Idx;
+
+    /// Same as [`PartitionConverted::setup_partition_weighted`], with the
+    /// given handling of diagonal entries.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as
+    /// [`PartitionConverted::setup_partition_weighted`], and also fails with
+    /// [`NewGraphError::SelfLoop`] if the matrix has diagonal entries and
+    /// `diagonal` is [`Diagonal::Error`].
+    fn setup_partition_diagonal<F>(
+        &self,
+        nparts: Idx,
+        diagonal: Diagonal,
+        weight: F,
+    ) -> Result<GraphOwned>
+    where
+        F: FnMut(&N) -> Idx;
+
+    /// Same as [`PartitionConverted::setup_partition_weighted`], for matrices
+    /// whose sparsity pattern is not symmetric.
+    ///
+    /// The graph is the pattern of `A + Aᵀ` without its diagonal: entries
+    /// `(i, j)` and `(j, i)` become one edge, whose weight combines the weights
+    /// of both entries according to `merge`.  A lone entry keeps its weight.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as
+    /// [`PartitionConverted::setup_partition_weighted`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// use metis::csr::WeightMerge;
+    /// use metis::sprs::PartitionConverted;
+    /// use sprs::TriMat;
+    ///
+    /// // Lower triangular matrix, with its diagonal.
+    /// // 1 . . .
+    /// // 2 1 . .
+    /// // . 3 1 .
+    /// // 4 . 5 1
+    /// let entries = [(0, 0), (1, 0), (1, 1), (2, 1), (2, 2), (3, 0), (3, 2), (3, 3)];
+    /// let values = [1, 2, 1, 3, 1, 4, 5, 1];
+    /// let mut triplets = TriMat::new((4, 4));
+    /// for ((i, j), v) in entries.into_iter().zip(values) {
+    ///     triplets.add_triplet(i, j, v);
+    /// }
+    /// let lower = triplets.to_csr::<usize>();
+    ///
+    /// let graph = lower.setup_partition_sym(2, WeightMerge::Sum, |v| *v)?;
+    /// assert_eq!(graph.xadj(), &[0, 2, 4, 6, 8]);
+    /// assert_eq!(graph.adjncy(), &[1, 3, 0, 2, 1, 3, 0, 2]);
+    /// assert_eq!(graph.adjwgt(), Some(&[2, 4, 2, 3, 3, 5, 4, 5][..]));
+    ///
+    /// // The full symmetric matrix gives the same graph with WeightMerge::Max.
+    /// let full = &lower + &lower.transpose_view().to_csr();
+    /// let max = full.setup_partition_sym(2, WeightMerge::Max, |v| *v)?;
+    /// let sum = full.setup_partition_sym(2, WeightMerge::Sum, |v| *v)?;
+    /// assert_eq!(max.adjwgt(), graph.adjwgt());
+    /// assert_eq!(sum.adjwgt(), Some(&[4, 8, 4, 6, 6, 10, 8, 10][..]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn setup_partition_sym<F>(
+        &self,
+        nparts: Idx,
+        merge: WeightMerge,
+        weight: F,
+    ) -> Result<GraphOwned>
+    where
+        F: FnMut(&N) -> Idx;
+}
+
+impl<N, I, Iptr, IptrStorage, IndStorage, DataStorage> PartitionConverted<N>
+    for CsMatBase<N, I, IptrStorage, IndStorage, DataStorage, Iptr>
+where
+    I: SpIndex + TryInto<Idx> + fmt::Display,
+    Iptr: SpIndex + TryInto<Idx> + fmt::Display,
+    IptrStorage: Deref<Target = [Iptr]>,
+    IndStorage: Deref<Target = [I]>,
+    DataStorage: Deref<Target = [N]>,
+{
+    fn setup_partition_converted(&self, nparts: Idx) -> Result<GraphOwned> {
+        let (xadj, adjncy, _) =
+            sparse::pattern(self.shape(), &self.proper_indptr(), self.indices(), true)?;
+        Ok(GraphOwned::new(1, nparts, xadj, adjncy)?)
+    }
+
+    fn setup_partition_weighted<F>(&self, nparts: Idx, weight: F) -> Result<GraphOwned>
+    where
+        F: FnMut(&N) -> Idx,
+    {
+        self.setup_partition_diagonal(nparts, Diagonal::Drop, weight)
+    }
+
+    fn setup_partition_diagonal<F>(
+        &self,
+        nparts: Idx,
+        diagonal: Diagonal,
+        mut weight: F,
+    ) -> Result<GraphOwned>
+    where
+        F: FnMut(&N) -> Idx,
+    {
+        let drop_diagonal = diagonal == Diagonal::Drop;
+        let (xadj, adjncy, kept) = sparse::pattern(
+            self.shape(),
+            &self.proper_indptr(),
+            self.indices(),
+            drop_diagonal,
+        )?;
+        let data = self.data();
+        let adjwgt = kept.into_iter().map(|e| weight(&data[e])).collect();
+        let graph = GraphOwned::new(1, nparts, xadj, adjncy)?;
+        Ok(graph.try_set_adjwgt(adjwgt)?)
+    }
+
+    fn setup_partition_sym<F>(
+        &self,
+        nparts: Idx,
+        merge: WeightMerge,
+        mut weight: F,
+    ) -> Result<GraphOwned>
+    where
+        F: FnMut(&N) -> Idx,
+    {
+        let (xadj, adjncy, kept) =
+            sparse::pattern(self.shape(), &self.proper_indptr(), self.indices(), true)?;
+        let data = self.data();
+        let adjwgt: Vec<Idx> = kept.into_iter().map(|e| weight(&data[e])).collect();
+        let (xadj, adjncy, adjwgt) = csr::symmetrize(&xadj, &adjncy, Some(&adjwgt), merge)?;
+        let graph = GraphOwned::new(1, nparts, xadj, adjncy)?;
+        Ok(graph.try_set_adjwgt(adjwgt.unwrap_or_default())?)
+    }
+}
+
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), metis::Error> {
+/// use metis::option::Opt;
+/// use metis::option::Seed;
+/// use metis::PartitionConfig;
+/// use metis::PartitionExt;
+/// use sprs::CsMat;
+///
+/// // Tridiagonal matrix: the graph is a path.
+/// let n = 6;
+/// let mut tri = sprs::TriMat::new((n, n));
+/// for i in 0..n {
+///     tri.add_triplet(i, i, 2.0);
+///     if i + 1 < n {
+///         tri.add_triplet(i, i + 1, -1.5);
+///         tri.add_triplet(i + 1, i, -1.5);
+///     }
+/// }
+/// let matrix: CsMat<f64> = tri.to_csr();
+///
+/// let config = PartitionConfig::new(2)
+///     .set_option(Seed(7))
+///     .set_adjwgt(|v: &f64| (-2.0 * v) as metis::Idx);
+/// let graph = matrix.setup_partition_with(&config)?;
+/// assert_eq!(graph.adjwgt(), Some(&[3; 10][..]));
+/// assert_eq!(graph.as_graph().into_inner().options[Seed::INDEX], 7);
+///
+/// let mut part = [0; 6];
+/// assert_eq!(graph.part_recursive(&mut part)?, 3);
+/// # Ok(())
+/// # }
+/// ```
+impl<N, I, Iptr, IptrStorage, IndStorage, DataStorage> PartitionExt<N>
+    for CsMatBase<N, I, IptrStorage, IndStorage, DataStorage, Iptr>
+where
+    I: SpIndex + TryInto<Idx> + fmt::Display,
+    Iptr: SpIndex + TryInto<Idx> + fmt::Display,
+    IptrStorage: Deref<Target = [Iptr]>,
+    IndStorage: Deref<Target = [I]>,
+    DataStorage: Deref<Target = [N]>,
+{
+    fn setup_partition_with(&self, config: &PartitionConfig<'_, N>) -> Result<GraphOwned> {
+        let (xadj, adjncy, kept) =
+            sparse::pattern(self.shape(), &self.proper_indptr(), self.indices(), true)?;
+        let data = self.data();
+        let adjwgt = config
+            .adjwgt()
+            .map(|weight| kept.into_iter().map(|e| weight(&data[e])).collect());
+        Ok(config.build(xadj, adjncy, adjwgt)?)
+    }
+}
+
+impl crate::Dual {
+    /// Copies the dual graph into a pattern matrix, whose entries are all one.
+    ///
+    /// Requires the `sprs` feature.
+    ///
+    /// The adjacency lists of METIS are not always sorted, as sprs requires,
+    /// so the arrays are copied and sorted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), metis::Error> {
+    /// // A 3x3 grid of quadrangles.
+    /// let eptr: Vec<metis::Idx> = (0..=9).map(|e| 4 * e).collect();
+    /// let eind: Vec<metis::Idx> = (0..9)
+    ///     .flat_map(|e| {
+    ///         let n = e / 3 * 4 + e % 3;
+    ///         [n, n + 1, n + 5, n + 4]
+    ///     })
+    ///     .collect();
+    ///
+    /// let dual = metis::mesh_to_dual(&eptr, &eind, 2)?;
+    /// let matrix = dual.to_csmat();
+    /// assert_eq!(matrix.shape(), (9, 9));
+    /// assert_eq!(matrix.nnz(), dual.adjncy().len());
+    /// assert!(sprs::is_symmetric(&matrix));
+    /// assert!(matrix.data().iter().all(|v| *v == 1));
+    ///
+    /// // The center element shares a side with four elements.
+    /// assert_eq!(matrix.outer_view(4).unwrap().indices(), &[1, 3, 5, 7]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_csmat(&self) -> CsMatI<Idx, Idx> {
+        let n = self.xadj.len() - 1;
+        let ones = vec![1; self.adjncy.len()];
+        CsMatI::new_from_unsorted((n, n), self.xadj.to_vec(), self.adjncy.to_vec(), ones)
+            .map_err(|(_, _, _, err)| err)
+            .expect("METIS returned an invalid dual graph")
+    }
+}